@@ -21,6 +21,9 @@
 //
 //******************************************************************************************************
 
+use crate::transport::{SecurityMode, TlsConfig};
+use crate::OverflowPolicy;
+
 /// Defines STTP connection related configuration parameters.
 #[derive(Clone)]
 pub struct Config {
@@ -72,6 +75,22 @@ pub struct Config {
 
     /// Defines the target STTP protocol version. This currently defaults to 2.
     pub version: u8,
+
+    /// Determines whether the command channel is secured with TLS. Defaults to off.
+    pub security_mode: SecurityMode,
+
+    /// Defines the TLS settings, e.g., trusted certificate authorities and the client
+    /// certificate for mutual TLS, applied when `security_mode` is `SecurityMode::TLS`.
+    pub tls_config: TlsConfig,
+
+    /// Defines the maximum number of received measurement batches that are queued for
+    /// reading with `Subscriber::read_measurements`. Set value to 0 to disable queuing
+    /// when measurements are only handled by callback.
+    pub measurement_queue_capacity: usize,
+
+    /// Defines the behavior when the measurement queue is full. Defaults to dropping
+    /// the oldest queued batch.
+    pub measurement_queue_overflow: OverflowPolicy,
 }
 
 /// Define the default values for an STTP connection Config.
@@ -89,6 +108,10 @@ impl Default for Config {
             compress_signal_index_cache: true,
             metadata_filters: "".to_string(),
             version: 2,
+            security_mode: SecurityMode::Off,
+            tls_config: TlsConfig::default(),
+            measurement_queue_capacity: 0,
+            measurement_queue_overflow: OverflowPolicy::DropOldest,
         }
     }
 }
@@ -20,3 +20,30 @@
 //       Generated original version of source code.
 //
 //******************************************************************************************************
+
+// Expose local submodules as public passthroughs of sttp::data module
+// for better organization and ease of use of public API
+
+mod data_type;
+pub use crate::data::data_type::DataType; // >> sttp::data::DataType
+
+mod data_value;
+pub use crate::data::data_value::DataValue; // >> sttp::data::DataValue
+
+mod data_column;
+pub use crate::data::data_column::DataColumn; // >> sttp::data::DataColumn
+
+mod data_row;
+pub use crate::data::data_row::DataRow; // >> sttp::data::DataRow
+
+mod data_table;
+pub use crate::data::data_table::DataTable; // >> sttp::data::DataTable
+
+mod data_set;
+pub use crate::data::data_set::DataSet; // >> sttp::data::DataSet
+
+mod expression;
+mod expression_tree;
+
+mod filter_expression_parser;
+pub use crate::data::filter_expression_parser::*;
@@ -0,0 +1,75 @@
+//******************************************************************************************************
+//  data_column.rs - Gbtc
+//
+//  Copyright © 2023, Grid Protection Alliance.  All Rights Reserved.
+//
+//  Licensed to the Grid Protection Alliance (GPA) under one or more contributor license agreements. See
+//  the NOTICE file distributed with this work for additional information regarding copyright ownership.
+//  The GPA licenses this file to you under the MIT License (MIT), the "License"; you may not use this
+//  file except in compliance with the License. You may obtain a copy of the License at:
+//
+//      http://opensource.org/licenses/MIT
+//
+//  Unless agreed to in writing, the subject software distributed under the License is distributed on an
+//  "AS-IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied. Refer to the
+//  License for the specific language governing permissions and limitations.
+//
+//  Code Modification History:
+//  ----------------------------------------------------------------------------------------------------
+//  04/01/2023 - J. Ritchie Carroll
+//       Generated original version of source code.
+//
+//******************************************************************************************************
+
+use crate::data::DataType;
+
+/// Represents a column, i.e., a field, in a `DataTable` defining a name and a data type
+/// for data values in a `DataRow`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DataColumn {
+    name: String,
+    data_type: DataType,
+    expression: String,
+    index: usize,
+}
+
+impl DataColumn {
+    /// Creates a new `DataColumn`. Provide an empty `expression` for non-computed columns.
+    pub fn new(name: &str, data_type: DataType, expression: &str) -> DataColumn {
+        DataColumn {
+            name: name.to_string(),
+            data_type,
+            expression: expression.to_string(),
+            index: 0,
+        }
+    }
+
+    /// Gets the column name of the `DataColumn`.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Gets the column `DataType` enumeration value of the `DataColumn`.
+    pub fn data_type(&self) -> DataType {
+        self.data_type
+    }
+
+    /// Gets the column expression value of the `DataColumn`, if any.
+    pub fn expression(&self) -> &str {
+        &self.expression
+    }
+
+    /// Determines if the `DataColumn` is a computed value, i.e., has a defined expression.
+    pub fn computed(&self) -> bool {
+        !self.expression.is_empty()
+    }
+
+    /// Gets the index of the `DataColumn` within its parent `DataTable` columns collection.
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    pub(crate) fn set_index(&mut self, index: usize) {
+        self.index = index;
+    }
+}
@@ -0,0 +1,119 @@
+//******************************************************************************************************
+//  data_row.rs - Gbtc
+//
+//  Copyright © 2023, Grid Protection Alliance.  All Rights Reserved.
+//
+//  Licensed to the Grid Protection Alliance (GPA) under one or more contributor license agreements. See
+//  the NOTICE file distributed with this work for additional information regarding copyright ownership.
+//  The GPA licenses this file to you under the MIT License (MIT), the "License"; you may not use this
+//  file except in compliance with the License. You may obtain a copy of the License at:
+//
+//      http://opensource.org/licenses/MIT
+//
+//  Unless agreed to in writing, the subject software distributed under the License is distributed on an
+//  "AS-IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied. Refer to the
+//  License for the specific language governing permissions and limitations.
+//
+//  Code Modification History:
+//  ----------------------------------------------------------------------------------------------------
+//  04/01/2023 - J. Ritchie Carroll
+//       Generated original version of source code.
+//
+//******************************************************************************************************
+
+use crate::data::{DataColumn, DataValue};
+use std::error::Error;
+use std::sync::Arc;
+
+/// Represents a row, i.e., a record, in a `DataTable` defining a set of values for each
+/// defined `DataColumn` field in the `DataTable` columns collection.
+///
+/// A `DataRow` is created for a specific table with `DataTable::create_row` and shares the
+/// column definitions of its parent table so that values can be accessed by column name.
+#[derive(Debug, Clone)]
+pub struct DataRow {
+    columns: Arc<Vec<DataColumn>>,
+    values: Vec<Option<DataValue>>,
+}
+
+impl DataRow {
+    pub(crate) fn new(columns: Arc<Vec<DataColumn>>) -> DataRow {
+        let values = vec![None; columns.len()];
+        DataRow { columns, values }
+    }
+
+    pub(crate) fn columns(&self) -> &Arc<Vec<DataColumn>> {
+        &self.columns
+    }
+
+    /// Gets the index of the column with the specified name, case-insensitive.
+    pub fn column_index(&self, column_name: &str) -> Option<usize> {
+        self.columns
+            .iter()
+            .position(|column| column.name().eq_ignore_ascii_case(column_name))
+    }
+
+    /// Gets the value of the `DataRow` at the specified column index. Return value is `None`
+    /// when the value is null or the column index is out of range.
+    pub fn value(&self, column_index: usize) -> Option<&DataValue> {
+        self.values.get(column_index).and_then(Option::as_ref)
+    }
+
+    /// Gets the value of the `DataRow` for the specified column name, case-insensitive. Return
+    /// value is `None` when the value is null or the column does not exist.
+    pub fn value_by_name(&self, column_name: &str) -> Option<&DataValue> {
+        self.value(self.column_index(column_name)?)
+    }
+
+    /// Sets the value of the `DataRow` at the specified column index. Provide `None` to set a
+    /// null value. Value type must match the `DataType` of the target column.
+    pub fn set_value(
+        &mut self,
+        column_index: usize,
+        value: Option<DataValue>,
+    ) -> Result<(), Box<dyn Error>> {
+        let column = self.columns.get(column_index).ok_or_else(|| {
+            format!(
+                "column index {} is out of range for row with {} columns",
+                column_index,
+                self.columns.len()
+            )
+        })?;
+
+        if column.computed() {
+            return Err(format!(
+                "cannot assign value to column \"{}\": column is computed with an expression",
+                column.name()
+            )
+            .into());
+        }
+
+        if let Some(value) = &value {
+            if value.data_type() != column.data_type() {
+                return Err(format!(
+                    "cannot assign {} value to column \"{}\": column data type is {}",
+                    value.data_type(),
+                    column.name(),
+                    column.data_type()
+                )
+                .into());
+            }
+        }
+
+        self.values[column_index] = value;
+        Ok(())
+    }
+
+    /// Sets the value of the `DataRow` for the specified column name, case-insensitive.
+    pub fn set_value_by_name(
+        &mut self,
+        column_name: &str,
+        value: Option<DataValue>,
+    ) -> Result<(), Box<dyn Error>> {
+        let column_index = self
+            .column_index(column_name)
+            .ok_or_else(|| format!("column \"{}\" does not exist", column_name))?;
+
+        self.set_value(column_index, value)
+    }
+}
@@ -0,0 +1,446 @@
+//******************************************************************************************************
+//  data_set.rs - Gbtc
+//
+//  Copyright © 2023, Grid Protection Alliance.  All Rights Reserved.
+//
+//  Licensed to the Grid Protection Alliance (GPA) under one or more contributor license agreements. See
+//  the NOTICE file distributed with this work for additional information regarding copyright ownership.
+//  The GPA licenses this file to you under the MIT License (MIT), the "License"; you may not use this
+//  file except in compliance with the License. You may obtain a copy of the License at:
+//
+//      http://opensource.org/licenses/MIT
+//
+//  Unless agreed to in writing, the subject software distributed under the License is distributed on an
+//  "AS-IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied. Refer to the
+//  License for the specific language governing permissions and limitations.
+//
+//  Code Modification History:
+//  ----------------------------------------------------------------------------------------------------
+//  04/01/2023 - J. Ritchie Carroll
+//       Generated original version of source code.
+//
+//******************************************************************************************************
+
+use crate::data::{DataColumn, DataTable, DataType, DataValue};
+use roxmltree::{Document, Node};
+use std::error::Error;
+use std::str;
+
+// XML namespace for XML schema definitions
+const XML_SCHEMA_NAMESPACE: &str = "http://www.w3.org/2001/XMLSchema";
+
+// Extended XML namespace used by .NET DataSet schema definitions
+const EXT_XML_SCHEMA_DATA_NAMESPACE: &str = "urn:schemas-microsoft-com:xml-msdata";
+
+const DEFAULT_NAME: &str = "DataSet";
+
+/// Represents an in-memory cache of records that is made up of typed columns, i.e., a collection
+/// of `DataTable` objects, typically used for STTP metadata received from a `DataPublisher`.
+#[derive(Debug, Clone)]
+pub struct DataSet {
+    /// Defines the name of the `DataSet`.
+    pub name: String,
+    tables: Vec<DataTable>,
+}
+
+impl DataSet {
+    /// Creates a new, empty `DataSet`.
+    pub fn new() -> DataSet {
+        DataSet {
+            name: DEFAULT_NAME.to_string(),
+            tables: Vec::new(),
+        }
+    }
+
+    /// Creates a new `DataSet` from the specified XML, including its inline XSD schema, as
+    /// serialized by an STTP `DataPublisher`.
+    pub fn from_xml(buffer: &[u8]) -> Result<DataSet, Box<dyn Error>> {
+        let mut data_set = DataSet::new();
+        data_set.parse_xml(buffer)?;
+        Ok(data_set)
+    }
+
+    /// Gets the tables defined in the `DataSet`.
+    pub fn tables(&self) -> &[DataTable] {
+        &self.tables
+    }
+
+    /// Gets the `DataTable` for the specified table name, case-insensitive, if it exists.
+    pub fn table(&self, table_name: &str) -> Option<&DataTable> {
+        self.tables
+            .iter()
+            .find(|table| table.name().eq_ignore_ascii_case(table_name))
+    }
+
+    /// Gets a mutable reference to the `DataTable` for the specified table name,
+    /// case-insensitive, if it exists.
+    pub fn table_mut(&mut self, table_name: &str) -> Option<&mut DataTable> {
+        self.tables
+            .iter_mut()
+            .find(|table| table.name().eq_ignore_ascii_case(table_name))
+    }
+
+    /// Gets the names of the tables defined in the `DataSet`.
+    pub fn table_names(&self) -> Vec<&str> {
+        self.tables.iter().map(DataTable::name).collect()
+    }
+
+    /// Gets the number of tables defined in the `DataSet`.
+    pub fn table_count(&self) -> usize {
+        self.tables.len()
+    }
+
+    /// Adds the specified table to the `DataSet`, replacing any existing table with the same name.
+    pub fn add_table(&mut self, table: DataTable) {
+        match self.table_mut(table.name()) {
+            Some(existing) => *existing = table,
+            None => self.tables.push(table),
+        }
+    }
+
+    /// Removes the table with the specified name, case-insensitive, from the `DataSet`.
+    /// Returns `true` if the table existed.
+    pub fn remove_table(&mut self, table_name: &str) -> bool {
+        let count = self.tables.len();
+
+        self.tables
+            .retain(|table| !table.name().eq_ignore_ascii_case(table_name));
+
+        self.tables.len() != count
+    }
+
+    /// Removes all tables from the `DataSet`.
+    pub fn clear(&mut self) {
+        self.tables.clear();
+    }
+
+    /// Loads the `DataSet` from the specified XML, including its inline XSD schema, as serialized
+    /// by an STTP `DataPublisher`. Tables defined in the schema are added to the `DataSet` and
+    /// then loaded with typed records. Columns with unsupported data types are skipped and values
+    /// that cannot be parsed are left null.
+    pub fn parse_xml(&mut self, buffer: &[u8]) -> Result<(), Box<dyn Error>> {
+        // Remove any UTF-8 byte order mark
+        let buffer = buffer.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(buffer);
+        let document = Document::parse(str::from_utf8(buffer)?)?;
+        let root = document.root_element();
+
+        let schema = child_elements(root, XML_SCHEMA_NAMESPACE, "schema")
+            .next()
+            .ok_or("failed to parse DataSet XML: cannot find XSD schema")?;
+
+        self.name = root.tag_name().name().to_string();
+
+        self.parse_xml_schema(schema)?;
+        self.load_records(root)
+    }
+
+    fn parse_xml_schema(&mut self, schema: Node) -> Result<(), Box<dyn Error>> {
+        // Schema defines the data set as an element whose choices are the table definitions
+        let data_set_element = child_elements(schema, XML_SCHEMA_NAMESPACE, "element")
+            .find(|element| {
+                element.attribute((EXT_XML_SCHEMA_DATA_NAMESPACE, "IsDataSet")) == Some("true")
+                    || element.attribute("name") == Some(self.name.as_str())
+            })
+            .ok_or("failed to parse DataSet XML: cannot find schema for DataSet element")?;
+
+        let table_elements = child_elements(data_set_element, XML_SCHEMA_NAMESPACE, "complexType")
+            .flat_map(|node| child_elements(node, XML_SCHEMA_NAMESPACE, "choice"))
+            .flat_map(|node| child_elements(node, XML_SCHEMA_NAMESPACE, "element"));
+
+        for table_element in table_elements {
+            let table_name = match table_element.attribute("name") {
+                Some(name) if !name.is_empty() => name,
+                _ => continue,
+            };
+
+            let mut table = DataTable::new(table_name);
+
+            let column_elements =
+                child_elements(table_element, XML_SCHEMA_NAMESPACE, "complexType")
+                    .flat_map(|node| child_elements(node, XML_SCHEMA_NAMESPACE, "sequence"))
+                    .flat_map(|node| child_elements(node, XML_SCHEMA_NAMESPACE, "element"));
+
+            for column_element in column_elements {
+                let column_name = match column_element.attribute("name") {
+                    Some(name) if !name.is_empty() => name,
+                    _ => continue,
+                };
+
+                let xsd_type = column_element.attribute("type").unwrap_or_default();
+                let ext_data_type = ext_attribute(column_element, "DataType");
+
+                // Columns of unsupported types, e.g., binary data, are not loaded
+                let data_type = match DataType::from_xsd_type(xsd_type, ext_data_type) {
+                    Some(data_type) => data_type,
+                    None => continue,
+                };
+
+                let expression = ext_attribute(column_element, "Expression");
+
+                table.add_column(DataColumn::new(column_name, data_type, expression))?;
+            }
+
+            self.add_table(table);
+        }
+
+        Ok(())
+    }
+
+    fn load_records(&mut self, root: Node) -> Result<(), Box<dyn Error>> {
+        for record in root.children().filter(Node::is_element) {
+            let table = match self.table_mut(record.tag_name().name()) {
+                Some(table) => table,
+                None => continue,
+            };
+
+            let mut row = table.create_row();
+
+            for field in record.children().filter(Node::is_element) {
+                let column = match table.column_by_name(field.tag_name().name()) {
+                    Some(column) if !column.computed() => column,
+                    _ => continue,
+                };
+
+                let text = field.text().unwrap_or_default();
+
+                // Empty elements only define a value for string columns
+                if text.trim().is_empty() && column.data_type() != DataType::String {
+                    continue;
+                }
+
+                // Invalid values are left null so the remainder of the record still loads
+                if let Ok(value) = DataValue::parse(column.data_type(), text) {
+                    row.set_value(column.index(), Some(value))?;
+                }
+            }
+
+            table.add_row(row)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Default for DataSet {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// Gets child elements of node with the specified namespace and local name
+fn child_elements<'a, 'input: 'a>(
+    node: Node<'a, 'input>,
+    namespace: &'a str,
+    name: &'a str,
+) -> impl Iterator<Item = Node<'a, 'input>> + 'a {
+    node.children().filter(move |child| {
+        child.is_element()
+            && child.tag_name().namespace() == Some(namespace)
+            && child.tag_name().name() == name
+    })
+}
+
+// Gets extended schema attribute value, e.g., "msdata:DataType", ignoring its namespace
+// since extended attributes may be defined under alternate namespaces
+fn ext_attribute<'a>(node: Node<'a, '_>, name: &str) -> &'a str {
+    node.attributes()
+        .find(|attribute| attribute.name() == name && attribute.namespace().is_some())
+        .map(|attribute| attribute.value())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal::Decimal;
+    use std::str::FromStr;
+    use uuid::Uuid;
+
+    const METADATA_XML: &str = r#"<?xml version="1.0" standalone="yes"?>
+<DataSet>
+  <xs:schema id="DataSet" xmlns="" xmlns:xs="http://www.w3.org/2001/XMLSchema" xmlns:msdata="urn:schemas-microsoft-com:xml-msdata">
+    <xs:element name="DataSet" msdata:IsDataSet="true" msdata:UseCurrentLocale="true">
+      <xs:complexType>
+        <xs:choice minOccurs="0" maxOccurs="unbounded">
+          <xs:element name="DeviceDetail">
+            <xs:complexType>
+              <xs:sequence>
+                <xs:element name="Acronym" type="xs:string" minOccurs="0" />
+                <xs:element name="UniqueID" msdata:DataType="System.Guid, mscorlib, Version=4.0.0.0, Culture=neutral, PublicKeyToken=b77a5c561934e089" type="xs:string" minOccurs="0" />
+                <xs:element name="FramesPerSecond" type="xs:int" minOccurs="0" />
+                <xs:element name="Longitude" type="xs:decimal" minOccurs="0" />
+                <xs:element name="Enabled" type="xs:boolean" minOccurs="0" />
+                <xs:element name="UpdatedOn" type="xs:dateTime" minOccurs="0" />
+                <xs:element name="Label" msdata:ReadOnly="true" msdata:Expression="Acronym + '!'" type="xs:string" minOccurs="0" />
+              </xs:sequence>
+            </xs:complexType>
+          </xs:element>
+          <xs:element name="MeasurementDetail">
+            <xs:complexType>
+              <xs:sequence>
+                <xs:element name="PointTag" type="xs:string" minOccurs="0" />
+                <xs:element name="PointID" type="xs:unsignedLong" minOccurs="0" />
+                <xs:element name="Adder" type="xs:double" minOccurs="0" />
+              </xs:sequence>
+            </xs:complexType>
+          </xs:element>
+        </xs:choice>
+      </xs:complexType>
+    </xs:element>
+  </xs:schema>
+  <DeviceDetail>
+    <Acronym>SHELBY</Acronym>
+    <UniqueID>6f8e8b61-7c31-4fd5-8f5e-6b0c1f4a0e2d</UniqueID>
+    <FramesPerSecond>30</FramesPerSecond>
+    <Longitude>-89.8772</Longitude>
+    <Enabled>true</Enabled>
+    <UpdatedOn>2023-03-25T13:30:00-04:00</UpdatedOn>
+  </DeviceDetail>
+  <DeviceDetail>
+    <Acronym>GPLAINS</Acronym>
+  </DeviceDetail>
+  <MeasurementDetail>
+    <PointTag>SHELBY:FREQ</PointTag>
+    <PointID>2</PointID>
+    <Adder>INF</Adder>
+  </MeasurementDetail>
+</DataSet>"#;
+
+    #[test]
+    fn test_parse_xml_loads_typed_tables() {
+        let data_set = DataSet::from_xml(METADATA_XML.as_bytes()).unwrap();
+
+        assert_eq!(data_set.name, "DataSet");
+        assert_eq!(
+            data_set.table_names(),
+            vec!["DeviceDetail", "MeasurementDetail"]
+        );
+
+        let devices = data_set.table("devicedetail").unwrap();
+
+        assert_eq!(devices.column_count(), 7);
+        assert_eq!(devices.row_count(), 2);
+        assert_eq!(
+            devices.column_by_name("UniqueID").unwrap().data_type(),
+            DataType::Guid
+        );
+        assert!(devices.column_by_name("Label").unwrap().computed());
+
+        let device = devices.row(0).unwrap();
+
+        assert_eq!(
+            device.value_by_name("acronym").and_then(DataValue::as_str),
+            Some("SHELBY")
+        );
+        assert_eq!(
+            device
+                .value_by_name("UniqueID")
+                .and_then(DataValue::as_guid),
+            Some(Uuid::parse_str("6f8e8b61-7c31-4fd5-8f5e-6b0c1f4a0e2d").unwrap())
+        );
+        assert_eq!(
+            device
+                .value_by_name("FramesPerSecond")
+                .and_then(DataValue::as_i32),
+            Some(30)
+        );
+        assert_eq!(
+            device
+                .value_by_name("Longitude")
+                .and_then(DataValue::as_decimal),
+            Some(Decimal::from_str("-89.8772").unwrap())
+        );
+        assert_eq!(
+            device.value_by_name("Enabled").and_then(DataValue::as_bool),
+            Some(true)
+        );
+        assert_eq!(
+            device.value_by_name("UpdatedOn").unwrap().to_string(),
+            "2023-03-25 17:30:00"
+        );
+        assert!(device.value_by_name("Label").is_none());
+
+        // Fields missing from a record are null
+        assert!(devices.row(1).unwrap().value_by_name("UniqueID").is_none());
+
+        let measurement = data_set.table("MeasurementDetail").unwrap().row(0).unwrap();
+
+        assert_eq!(
+            measurement
+                .value_by_name("PointID")
+                .and_then(DataValue::as_u64),
+            Some(2)
+        );
+        assert_eq!(
+            measurement
+                .value_by_name("Adder")
+                .and_then(DataValue::as_f64),
+            Some(f64::INFINITY)
+        );
+    }
+
+    #[test]
+    fn test_parse_xml_skips_unsupported_columns_and_invalid_values() {
+        let xml = METADATA_XML
+            .replace(
+                r#"<xs:element name="Adder" type="xs:double" minOccurs="0" />"#,
+                r#"<xs:element name="Adder" type="xs:double" minOccurs="0" />
+                <xs:element name="Signature" type="xs:base64Binary" minOccurs="0" />"#,
+            )
+            .replace(
+                "<Adder>INF</Adder>",
+                "<Adder>INF</Adder>\n    <Signature>AAEC</Signature>",
+            )
+            .replace(
+                "<FramesPerSecond>30</FramesPerSecond>",
+                "<FramesPerSecond>thirty</FramesPerSecond>",
+            );
+
+        let data_set = DataSet::from_xml(xml.as_bytes()).unwrap();
+        let measurements = data_set.table("MeasurementDetail").unwrap();
+
+        assert_eq!(measurements.column_count(), 3);
+        assert!(measurements.column_by_name("Signature").is_none());
+        assert_eq!(
+            measurements
+                .row(0)
+                .unwrap()
+                .value_by_name("PointID")
+                .and_then(DataValue::as_u64),
+            Some(2)
+        );
+
+        let device = data_set.table("DeviceDetail").unwrap().row(0).unwrap();
+
+        assert!(device.value_by_name("FramesPerSecond").is_none());
+        assert_eq!(
+            device.value_by_name("Acronym").and_then(DataValue::as_str),
+            Some("SHELBY")
+        );
+
+        assert!(DataSet::from_xml(b"<DataSet><DeviceDetail /></DataSet>").is_err());
+    }
+
+    #[test]
+    fn test_row_values_are_type_checked() {
+        let mut table = DataTable::new("Test");
+        table
+            .add_column(DataColumn::new("ID", DataType::Int32, ""))
+            .unwrap();
+
+        let mut row = table.create_row();
+
+        assert!(row.set_value(0, Some(DataValue::Int32(1))).is_ok());
+        assert!(row
+            .set_value_by_name("id", Some(DataValue::Int64(1)))
+            .is_err());
+        assert!(row.set_value(1, None).is_err());
+
+        table.add_row(row).unwrap();
+
+        assert!(table
+            .add_column(DataColumn::new("Name", DataType::String, ""))
+            .is_err());
+        assert!(DataTable::new("Other").add_row(table.create_row()).is_err());
+    }
+}
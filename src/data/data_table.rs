@@ -0,0 +1,153 @@
+//******************************************************************************************************
+//  data_table.rs - Gbtc
+//
+//  Copyright © 2023, Grid Protection Alliance.  All Rights Reserved.
+//
+//  Licensed to the Grid Protection Alliance (GPA) under one or more contributor license agreements. See
+//  the NOTICE file distributed with this work for additional information regarding copyright ownership.
+//  The GPA licenses this file to you under the MIT License (MIT), the "License"; you may not use this
+//  file except in compliance with the License. You may obtain a copy of the License at:
+//
+//      http://opensource.org/licenses/MIT
+//
+//  Unless agreed to in writing, the subject software distributed under the License is distributed on an
+//  "AS-IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied. Refer to the
+//  License for the specific language governing permissions and limitations.
+//
+//  Code Modification History:
+//  ----------------------------------------------------------------------------------------------------
+//  04/01/2023 - J. Ritchie Carroll
+//       Generated original version of source code.
+//
+//******************************************************************************************************
+
+use crate::data::{DataColumn, DataRow};
+use std::error::Error;
+use std::sync::Arc;
+
+/// Represents a collection of `DataColumn` objects where each data column defines a name and a data
+/// type. Data columns can then be accessed by name or index. Additionally, a `DataTable` contains a
+/// collection of `DataRow` objects where each data row defines a value for each data column.
+#[derive(Debug, Clone, Default)]
+pub struct DataTable {
+    name: String,
+    columns: Arc<Vec<DataColumn>>,
+    rows: Vec<DataRow>,
+}
+
+impl DataTable {
+    /// Creates a new, empty `DataTable` with the specified name.
+    pub fn new(name: &str) -> DataTable {
+        DataTable {
+            name: name.to_string(),
+            columns: Arc::new(Vec::new()),
+            rows: Vec::new(),
+        }
+    }
+
+    /// Gets the name of the `DataTable`.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Adds the specified column to the `DataTable`. Columns must be defined before any rows are
+    /// added and column names must be unique, case-insensitive.
+    pub fn add_column(&mut self, mut column: DataColumn) -> Result<(), Box<dyn Error>> {
+        if !self.rows.is_empty() {
+            return Err(format!(
+                "cannot add column \"{}\" to table \"{}\": table already contains rows",
+                column.name(),
+                self.name
+            )
+            .into());
+        }
+
+        if self.column_index(column.name()).is_some() {
+            return Err(format!(
+                "cannot add column \"{}\" to table \"{}\": column already exists",
+                column.name(),
+                self.name
+            )
+            .into());
+        }
+
+        let columns = Arc::make_mut(&mut self.columns);
+        column.set_index(columns.len());
+        columns.push(column);
+
+        Ok(())
+    }
+
+    /// Gets the columns defined for the `DataTable`.
+    pub fn columns(&self) -> &[DataColumn] {
+        &self.columns
+    }
+
+    /// Gets the `DataColumn` at the specified column index, if it exists.
+    pub fn column(&self, column_index: usize) -> Option<&DataColumn> {
+        self.columns.get(column_index)
+    }
+
+    /// Gets the `DataColumn` for the specified column name, case-insensitive, if it exists.
+    pub fn column_by_name(&self, column_name: &str) -> Option<&DataColumn> {
+        self.column(self.column_index(column_name)?)
+    }
+
+    /// Gets the index of the column with the specified name, case-insensitive.
+    pub fn column_index(&self, column_name: &str) -> Option<usize> {
+        self.columns
+            .iter()
+            .position(|column| column.name().eq_ignore_ascii_case(column_name))
+    }
+
+    /// Gets the number of columns defined in the `DataTable`.
+    pub fn column_count(&self) -> usize {
+        self.columns.len()
+    }
+
+    /// Creates a new `DataRow` associated with the `DataTable`. Use `add_row` to add the
+    /// new row to the table.
+    pub fn create_row(&self) -> DataRow {
+        DataRow::new(self.columns.clone())
+    }
+
+    /// Adds the specified row to the `DataTable`. The row must have been created by this
+    /// table with `create_row` using the current column definitions.
+    pub fn add_row(&mut self, row: DataRow) -> Result<(), Box<dyn Error>> {
+        if !Arc::ptr_eq(row.columns(), &self.columns) {
+            return Err(format!(
+                "cannot add row to table \"{}\": row was not created for this table",
+                self.name
+            )
+            .into());
+        }
+
+        self.rows.push(row);
+        Ok(())
+    }
+
+    /// Gets the rows of the `DataTable`.
+    pub fn rows(&self) -> &[DataRow] {
+        &self.rows
+    }
+
+    /// Gets the `DataRow` at the specified row index, if it exists.
+    pub fn row(&self, row_index: usize) -> Option<&DataRow> {
+        self.rows.get(row_index)
+    }
+
+    /// Gets a mutable reference to the `DataRow` at the specified row index, if it exists.
+    pub fn row_mut(&mut self, row_index: usize) -> Option<&mut DataRow> {
+        self.rows.get_mut(row_index)
+    }
+
+    /// Gets the number of rows in the `DataTable`.
+    pub fn row_count(&self) -> usize {
+        self.rows.len()
+    }
+
+    /// Removes all rows from the `DataTable`.
+    pub fn clear_rows(&mut self) {
+        self.rows.clear();
+    }
+}
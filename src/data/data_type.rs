@@ -0,0 +1,103 @@
+//******************************************************************************************************
+//  data_type.rs - Gbtc
+//
+//  Copyright © 2023, Grid Protection Alliance.  All Rights Reserved.
+//
+//  Licensed to the Grid Protection Alliance (GPA) under one or more contributor license agreements. See
+//  the NOTICE file distributed with this work for additional information regarding copyright ownership.
+//  The GPA licenses this file to you under the MIT License (MIT), the "License"; you may not use this
+//  file except in compliance with the License. You may obtain a copy of the License at:
+//
+//      http://opensource.org/licenses/MIT
+//
+//  Unless agreed to in writing, the subject software distributed under the License is distributed on an
+//  "AS-IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied. Refer to the
+//  License for the specific language governing permissions and limitations.
+//
+//  Code Modification History:
+//  ----------------------------------------------------------------------------------------------------
+//  04/01/2023 - J. Ritchie Carroll
+//       Generated original version of source code.
+//
+//******************************************************************************************************
+
+use std::fmt::{Display, Formatter, Result as FmtResult};
+
+/// Specifies the type of a `DataColumn`.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DataType {
+    /// Represents a Rust `String` data type.
+    #[default]
+    String,
+    /// Represents a Rust `bool` data type.
+    Boolean,
+    /// Represents a Rust `chrono::DateTime<Utc>` data type.
+    DateTime,
+    /// Represents a Rust `f32` data type.
+    Single,
+    /// Represents a Rust `f64` data type.
+    Double,
+    /// Represents a Rust `rust_decimal::Decimal` data type.
+    Decimal,
+    /// Represents a Rust `uuid::Uuid` data type.
+    Guid,
+    /// Represents a Rust `i8` data type.
+    Int8,
+    /// Represents a Rust `i16` data type.
+    Int16,
+    /// Represents a Rust `i32` data type.
+    Int32,
+    /// Represents a Rust `i64` data type.
+    Int64,
+    /// Represents a Rust `u8` data type.
+    UInt8,
+    /// Represents a Rust `u16` data type.
+    UInt16,
+    /// Represents a Rust `u32` data type.
+    UInt32,
+    /// Represents a Rust `u64` data type.
+    UInt64,
+}
+
+impl DataType {
+    /// Gets the `DataType` from the provided XSD data type. Return value is `None` when the type
+    /// is not recognized. The extended data type, e.g., `msdata:DataType` attribute value, is used to
+    /// distinguish types that share an XSD type, such as a `Guid` which is serialized as a string.
+    pub fn from_xsd_type(xsd_type: &str, ext_data_type: &str) -> Option<DataType> {
+        // Remove any namespace prefix, e.g., "xs:string"
+        let xsd_type = match xsd_type.rfind(':') {
+            Some(index) => &xsd_type[index + 1..],
+            None => xsd_type,
+        };
+
+        match xsd_type {
+            "string" => {
+                if ext_data_type.starts_with("System.Guid") {
+                    Some(DataType::Guid)
+                } else {
+                    Some(DataType::String)
+                }
+            }
+            "boolean" => Some(DataType::Boolean),
+            "dateTime" => Some(DataType::DateTime),
+            "float" => Some(DataType::Single),
+            "double" => Some(DataType::Double),
+            "decimal" => Some(DataType::Decimal),
+            "byte" => Some(DataType::Int8),
+            "short" => Some(DataType::Int16),
+            "int" => Some(DataType::Int32),
+            "long" => Some(DataType::Int64),
+            "unsignedByte" => Some(DataType::UInt8),
+            "unsignedShort" => Some(DataType::UInt16),
+            "unsignedInt" => Some(DataType::UInt32),
+            "unsignedLong" => Some(DataType::UInt64),
+            _ => None,
+        }
+    }
+}
+
+impl Display for DataType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(f, "{:?}", self)
+    }
+}
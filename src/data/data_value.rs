@@ -0,0 +1,358 @@
+//******************************************************************************************************
+//  data_value.rs - Gbtc
+//
+//  Copyright © 2023, Grid Protection Alliance.  All Rights Reserved.
+//
+//  Licensed to the Grid Protection Alliance (GPA) under one or more contributor license agreements. See
+//  the NOTICE file distributed with this work for additional information regarding copyright ownership.
+//  The GPA licenses this file to you under the MIT License (MIT), the "License"; you may not use this
+//  file except in compliance with the License. You may obtain a copy of the License at:
+//
+//      http://opensource.org/licenses/MIT
+//
+//  Unless agreed to in writing, the subject software distributed under the License is distributed on an
+//  "AS-IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied. Refer to the
+//  License for the specific language governing permissions and limitations.
+//
+//  Code Modification History:
+//  ----------------------------------------------------------------------------------------------------
+//  04/01/2023 - J. Ritchie Carroll
+//       Generated original version of source code.
+//
+//******************************************************************************************************
+
+use crate::data::DataType;
+use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
+use rust_decimal::Decimal;
+use std::error::Error;
+use std::fmt::{Display, Formatter, Result as FmtResult};
+use std::str::FromStr;
+use uuid::Uuid;
+
+/// Represents a typed value stored in a `DataRow`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum DataValue {
+    /// Represents a `DataType::String` value.
+    String(String),
+    /// Represents a `DataType::Boolean` value.
+    Boolean(bool),
+    /// Represents a `DataType::DateTime` value.
+    DateTime(DateTime<Utc>),
+    /// Represents a `DataType::Single` value.
+    Single(f32),
+    /// Represents a `DataType::Double` value.
+    Double(f64),
+    /// Represents a `DataType::Decimal` value.
+    Decimal(Decimal),
+    /// Represents a `DataType::Guid` value.
+    Guid(Uuid),
+    /// Represents a `DataType::Int8` value.
+    Int8(i8),
+    /// Represents a `DataType::Int16` value.
+    Int16(i16),
+    /// Represents a `DataType::Int32` value.
+    Int32(i32),
+    /// Represents a `DataType::Int64` value.
+    Int64(i64),
+    /// Represents a `DataType::UInt8` value.
+    UInt8(u8),
+    /// Represents a `DataType::UInt16` value.
+    UInt16(u16),
+    /// Represents a `DataType::UInt32` value.
+    UInt32(u32),
+    /// Represents a `DataType::UInt64` value.
+    UInt64(u64),
+}
+
+impl DataValue {
+    /// Parses a `DataValue` of the specified `DataType` from its XML string representation.
+    pub fn parse(data_type: DataType, value: &str) -> Result<DataValue, Box<dyn Error>> {
+        // String values are preserved as-is, other types ignore surrounding whitespace
+        if data_type == DataType::String {
+            return Ok(DataValue::String(value.to_string()));
+        }
+
+        let value = value.trim();
+
+        Ok(match data_type {
+            DataType::String => DataValue::String(value.to_string()),
+            DataType::Boolean => DataValue::Boolean(parse_boolean(value)?),
+            DataType::DateTime => DataValue::DateTime(parse_datetime(value)?),
+            DataType::Single => DataValue::Single(parse_float(value)? as f32),
+            DataType::Double => DataValue::Double(parse_float(value)?),
+            DataType::Decimal => DataValue::Decimal(
+                Decimal::from_str(value).or_else(|_| Decimal::from_scientific(value))?,
+            ),
+            DataType::Guid => DataValue::Guid(Uuid::parse_str(value)?),
+            DataType::Int8 => DataValue::Int8(value.parse()?),
+            DataType::Int16 => DataValue::Int16(value.parse()?),
+            DataType::Int32 => DataValue::Int32(value.parse()?),
+            DataType::Int64 => DataValue::Int64(value.parse()?),
+            DataType::UInt8 => DataValue::UInt8(value.parse()?),
+            DataType::UInt16 => DataValue::UInt16(value.parse()?),
+            DataType::UInt32 => DataValue::UInt32(value.parse()?),
+            DataType::UInt64 => DataValue::UInt64(value.parse()?),
+        })
+    }
+
+    /// Gets the `DataType` of the `DataValue`.
+    pub fn data_type(&self) -> DataType {
+        match self {
+            DataValue::String(_) => DataType::String,
+            DataValue::Boolean(_) => DataType::Boolean,
+            DataValue::DateTime(_) => DataType::DateTime,
+            DataValue::Single(_) => DataType::Single,
+            DataValue::Double(_) => DataType::Double,
+            DataValue::Decimal(_) => DataType::Decimal,
+            DataValue::Guid(_) => DataType::Guid,
+            DataValue::Int8(_) => DataType::Int8,
+            DataValue::Int16(_) => DataType::Int16,
+            DataValue::Int32(_) => DataType::Int32,
+            DataValue::Int64(_) => DataType::Int64,
+            DataValue::UInt8(_) => DataType::UInt8,
+            DataValue::UInt16(_) => DataType::UInt16,
+            DataValue::UInt32(_) => DataType::UInt32,
+            DataValue::UInt64(_) => DataType::UInt64,
+        }
+    }
+
+    /// Gets the value as a string slice if the `DataValue` is a `DataType::String`.
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            DataValue::String(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Gets the value as a `bool` if the `DataValue` is a `DataType::Boolean`.
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            DataValue::Boolean(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    /// Gets the value as a `DateTime<Utc>` if the `DataValue` is a `DataType::DateTime`.
+    pub fn as_datetime(&self) -> Option<DateTime<Utc>> {
+        match self {
+            DataValue::DateTime(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    /// Gets the value as an `f32` if the `DataValue` is a `DataType::Single`.
+    pub fn as_f32(&self) -> Option<f32> {
+        match self {
+            DataValue::Single(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    /// Gets the value as an `f64` if the `DataValue` is a `DataType::Double`.
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            DataValue::Double(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    /// Gets the value as a `Decimal` if the `DataValue` is a `DataType::Decimal`.
+    pub fn as_decimal(&self) -> Option<Decimal> {
+        match self {
+            DataValue::Decimal(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    /// Gets the value as a `Uuid` if the `DataValue` is a `DataType::Guid`.
+    pub fn as_guid(&self) -> Option<Uuid> {
+        match self {
+            DataValue::Guid(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    /// Gets the value as an `i8` if the `DataValue` is a `DataType::Int8`.
+    pub fn as_i8(&self) -> Option<i8> {
+        match self {
+            DataValue::Int8(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    /// Gets the value as an `i16` if the `DataValue` is a `DataType::Int16`.
+    pub fn as_i16(&self) -> Option<i16> {
+        match self {
+            DataValue::Int16(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    /// Gets the value as an `i32` if the `DataValue` is a `DataType::Int32`.
+    pub fn as_i32(&self) -> Option<i32> {
+        match self {
+            DataValue::Int32(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    /// Gets the value as an `i64` if the `DataValue` is a `DataType::Int64`.
+    pub fn as_i64(&self) -> Option<i64> {
+        match self {
+            DataValue::Int64(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    /// Gets the value as a `u8` if the `DataValue` is a `DataType::UInt8`.
+    pub fn as_u8(&self) -> Option<u8> {
+        match self {
+            DataValue::UInt8(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    /// Gets the value as a `u16` if the `DataValue` is a `DataType::UInt16`.
+    pub fn as_u16(&self) -> Option<u16> {
+        match self {
+            DataValue::UInt16(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    /// Gets the value as a `u32` if the `DataValue` is a `DataType::UInt32`.
+    pub fn as_u32(&self) -> Option<u32> {
+        match self {
+            DataValue::UInt32(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    /// Gets the value as a `u64` if the `DataValue` is a `DataType::UInt64`.
+    pub fn as_u64(&self) -> Option<u64> {
+        match self {
+            DataValue::UInt64(value) => Some(*value),
+            _ => None,
+        }
+    }
+}
+
+impl Display for DataValue {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            DataValue::String(value) => write!(f, "{}", value),
+            DataValue::Boolean(value) => write!(f, "{}", value),
+            DataValue::DateTime(value) => write!(f, "{}", value.format("%Y-%m-%d %H:%M:%S%.f")),
+            DataValue::Single(value) => write!(f, "{}", value),
+            DataValue::Double(value) => write!(f, "{}", value),
+            DataValue::Decimal(value) => write!(f, "{}", value),
+            DataValue::Guid(value) => write!(f, "{}", value),
+            DataValue::Int8(value) => write!(f, "{}", value),
+            DataValue::Int16(value) => write!(f, "{}", value),
+            DataValue::Int32(value) => write!(f, "{}", value),
+            DataValue::Int64(value) => write!(f, "{}", value),
+            DataValue::UInt8(value) => write!(f, "{}", value),
+            DataValue::UInt16(value) => write!(f, "{}", value),
+            DataValue::UInt32(value) => write!(f, "{}", value),
+            DataValue::UInt64(value) => write!(f, "{}", value),
+        }
+    }
+}
+
+fn parse_boolean(value: &str) -> Result<bool, Box<dyn Error>> {
+    if value.eq_ignore_ascii_case("true") || value == "1" {
+        Ok(true)
+    } else if value.eq_ignore_ascii_case("false") || value == "0" {
+        Ok(false)
+    } else {
+        Err(format!("invalid boolean value \"{}\"", value).into())
+    }
+}
+
+// XML serialized floating-point values use "INF" and "-INF" for infinities
+fn parse_float(value: &str) -> Result<f64, Box<dyn Error>> {
+    match value {
+        "INF" => Ok(f64::INFINITY),
+        "-INF" => Ok(f64::NEG_INFINITY),
+        _ => Ok(value.parse()?),
+    }
+}
+
+// Date/time formats without a time zone offset, values are assumed to be UTC
+const DATETIME_FORMATS: [&str; 4] = [
+    "%Y-%m-%dT%H:%M:%S%.f",
+    "%Y-%m-%d %H:%M:%S%.f",
+    "%m/%d/%Y %H:%M:%S%.f",
+    "%m/%d/%Y %H:%M",
+];
+
+const DATE_FORMATS: [&str; 2] = ["%Y-%m-%d", "%m/%d/%Y"];
+
+// XML serialized date/time values may or may not include a time zone offset, other
+// common formats are accepted for date/time literals used in filter expressions
+fn parse_datetime(value: &str) -> Result<DateTime<Utc>, Box<dyn Error>> {
+    if let Ok(datetime) = DateTime::parse_from_rfc3339(value) {
+        return Ok(datetime.with_timezone(&Utc));
+    }
+
+    for format in DATETIME_FORMATS {
+        if let Ok(datetime) = NaiveDateTime::parse_from_str(value, format) {
+            return Ok(datetime.and_utc());
+        }
+    }
+
+    for format in DATE_FORMATS {
+        if let Ok(date) = NaiveDate::parse_from_str(value, format) {
+            return Ok(date.and_hms_opt(0, 0, 0).unwrap().and_utc());
+        }
+    }
+
+    Err(format!("invalid date/time value \"{}\"", value).into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_xml_values() {
+        assert_eq!(
+            DataValue::parse(DataType::Boolean, "true").unwrap(),
+            DataValue::Boolean(true)
+        );
+        assert_eq!(
+            DataValue::parse(DataType::Single, "-INF").unwrap(),
+            DataValue::Single(f32::NEG_INFINITY)
+        );
+        assert_eq!(
+            DataValue::parse(DataType::Decimal, "-89.8772").unwrap(),
+            DataValue::Decimal(Decimal::from_str("-89.8772").unwrap())
+        );
+        assert_eq!(
+            DataValue::parse(DataType::Guid, "{29A2F3D8-E6B1-4A5A-9A2C-0C4C4D0C7D51}").unwrap(),
+            DataValue::Guid(Uuid::parse_str("29a2f3d8-e6b1-4a5a-9a2c-0c4c4d0c7d51").unwrap())
+        );
+        assert_eq!(
+            DataValue::parse(DataType::UInt64, "18446744073709551615").unwrap(),
+            DataValue::UInt64(u64::MAX)
+        );
+
+        let expected = NaiveDate::from_ymd_opt(2023, 3, 25)
+            .unwrap()
+            .and_hms_milli_opt(17, 30, 0, 500)
+            .unwrap()
+            .and_utc();
+
+        assert_eq!(
+            DataValue::parse(DataType::DateTime, "2023-03-25T13:30:00.5-04:00").unwrap(),
+            DataValue::DateTime(expected)
+        );
+        assert_eq!(
+            DataValue::parse(DataType::DateTime, "2023-03-25T17:30:00.5").unwrap(),
+            DataValue::DateTime(expected)
+        );
+
+        assert!(DataValue::parse(DataType::Int8, "128").is_err());
+        assert!(DataValue::parse(DataType::Boolean, "yes").is_err());
+    }
+}
@@ -0,0 +1,231 @@
+//******************************************************************************************************
+//  expression.rs - Gbtc
+//
+//  Copyright © 2023, Grid Protection Alliance.  All Rights Reserved.
+//
+//  Licensed to the Grid Protection Alliance (GPA) under one or more contributor license agreements. See
+//  the NOTICE file distributed with this work for additional information regarding copyright ownership.
+//  The GPA licenses this file to you under the MIT License (MIT), the "License"; you may not use this
+//  file except in compliance with the License. You may obtain a copy of the License at:
+//
+//      http://opensource.org/licenses/MIT
+//
+//  Unless agreed to in writing, the subject software distributed under the License is distributed on an
+//  "AS-IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied. Refer to the
+//  License for the specific language governing permissions and limitations.
+//
+//  Code Modification History:
+//  ----------------------------------------------------------------------------------------------------
+//  04/01/2023 - J. Ritchie Carroll
+//       Generated original version of source code.
+//
+//******************************************************************************************************
+
+use crate::data::DataValue;
+
+// Defines the unary operators of the filter expression syntax
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum UnaryOperator {
+    Plus,
+    Minus,
+    Not,
+    BitwiseComplement,
+}
+
+// Defines the binary operators of the filter expression syntax
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum OperatorType {
+    Multiply,
+    Divide,
+    Modulus,
+    Add,
+    Subtract,
+    BitShiftLeft,
+    BitShiftRight,
+    BitwiseAnd,
+    BitwiseOr,
+    BitwiseXor,
+    LessThan,
+    LessThanOrEqual,
+    GreaterThan,
+    GreaterThanOrEqual,
+    Equal,
+    EqualExactMatch,
+    NotEqual,
+    NotEqualExactMatch,
+    Like,
+    LikeExactMatch,
+    NotLike,
+    NotLikeExactMatch,
+    And,
+    Or,
+}
+
+// Defines the functions of the filter expression syntax
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum FunctionType {
+    Abs,
+    Ceiling,
+    Coalesce,
+    Convert,
+    Contains,
+    DateAdd,
+    DateDiff,
+    DatePart,
+    EndsWith,
+    Floor,
+    IIf,
+    IndexOf,
+    IsDate,
+    IsInteger,
+    IsGuid,
+    IsNull,
+    IsNumeric,
+    LastIndexOf,
+    Len,
+    Lower,
+    MaxOf,
+    MinOf,
+    Now,
+    NthIndexOf,
+    Power,
+    RegExMatch,
+    RegExVal,
+    Replace,
+    Reverse,
+    Round,
+    Split,
+    Sqrt,
+    StartsWith,
+    StrCount,
+    StrCmp,
+    SubStr,
+    Trim,
+    TrimLeft,
+    TrimRight,
+    Upper,
+    UtcNow,
+}
+
+const FUNCTION_TYPES: [FunctionType; 41] = [
+    FunctionType::Abs,
+    FunctionType::Ceiling,
+    FunctionType::Coalesce,
+    FunctionType::Convert,
+    FunctionType::Contains,
+    FunctionType::DateAdd,
+    FunctionType::DateDiff,
+    FunctionType::DatePart,
+    FunctionType::EndsWith,
+    FunctionType::Floor,
+    FunctionType::IIf,
+    FunctionType::IndexOf,
+    FunctionType::IsDate,
+    FunctionType::IsInteger,
+    FunctionType::IsGuid,
+    FunctionType::IsNull,
+    FunctionType::IsNumeric,
+    FunctionType::LastIndexOf,
+    FunctionType::Len,
+    FunctionType::Lower,
+    FunctionType::MaxOf,
+    FunctionType::MinOf,
+    FunctionType::Now,
+    FunctionType::NthIndexOf,
+    FunctionType::Power,
+    FunctionType::RegExMatch,
+    FunctionType::RegExVal,
+    FunctionType::Replace,
+    FunctionType::Reverse,
+    FunctionType::Round,
+    FunctionType::Split,
+    FunctionType::Sqrt,
+    FunctionType::StartsWith,
+    FunctionType::StrCount,
+    FunctionType::StrCmp,
+    FunctionType::SubStr,
+    FunctionType::Trim,
+    FunctionType::TrimLeft,
+    FunctionType::TrimRight,
+    FunctionType::Upper,
+    FunctionType::UtcNow,
+];
+
+impl FunctionType {
+    // Gets the function with the specified name, case-insensitive
+    pub(crate) fn from_name(name: &str) -> Option<FunctionType> {
+        // "IsRegExMatch" is an accepted alias for "RegExMatch"
+        if name.eq_ignore_ascii_case("IsRegExMatch") {
+            return Some(FunctionType::RegExMatch);
+        }
+
+        FUNCTION_TYPES
+            .iter()
+            .find(|function| format!("{:?}", function).eq_ignore_ascii_case(name))
+            .copied()
+    }
+
+    // Gets the minimum and maximum number of arguments accepted by the function
+    pub(crate) fn argument_range(&self) -> (usize, usize) {
+        match self {
+            FunctionType::Now | FunctionType::UtcNow => (0, 0),
+            FunctionType::Abs
+            | FunctionType::Ceiling
+            | FunctionType::Floor
+            | FunctionType::IsDate
+            | FunctionType::IsInteger
+            | FunctionType::IsGuid
+            | FunctionType::IsNumeric
+            | FunctionType::Len
+            | FunctionType::Lower
+            | FunctionType::Reverse
+            | FunctionType::Round
+            | FunctionType::Sqrt
+            | FunctionType::Trim
+            | FunctionType::TrimLeft
+            | FunctionType::TrimRight
+            | FunctionType::Upper => (1, 1),
+            FunctionType::Convert
+            | FunctionType::DatePart
+            | FunctionType::IsNull
+            | FunctionType::Power
+            | FunctionType::RegExMatch
+            | FunctionType::RegExVal => (2, 2),
+            FunctionType::Contains
+            | FunctionType::EndsWith
+            | FunctionType::IndexOf
+            | FunctionType::LastIndexOf
+            | FunctionType::StartsWith
+            | FunctionType::StrCount
+            | FunctionType::StrCmp
+            | FunctionType::SubStr => (2, 3),
+            FunctionType::DateAdd | FunctionType::DateDiff | FunctionType::IIf => (3, 3),
+            FunctionType::NthIndexOf | FunctionType::Replace | FunctionType::Split => (3, 4),
+            FunctionType::Coalesce | FunctionType::MaxOf | FunctionType::MinOf => (2, usize::MAX),
+        }
+    }
+}
+
+// Represents a node in a parsed filter expression tree
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Expression {
+    // Literal value, `None` represents NULL
+    Value(Option<DataValue>),
+    // Column reference by name, as parsed
+    ColumnName(String),
+    // Column reference by index, once bound to a table
+    Column(usize),
+    Unary(UnaryOperator, Box<Expression>),
+    Operator(OperatorType, Box<Expression>, Box<Expression>),
+    IsNull {
+        value: Box<Expression>,
+        negated: bool,
+    },
+    In {
+        value: Box<Expression>,
+        arguments: Vec<Expression>,
+        negated: bool,
+        exact_match: bool,
+    },
+    Function(FunctionType, Vec<Expression>),
+}
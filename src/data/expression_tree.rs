@@ -0,0 +1,1223 @@
+//******************************************************************************************************
+//  expression_tree.rs - Gbtc
+//
+//  Copyright © 2023, Grid Protection Alliance.  All Rights Reserved.
+//
+//  Licensed to the Grid Protection Alliance (GPA) under one or more contributor license agreements. See
+//  the NOTICE file distributed with this work for additional information regarding copyright ownership.
+//  The GPA licenses this file to you under the MIT License (MIT), the "License"; you may not use this
+//  file except in compliance with the License. You may obtain a copy of the License at:
+//
+//      http://opensource.org/licenses/MIT
+//
+//  Unless agreed to in writing, the subject software distributed under the License is distributed on an
+//  "AS-IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied. Refer to the
+//  License for the specific language governing permissions and limitations.
+//
+//  Code Modification History:
+//  ----------------------------------------------------------------------------------------------------
+//  04/01/2023 - J. Ritchie Carroll
+//       Generated original version of source code.
+//
+//******************************************************************************************************
+
+use crate::data::expression::{Expression, FunctionType, OperatorType, UnaryOperator};
+use crate::data::{DataRow, DataTable, DataType, DataValue};
+use chrono::{DateTime, Datelike, Duration, Local, Months, Timelike, Utc};
+use regex::{NoExpand, Regex, RegexBuilder};
+use rust_decimal::prelude::{FromPrimitive, ToPrimitive};
+use rust_decimal::Decimal;
+use std::cmp::Ordering;
+use std::error::Error;
+
+type EvaluationResult = Result<Option<DataValue>, Box<dyn Error>>;
+
+// Defines the date/time intervals used by the DateAdd, DateDiff and DatePart functions
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TimeInterval {
+    Year,
+    Month,
+    DayOfYear,
+    Day,
+    Week,
+    WeekDay,
+    Hour,
+    Minute,
+    Second,
+    Millisecond,
+}
+
+const TIME_INTERVALS: [TimeInterval; 10] = [
+    TimeInterval::Year,
+    TimeInterval::Month,
+    TimeInterval::DayOfYear,
+    TimeInterval::Day,
+    TimeInterval::Week,
+    TimeInterval::WeekDay,
+    TimeInterval::Hour,
+    TimeInterval::Minute,
+    TimeInterval::Second,
+    TimeInterval::Millisecond,
+];
+
+// Binds column references in the expression to the column indexes of the specified table
+pub(crate) fn bind(
+    expression: &Expression,
+    table: &DataTable,
+) -> Result<Expression, Box<dyn Error>> {
+    let bind_all = |expressions: &[Expression]| -> Result<Vec<Expression>, Box<dyn Error>> {
+        expressions.iter().map(|item| bind(item, table)).collect()
+    };
+
+    Ok(match expression {
+        Expression::ColumnName(name) => {
+            Expression::Column(table.column_index(name).ok_or_else(|| {
+                format!(
+                    "failed to find column \"{}\" in table \"{}\"",
+                    name,
+                    table.name()
+                )
+            })?)
+        }
+        Expression::Unary(operator, value) => {
+            Expression::Unary(*operator, Box::new(bind(value, table)?))
+        }
+        Expression::Operator(operator, left, right) => Expression::Operator(
+            *operator,
+            Box::new(bind(left, table)?),
+            Box::new(bind(right, table)?),
+        ),
+        Expression::IsNull { value, negated } => Expression::IsNull {
+            value: Box::new(bind(value, table)?),
+            negated: *negated,
+        },
+        Expression::In {
+            value,
+            arguments,
+            negated,
+            exact_match,
+        } => Expression::In {
+            value: Box::new(bind(value, table)?),
+            arguments: bind_all(arguments)?,
+            negated: *negated,
+            exact_match: *exact_match,
+        },
+        Expression::Function(function, arguments) => {
+            Expression::Function(*function, bind_all(arguments)?)
+        }
+        Expression::Value(_) | Expression::Column(_) => expression.clone(),
+    })
+}
+
+// Evaluates the expression for the specified row, column references must be bound
+pub(crate) fn evaluate(expression: &Expression, row: Option<&DataRow>) -> EvaluationResult {
+    match expression {
+        Expression::Value(value) => Ok(value.clone()),
+        Expression::ColumnName(name) => {
+            Err(format!("column \"{}\" is not bound to a table", name).into())
+        }
+        Expression::Column(index) => {
+            let row = row.ok_or("cannot evaluate column reference without a data row")?;
+            Ok(row.value(*index).map(normalize))
+        }
+        Expression::Unary(operator, value) => evaluate_unary(*operator, evaluate(value, row)?),
+        Expression::Operator(operator, left, right) => {
+            evaluate_operator(*operator, evaluate(left, row)?, evaluate(right, row)?)
+        }
+        Expression::IsNull { value, negated } => Ok(Some(DataValue::Boolean(
+            evaluate(value, row)?.is_none() != *negated,
+        ))),
+        Expression::In {
+            value,
+            arguments,
+            negated,
+            exact_match,
+        } => {
+            let value = match evaluate(value, row)? {
+                Some(value) => value,
+                None => return Ok(None),
+            };
+
+            for argument in arguments {
+                if let Some(argument) = evaluate(argument, row)? {
+                    if compare(&value, &argument, *exact_match)? == Ordering::Equal {
+                        return Ok(Some(DataValue::Boolean(!negated)));
+                    }
+                }
+            }
+
+            Ok(Some(DataValue::Boolean(*negated)))
+        }
+        Expression::Function(function, arguments) => evaluate_function(*function, arguments, row),
+    }
+}
+
+// Compares two optional values for sorting where nulls are ordered first
+pub(crate) fn compare_nullable(
+    left: Option<&DataValue>,
+    right: Option<&DataValue>,
+    exact_match: bool,
+) -> Ordering {
+    match (left, right) {
+        (None, None) => Ordering::Equal,
+        (None, Some(_)) => Ordering::Less,
+        (Some(_), None) => Ordering::Greater,
+        (Some(left), Some(right)) => {
+            compare(&normalize(left), &normalize(right), exact_match).unwrap_or(Ordering::Equal)
+        }
+    }
+}
+
+// Converts a value to its type used during expression evaluation, i.e., Boolean, Int32,
+// Int64, Decimal, Double, String, Guid or DateTime
+fn normalize(value: &DataValue) -> DataValue {
+    match value {
+        DataValue::Single(value) => DataValue::Double(*value as f64),
+        DataValue::Int8(value) => DataValue::Int32(*value as i32),
+        DataValue::Int16(value) => DataValue::Int32(*value as i32),
+        DataValue::UInt8(value) => DataValue::Int32(*value as i32),
+        DataValue::UInt16(value) => DataValue::Int32(*value as i32),
+        DataValue::UInt32(value) => DataValue::Int64(*value as i64),
+        DataValue::UInt64(value) => match i64::try_from(*value) {
+            Ok(value) => DataValue::Int64(value),
+            Err(_) => DataValue::Decimal(Decimal::from(*value)),
+        },
+        _ => value.clone(),
+    }
+}
+
+// Gets the rank of a numeric value type used for type promotion
+fn numeric_rank(value: &DataValue) -> Option<u8> {
+    match value {
+        DataValue::Boolean(_) => Some(0),
+        DataValue::Int32(_) => Some(1),
+        DataValue::Int64(_) => Some(2),
+        DataValue::Decimal(_) => Some(3),
+        DataValue::Double(_) => Some(4),
+        _ => None,
+    }
+}
+
+fn rank_data_type(rank: u8) -> DataType {
+    match rank {
+        0 => DataType::Boolean,
+        1 => DataType::Int32,
+        2 => DataType::Int64,
+        3 => DataType::Decimal,
+        _ => DataType::Double,
+    }
+}
+
+fn to_i64(value: &DataValue) -> Option<i64> {
+    match value {
+        DataValue::Boolean(value) => Some(*value as i64),
+        DataValue::Int32(value) => Some(*value as i64),
+        DataValue::Int64(value) => Some(*value),
+        DataValue::Decimal(value) => value.trunc().to_i64(),
+        DataValue::Double(value) => {
+            if value.is_finite() && *value >= i64::MIN as f64 && *value <= i64::MAX as f64 {
+                Some(value.trunc() as i64)
+            } else {
+                None
+            }
+        }
+        _ => None,
+    }
+}
+
+fn to_f64(value: &DataValue) -> Option<f64> {
+    match value {
+        DataValue::Boolean(value) => Some(if *value { 1.0 } else { 0.0 }),
+        DataValue::Int32(value) => Some(*value as f64),
+        DataValue::Int64(value) => Some(*value as f64),
+        DataValue::Decimal(value) => value.to_f64(),
+        DataValue::Double(value) => Some(*value),
+        _ => None,
+    }
+}
+
+fn to_decimal(value: &DataValue) -> Option<Decimal> {
+    match value {
+        DataValue::Boolean(value) => Some(Decimal::from(*value as i32)),
+        DataValue::Int32(value) => Some(Decimal::from(*value)),
+        DataValue::Int64(value) => Some(Decimal::from(*value)),
+        DataValue::Decimal(value) => Some(*value),
+        DataValue::Double(value) => Decimal::from_f64(*value),
+        _ => None,
+    }
+}
+
+// Converts a value to the specified data type
+pub(crate) fn convert(value: &DataValue, data_type: DataType) -> Result<DataValue, Box<dyn Error>> {
+    let value = normalize(value);
+
+    if value.data_type() == data_type {
+        return Ok(value);
+    }
+
+    if data_type == DataType::String {
+        return Ok(DataValue::String(value.to_string()));
+    }
+
+    if let DataValue::String(text) = &value {
+        return DataValue::parse(data_type, text).map_err(|_| {
+            format!("cannot convert String value \"{}\" to {}", text, data_type).into()
+        });
+    }
+
+    let converted = match data_type {
+        DataType::Boolean => to_f64(&value).map(|value| DataValue::Boolean(value != 0.0)),
+        DataType::Single => to_f64(&value).map(|value| DataValue::Single(value as f32)),
+        DataType::Double => to_f64(&value).map(DataValue::Double),
+        DataType::Decimal => to_decimal(&value).map(DataValue::Decimal),
+        DataType::Int8 => to_i64(&value)
+            .and_then(|value| i8::try_from(value).ok())
+            .map(DataValue::Int8),
+        DataType::Int16 => to_i64(&value)
+            .and_then(|value| i16::try_from(value).ok())
+            .map(DataValue::Int16),
+        DataType::Int32 => to_i64(&value)
+            .and_then(|value| i32::try_from(value).ok())
+            .map(DataValue::Int32),
+        DataType::Int64 => to_i64(&value).map(DataValue::Int64),
+        DataType::UInt8 => to_i64(&value)
+            .and_then(|value| u8::try_from(value).ok())
+            .map(DataValue::UInt8),
+        DataType::UInt16 => to_i64(&value)
+            .and_then(|value| u16::try_from(value).ok())
+            .map(DataValue::UInt16),
+        DataType::UInt32 => to_i64(&value)
+            .and_then(|value| u32::try_from(value).ok())
+            .map(DataValue::UInt32),
+        DataType::UInt64 => match &value {
+            DataValue::Decimal(value) => value.trunc().to_u64(),
+            _ => to_i64(&value).and_then(|value| u64::try_from(value).ok()),
+        }
+        .map(DataValue::UInt64),
+        DataType::String | DataType::Guid | DataType::DateTime => None,
+    };
+
+    converted.ok_or_else(|| {
+        format!(
+            "cannot convert {} value \"{}\" to {}",
+            value.data_type(),
+            value,
+            data_type
+        )
+        .into()
+    })
+}
+
+// Converts two values to a common type for comparison or arithmetic
+fn unify(left: &DataValue, right: &DataValue) -> Result<(DataValue, DataValue), Box<dyn Error>> {
+    let left = normalize(left);
+    let right = normalize(right);
+
+    if left.data_type() == right.data_type() {
+        return Ok((left, right));
+    }
+
+    // Strings are converted to the type of the other operand
+    if let DataValue::String(_) = left {
+        return Ok((convert(&left, right.data_type())?, right));
+    }
+
+    if let DataValue::String(_) = right {
+        return Ok((left.clone(), convert(&right, left.data_type())?));
+    }
+
+    match (numeric_rank(&left), numeric_rank(&right)) {
+        (Some(left_rank), Some(right_rank)) => {
+            let data_type = rank_data_type(left_rank.max(right_rank));
+            Ok((convert(&left, data_type)?, convert(&right, data_type)?))
+        }
+        _ => Err(format!(
+            "cannot operate on {} and {} values",
+            left.data_type(),
+            right.data_type()
+        )
+        .into()),
+    }
+}
+
+// Compares two values, string comparisons are case-insensitive unless exact match is requested
+fn compare(
+    left: &DataValue,
+    right: &DataValue,
+    exact_match: bool,
+) -> Result<Ordering, Box<dyn Error>> {
+    Ok(match unify(left, right)? {
+        (DataValue::String(left), DataValue::String(right)) => {
+            if exact_match {
+                left.cmp(&right)
+            } else {
+                left.to_lowercase().cmp(&right.to_lowercase())
+            }
+        }
+        (DataValue::Boolean(left), DataValue::Boolean(right)) => left.cmp(&right),
+        (DataValue::Int32(left), DataValue::Int32(right)) => left.cmp(&right),
+        (DataValue::Int64(left), DataValue::Int64(right)) => left.cmp(&right),
+        (DataValue::Decimal(left), DataValue::Decimal(right)) => left.cmp(&right),
+        (DataValue::Double(left), DataValue::Double(right)) => {
+            left.partial_cmp(&right).unwrap_or(Ordering::Equal)
+        }
+        (DataValue::Guid(left), DataValue::Guid(right)) => left.cmp(&right),
+        (DataValue::DateTime(left), DataValue::DateTime(right)) => left.cmp(&right),
+        (left, right) => {
+            return Err(format!(
+                "cannot compare {} and {} values",
+                left.data_type(),
+                right.data_type()
+            )
+            .into())
+        }
+    })
+}
+
+fn evaluate_unary(operator: UnaryOperator, value: Option<DataValue>) -> EvaluationResult {
+    let value = match value {
+        Some(value) => value,
+        None => return Ok(None),
+    };
+
+    let result = match (operator, &value) {
+        (UnaryOperator::Plus, DataValue::Int32(_))
+        | (UnaryOperator::Plus, DataValue::Int64(_))
+        | (UnaryOperator::Plus, DataValue::Decimal(_))
+        | (UnaryOperator::Plus, DataValue::Double(_)) => Some(value.clone()),
+        (UnaryOperator::Minus, DataValue::Int32(value)) => {
+            Some(DataValue::Int32(value.wrapping_neg()))
+        }
+        (UnaryOperator::Minus, DataValue::Int64(value)) => {
+            Some(DataValue::Int64(value.wrapping_neg()))
+        }
+        (UnaryOperator::Minus, DataValue::Decimal(value)) => Some(DataValue::Decimal(-value)),
+        (UnaryOperator::Minus, DataValue::Double(value)) => Some(DataValue::Double(-value)),
+        (UnaryOperator::Not, DataValue::Boolean(value)) => Some(DataValue::Boolean(!value)),
+        (UnaryOperator::BitwiseComplement, DataValue::Boolean(value)) => {
+            Some(DataValue::Boolean(!value))
+        }
+        (UnaryOperator::BitwiseComplement, DataValue::Int32(value)) => {
+            Some(DataValue::Int32(!value))
+        }
+        (UnaryOperator::BitwiseComplement, DataValue::Int64(value)) => {
+            Some(DataValue::Int64(!value))
+        }
+        _ => None,
+    };
+
+    match result {
+        Some(result) => Ok(Some(result)),
+        None => Err(format!(
+            "cannot apply unary {:?} operator to {} value",
+            operator,
+            value.data_type()
+        )
+        .into()),
+    }
+}
+
+fn boolean_operand(
+    value: Option<DataValue>,
+    operator: OperatorType,
+) -> Result<Option<bool>, Box<dyn Error>> {
+    match value {
+        None => Ok(None),
+        Some(DataValue::Boolean(value)) => Ok(Some(value)),
+        Some(value) => Err(format!(
+            "cannot apply {:?} operator to {} value, Boolean expected",
+            operator,
+            value.data_type()
+        )
+        .into()),
+    }
+}
+
+fn evaluate_operator(
+    operator: OperatorType,
+    left: Option<DataValue>,
+    right: Option<DataValue>,
+) -> EvaluationResult {
+    // Logical operators use three-valued logic where NULL represents an unknown value
+    match operator {
+        OperatorType::And => {
+            let left = boolean_operand(left, operator)?;
+            let right = boolean_operand(right, operator)?;
+
+            return Ok(match (left, right) {
+                (Some(false), _) | (_, Some(false)) => Some(DataValue::Boolean(false)),
+                (Some(true), Some(true)) => Some(DataValue::Boolean(true)),
+                _ => None,
+            });
+        }
+        OperatorType::Or => {
+            let left = boolean_operand(left, operator)?;
+            let right = boolean_operand(right, operator)?;
+
+            return Ok(match (left, right) {
+                (Some(true), _) | (_, Some(true)) => Some(DataValue::Boolean(true)),
+                (Some(false), Some(false)) => Some(DataValue::Boolean(false)),
+                _ => None,
+            });
+        }
+        _ => {}
+    }
+
+    let (left, right) = match (left, right) {
+        (Some(left), Some(right)) => (left, right),
+        _ => return Ok(None),
+    };
+
+    let result = match operator {
+        OperatorType::Multiply
+        | OperatorType::Divide
+        | OperatorType::Modulus
+        | OperatorType::Add
+        | OperatorType::Subtract => evaluate_arithmetic(operator, &left, &right)?,
+        OperatorType::BitShiftLeft
+        | OperatorType::BitShiftRight
+        | OperatorType::BitwiseAnd
+        | OperatorType::BitwiseOr
+        | OperatorType::BitwiseXor => evaluate_bitwise(operator, &left, &right)?,
+        OperatorType::LessThan => DataValue::Boolean(compare(&left, &right, false)?.is_lt()),
+        OperatorType::LessThanOrEqual => DataValue::Boolean(compare(&left, &right, false)?.is_le()),
+        OperatorType::GreaterThan => DataValue::Boolean(compare(&left, &right, false)?.is_gt()),
+        OperatorType::GreaterThanOrEqual => {
+            DataValue::Boolean(compare(&left, &right, false)?.is_ge())
+        }
+        OperatorType::Equal => DataValue::Boolean(compare(&left, &right, false)?.is_eq()),
+        OperatorType::EqualExactMatch => DataValue::Boolean(compare(&left, &right, true)?.is_eq()),
+        OperatorType::NotEqual => DataValue::Boolean(compare(&left, &right, false)?.is_ne()),
+        OperatorType::NotEqualExactMatch => {
+            DataValue::Boolean(compare(&left, &right, true)?.is_ne())
+        }
+        OperatorType::Like => DataValue::Boolean(evaluate_like(&left, &right, false)?),
+        OperatorType::LikeExactMatch => DataValue::Boolean(evaluate_like(&left, &right, true)?),
+        OperatorType::NotLike => DataValue::Boolean(!evaluate_like(&left, &right, false)?),
+        OperatorType::NotLikeExactMatch => DataValue::Boolean(!evaluate_like(&left, &right, true)?),
+        OperatorType::And | OperatorType::Or => unreachable!(),
+    };
+
+    Ok(Some(result))
+}
+
+fn evaluate_arithmetic(
+    operator: OperatorType,
+    left: &DataValue,
+    right: &DataValue,
+) -> Result<DataValue, Box<dyn Error>> {
+    // Addition with a string operand is a concatenation
+    if operator == OperatorType::Add
+        && (matches!(left, DataValue::String(_)) || matches!(right, DataValue::String(_)))
+    {
+        return Ok(DataValue::String(format!("{}{}", left, right)));
+    }
+
+    let invalid_operands = || -> Box<dyn Error> {
+        format!(
+            "cannot apply {:?} operator to {} and {} values",
+            operator,
+            left.data_type(),
+            right.data_type()
+        )
+        .into()
+    };
+
+    let left = normalize(left);
+    let right = normalize(right);
+
+    let rank = match (numeric_rank(&left), numeric_rank(&right)) {
+        (Some(left_rank), Some(right_rank)) if left_rank > 0 && right_rank > 0 => {
+            left_rank.max(right_rank)
+        }
+        _ => return Err(invalid_operands()),
+    };
+
+    let data_type = rank_data_type(rank);
+    let is_zero = to_f64(&right) == Some(0.0);
+
+    if data_type != DataType::Double
+        && is_zero
+        && matches!(operator, OperatorType::Divide | OperatorType::Modulus)
+    {
+        return Err("division by zero".into());
+    }
+
+    Ok(
+        match (convert(&left, data_type)?, convert(&right, data_type)?) {
+            (DataValue::Int32(left), DataValue::Int32(right)) => DataValue::Int32(match operator {
+                OperatorType::Multiply => left.wrapping_mul(right),
+                OperatorType::Divide => left.wrapping_div(right),
+                OperatorType::Modulus => left.wrapping_rem(right),
+                OperatorType::Add => left.wrapping_add(right),
+                _ => left.wrapping_sub(right),
+            }),
+            (DataValue::Int64(left), DataValue::Int64(right)) => DataValue::Int64(match operator {
+                OperatorType::Multiply => left.wrapping_mul(right),
+                OperatorType::Divide => left.wrapping_div(right),
+                OperatorType::Modulus => left.wrapping_rem(right),
+                OperatorType::Add => left.wrapping_add(right),
+                _ => left.wrapping_sub(right),
+            }),
+            (DataValue::Decimal(left), DataValue::Decimal(right)) => DataValue::Decimal(
+                match operator {
+                    OperatorType::Multiply => left.checked_mul(right),
+                    OperatorType::Divide => left.checked_div(right),
+                    OperatorType::Modulus => left.checked_rem(right),
+                    OperatorType::Add => left.checked_add(right),
+                    _ => left.checked_sub(right),
+                }
+                .ok_or("Decimal arithmetic overflow")?,
+            ),
+            (DataValue::Double(left), DataValue::Double(right)) => {
+                DataValue::Double(match operator {
+                    OperatorType::Multiply => left * right,
+                    OperatorType::Divide => left / right,
+                    OperatorType::Modulus => left % right,
+                    OperatorType::Add => left + right,
+                    _ => left - right,
+                })
+            }
+            _ => return Err(invalid_operands()),
+        },
+    )
+}
+
+fn evaluate_bitwise(
+    operator: OperatorType,
+    left: &DataValue,
+    right: &DataValue,
+) -> Result<DataValue, Box<dyn Error>> {
+    let left = normalize(left);
+    let right = normalize(right);
+
+    let invalid_operands = || -> Box<dyn Error> {
+        format!(
+            "cannot apply {:?} operator to {} and {} values",
+            operator,
+            left.data_type(),
+            right.data_type()
+        )
+        .into()
+    };
+
+    // Shift operations retain the type of the left operand
+    if matches!(
+        operator,
+        OperatorType::BitShiftLeft | OperatorType::BitShiftRight
+    ) {
+        let shift = match right {
+            DataValue::Int32(_) | DataValue::Int64(_) => to_i64(&right).unwrap() as u32,
+            _ => return Err(invalid_operands()),
+        };
+
+        return Ok(match (&left, operator) {
+            (DataValue::Int32(value), OperatorType::BitShiftLeft) => {
+                DataValue::Int32(value.wrapping_shl(shift))
+            }
+            (DataValue::Int32(value), _) => DataValue::Int32(value.wrapping_shr(shift)),
+            (DataValue::Int64(value), OperatorType::BitShiftLeft) => {
+                DataValue::Int64(value.wrapping_shl(shift))
+            }
+            (DataValue::Int64(value), _) => DataValue::Int64(value.wrapping_shr(shift)),
+            _ => return Err(invalid_operands()),
+        });
+    }
+
+    Ok(match (&left, &right) {
+        (DataValue::Boolean(left), DataValue::Boolean(right)) => {
+            DataValue::Boolean(match operator {
+                OperatorType::BitwiseAnd => left & right,
+                OperatorType::BitwiseOr => left | right,
+                _ => left ^ right,
+            })
+        }
+        (DataValue::Int32(left), DataValue::Int32(right)) => DataValue::Int32(match operator {
+            OperatorType::BitwiseAnd => left & right,
+            OperatorType::BitwiseOr => left | right,
+            _ => left ^ right,
+        }),
+        (DataValue::Int32(_) | DataValue::Int64(_), DataValue::Int32(_) | DataValue::Int64(_)) => {
+            let left = to_i64(&left).unwrap();
+            let right = to_i64(&right).unwrap();
+
+            DataValue::Int64(match operator {
+                OperatorType::BitwiseAnd => left & right,
+                OperatorType::BitwiseOr => left | right,
+                _ => left ^ right,
+            })
+        }
+        _ => return Err(invalid_operands()),
+    })
+}
+
+// Evaluates a LIKE pattern where "%" or "*" match any sequence of characters
+fn evaluate_like(
+    value: &DataValue,
+    pattern: &DataValue,
+    exact_match: bool,
+) -> Result<bool, Box<dyn Error>> {
+    let pattern = match pattern {
+        DataValue::String(pattern) => pattern,
+        _ => {
+            return Err(format!(
+                "LIKE pattern must be a String value, received {}",
+                pattern.data_type()
+            )
+            .into())
+        }
+    };
+
+    let mut expression = String::from("^");
+
+    for char in pattern.chars() {
+        match char {
+            '%' | '*' => expression.push_str(".*"),
+            _ => expression.push_str(&regex::escape(char.encode_utf8(&mut [0u8; 4]))),
+        }
+    }
+
+    expression.push('$');
+
+    let regex = RegexBuilder::new(&expression)
+        .case_insensitive(!exact_match)
+        .dot_matches_new_line(true)
+        .build()?;
+
+    Ok(regex.is_match(&value.to_string()))
+}
+
+fn evaluate_function(
+    function: FunctionType,
+    arguments: &[Expression],
+    row: Option<&DataRow>,
+) -> EvaluationResult {
+    // Functions that conditionally evaluate their arguments
+    match function {
+        FunctionType::Coalesce => {
+            for argument in arguments {
+                if let Some(value) = evaluate(argument, row)? {
+                    return Ok(Some(value));
+                }
+            }
+
+            return Ok(None);
+        }
+        FunctionType::IIf => {
+            let test = boolean_argument(function, evaluate(&arguments[0], row)?.as_ref())?;
+            return evaluate(&arguments[if test { 1 } else { 2 }], row);
+        }
+        FunctionType::IsNull => {
+            return match evaluate(&arguments[0], row)? {
+                Some(value) => Ok(Some(value)),
+                None => evaluate(&arguments[1], row),
+            };
+        }
+        _ => {}
+    }
+
+    let values = arguments
+        .iter()
+        .map(|argument| evaluate(argument, row))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    evaluate_function_values(function, &values)
+}
+
+fn evaluate_function_values(
+    function: FunctionType,
+    values: &[Option<DataValue>],
+) -> EvaluationResult {
+    // Optional ignore case argument for string functions
+    let ignore_case = |index: usize| -> Result<bool, Box<dyn Error>> {
+        match values.get(index) {
+            Some(value) => boolean_argument(function, value.as_ref()),
+            None => Ok(false),
+        }
+    };
+
+    match function {
+        FunctionType::Now => {
+            return Ok(Some(DataValue::DateTime(
+                Local::now().naive_local().and_utc(),
+            )))
+        }
+        FunctionType::UtcNow => return Ok(Some(DataValue::DateTime(Utc::now()))),
+        FunctionType::IsDate
+        | FunctionType::IsInteger
+        | FunctionType::IsGuid
+        | FunctionType::IsNumeric => {
+            return Ok(Some(DataValue::Boolean(is_type(
+                function,
+                values[0].as_ref(),
+            ))))
+        }
+        FunctionType::MaxOf | FunctionType::MinOf => {
+            let mut result: Option<DataValue> = None;
+
+            for value in values.iter().flatten() {
+                let replace = match &result {
+                    None => true,
+                    Some(current) => {
+                        let ordering = compare(value, current, false)?;
+
+                        if function == FunctionType::MaxOf {
+                            ordering.is_gt()
+                        } else {
+                            ordering.is_lt()
+                        }
+                    }
+                };
+
+                if replace {
+                    result = Some(normalize(value));
+                }
+            }
+
+            return Ok(result);
+        }
+        _ => {}
+    }
+
+    // Remaining functions return NULL when the source argument is NULL
+    let source = match &values[0] {
+        Some(source) => source,
+        None => return Ok(None),
+    };
+
+    let value = match function {
+        FunctionType::Abs | FunctionType::Ceiling | FunctionType::Floor | FunctionType::Round => {
+            evaluate_rounding(function, numeric_argument(function, source)?)
+        }
+        FunctionType::Sqrt => DataValue::Double(double_argument(function, source)?.sqrt()),
+        FunctionType::Power => {
+            let exponent = match &values[1] {
+                Some(exponent) => double_argument(function, exponent)?,
+                None => return Ok(None),
+            };
+
+            DataValue::Double(double_argument(function, source)?.powf(exponent))
+        }
+        FunctionType::Convert => {
+            let type_name = string_argument(function, values[1].as_ref())?;
+            normalize(&convert(source, parse_type_name(&type_name)?)?)
+        }
+        FunctionType::Contains | FunctionType::StartsWith | FunctionType::EndsWith => {
+            let ignore_case = ignore_case(2)?;
+            let source = case(&source.to_string(), ignore_case);
+            let test = case(&string_argument(function, values[1].as_ref())?, ignore_case);
+
+            DataValue::Boolean(match function {
+                FunctionType::Contains => source.contains(&test),
+                FunctionType::StartsWith => source.starts_with(&test),
+                _ => source.ends_with(&test),
+            })
+        }
+        FunctionType::IndexOf | FunctionType::LastIndexOf | FunctionType::StrCount => {
+            let indexes = find_all(
+                &source.to_string(),
+                &string_argument(function, values[1].as_ref())?,
+                ignore_case(2)?,
+            );
+
+            DataValue::Int32(match function {
+                FunctionType::IndexOf => indexes.first().map_or(-1, |index| *index as i32),
+                FunctionType::LastIndexOf => indexes.last().map_or(-1, |index| *index as i32),
+                _ => indexes.len() as i32,
+            })
+        }
+        FunctionType::NthIndexOf => {
+            let indexes = find_all(
+                &source.to_string(),
+                &string_argument(function, values[1].as_ref())?,
+                ignore_case(3)?,
+            );
+
+            let nth = integer_argument(function, values[2].as_ref())?;
+
+            DataValue::Int32(match usize::try_from(nth - 1) {
+                Ok(nth) => indexes.get(nth).map_or(-1, |index| *index as i32),
+                Err(_) => -1,
+            })
+        }
+        FunctionType::StrCmp => {
+            let ignore_case = ignore_case(2)?;
+            let left = case(&source.to_string(), ignore_case);
+            let right = case(&string_argument(function, values[1].as_ref())?, ignore_case);
+
+            DataValue::Int32(match left.cmp(&right) {
+                Ordering::Less => -1,
+                Ordering::Equal => 0,
+                Ordering::Greater => 1,
+            })
+        }
+        FunctionType::Replace => {
+            let test = string_argument(function, values[1].as_ref())?;
+            let replacement = string_argument(function, values[2].as_ref())?;
+
+            DataValue::String(
+                literal_regex(&test, ignore_case(3)?)?
+                    .replace_all(&source.to_string(), NoExpand(&replacement))
+                    .into_owned(),
+            )
+        }
+        FunctionType::Split => {
+            let delimiter = string_argument(function, values[1].as_ref())?;
+            let index = integer_argument(function, values[2].as_ref())?;
+            let source = source.to_string();
+
+            match usize::try_from(index) {
+                Ok(index) => match literal_regex(&delimiter, ignore_case(3)?)?
+                    .split(&source)
+                    .nth(index)
+                {
+                    Some(value) => DataValue::String(value.to_string()),
+                    None => return Ok(None),
+                },
+                Err(_) => return Ok(None),
+            }
+        }
+        FunctionType::SubStr => {
+            let index = integer_argument(function, values[1].as_ref())?.max(0) as usize;
+            let source = source.to_string();
+            let chars = source.chars().skip(index);
+
+            DataValue::String(match values.get(2) {
+                Some(length) => chars
+                    .take(integer_argument(function, length.as_ref())?.max(0) as usize)
+                    .collect(),
+                None => chars.collect(),
+            })
+        }
+        FunctionType::Len => DataValue::Int32(source.to_string().chars().count() as i32),
+        FunctionType::Lower => DataValue::String(source.to_string().to_lowercase()),
+        FunctionType::Upper => DataValue::String(source.to_string().to_uppercase()),
+        FunctionType::Trim => DataValue::String(source.to_string().trim().to_string()),
+        FunctionType::TrimLeft => DataValue::String(source.to_string().trim_start().to_string()),
+        FunctionType::TrimRight => DataValue::String(source.to_string().trim_end().to_string()),
+        FunctionType::Reverse => DataValue::String(source.to_string().chars().rev().collect()),
+        FunctionType::RegExMatch | FunctionType::RegExVal => {
+            let regex = Regex::new(&source.to_string())?;
+
+            let test = match &values[1] {
+                Some(test) => test.to_string(),
+                None => return Ok(None),
+            };
+
+            if function == FunctionType::RegExMatch {
+                DataValue::Boolean(regex.is_match(&test))
+            } else {
+                match regex.find(&test) {
+                    Some(found) => DataValue::String(found.as_str().to_string()),
+                    None => return Ok(None),
+                }
+            }
+        }
+        FunctionType::DateAdd => {
+            let datetime = datetime_argument(function, source)?;
+            let value = integer_argument(function, values[1].as_ref())?;
+            let interval = interval_argument(function, values[2].as_ref())?;
+
+            DataValue::DateTime(date_add(datetime, value, interval).ok_or_else(|| {
+                format!(
+                    "DateAdd function result is out of range for {:?} interval",
+                    interval
+                )
+            })?)
+        }
+        FunctionType::DateDiff => {
+            let left = datetime_argument(function, source)?;
+
+            let right = match &values[1] {
+                Some(right) => datetime_argument(function, right)?,
+                None => return Ok(None),
+            };
+
+            let interval = interval_argument(function, values[2].as_ref())?;
+
+            DataValue::Int32(date_diff(left, right, interval) as i32)
+        }
+        FunctionType::DatePart => {
+            let datetime = datetime_argument(function, source)?;
+            let interval = interval_argument(function, values[1].as_ref())?;
+
+            DataValue::Int32(match interval {
+                TimeInterval::Year => datetime.year(),
+                TimeInterval::Month => datetime.month() as i32,
+                TimeInterval::DayOfYear => datetime.ordinal() as i32,
+                TimeInterval::Day => datetime.day() as i32,
+                TimeInterval::Week => datetime.iso_week().week() as i32,
+                TimeInterval::WeekDay => datetime.weekday().num_days_from_sunday() as i32,
+                TimeInterval::Hour => datetime.hour() as i32,
+                TimeInterval::Minute => datetime.minute() as i32,
+                TimeInterval::Second => datetime.second() as i32,
+                TimeInterval::Millisecond => datetime.timestamp_subsec_millis() as i32,
+            })
+        }
+        FunctionType::Coalesce
+        | FunctionType::IIf
+        | FunctionType::IsNull
+        | FunctionType::Now
+        | FunctionType::UtcNow
+        | FunctionType::IsDate
+        | FunctionType::IsInteger
+        | FunctionType::IsGuid
+        | FunctionType::IsNumeric
+        | FunctionType::MaxOf
+        | FunctionType::MinOf => unreachable!(),
+    };
+
+    Ok(Some(value))
+}
+
+fn is_type(function: FunctionType, value: Option<&DataValue>) -> bool {
+    let value = match value {
+        Some(value) => normalize(value),
+        None => return false,
+    };
+
+    let (data_type, text) = match (function, &value) {
+        (FunctionType::IsDate, DataValue::DateTime(_))
+        | (FunctionType::IsGuid, DataValue::Guid(_))
+        | (FunctionType::IsInteger, DataValue::Int32(_) | DataValue::Int64(_))
+        | (
+            FunctionType::IsNumeric,
+            DataValue::Int32(_)
+            | DataValue::Int64(_)
+            | DataValue::Decimal(_)
+            | DataValue::Double(_),
+        ) => return true,
+        (FunctionType::IsDate, DataValue::String(text)) => (DataType::DateTime, text),
+        (FunctionType::IsGuid, DataValue::String(text)) => (DataType::Guid, text),
+        (FunctionType::IsInteger, DataValue::String(text)) => (DataType::Int64, text),
+        (FunctionType::IsNumeric, DataValue::String(text)) => (DataType::Double, text),
+        _ => return false,
+    };
+
+    DataValue::parse(data_type, text).is_ok()
+}
+
+fn evaluate_rounding(function: FunctionType, value: DataValue) -> DataValue {
+    match value {
+        DataValue::Int32(value) if function == FunctionType::Abs => {
+            DataValue::Int32(value.wrapping_abs())
+        }
+        DataValue::Int64(value) if function == FunctionType::Abs => {
+            DataValue::Int64(value.wrapping_abs())
+        }
+        DataValue::Decimal(value) => DataValue::Decimal(match function {
+            FunctionType::Abs => value.abs(),
+            FunctionType::Ceiling => value.ceil(),
+            FunctionType::Floor => value.floor(),
+            _ => value.round(),
+        }),
+        DataValue::Double(value) => DataValue::Double(match function {
+            FunctionType::Abs => value.abs(),
+            FunctionType::Ceiling => value.ceil(),
+            FunctionType::Floor => value.floor(),
+            _ => value.round_ties_even(),
+        }),
+        // Integer values are already rounded
+        value => value,
+    }
+}
+
+fn invalid_argument(
+    function: FunctionType,
+    expected: &str,
+    value: Option<&DataValue>,
+) -> Box<dyn Error> {
+    match value {
+        Some(value) => format!(
+            "{:?} function expects {} argument, received {} value \"{}\"",
+            function,
+            expected,
+            value.data_type(),
+            value
+        ),
+        None => format!(
+            "{:?} function expects {} argument, received NULL",
+            function, expected
+        ),
+    }
+    .into()
+}
+
+fn numeric_argument(
+    function: FunctionType,
+    value: &DataValue,
+) -> Result<DataValue, Box<dyn Error>> {
+    match normalize(value) {
+        DataValue::String(text) => DataValue::parse(DataType::Double, &text)
+            .map_err(|_| invalid_argument(function, "a numeric", Some(value))),
+        value @ (DataValue::Int32(_)
+        | DataValue::Int64(_)
+        | DataValue::Decimal(_)
+        | DataValue::Double(_)) => Ok(value),
+        _ => Err(invalid_argument(function, "a numeric", Some(value))),
+    }
+}
+
+fn double_argument(function: FunctionType, value: &DataValue) -> Result<f64, Box<dyn Error>> {
+    Ok(to_f64(&numeric_argument(function, value)?).unwrap_or(f64::NAN))
+}
+
+fn integer_argument(
+    function: FunctionType,
+    value: Option<&DataValue>,
+) -> Result<i64, Box<dyn Error>> {
+    match value.map(|value| convert(value, DataType::Int64)) {
+        Some(Ok(DataValue::Int64(value))) => Ok(value),
+        _ => Err(invalid_argument(function, "an integer", value)),
+    }
+}
+
+fn boolean_argument(
+    function: FunctionType,
+    value: Option<&DataValue>,
+) -> Result<bool, Box<dyn Error>> {
+    match value.map(|value| convert(value, DataType::Boolean)) {
+        None => Ok(false),
+        Some(Ok(DataValue::Boolean(value))) => Ok(value),
+        _ => Err(invalid_argument(function, "a Boolean", value)),
+    }
+}
+
+fn string_argument(
+    function: FunctionType,
+    value: Option<&DataValue>,
+) -> Result<String, Box<dyn Error>> {
+    match value {
+        Some(value) => Ok(value.to_string()),
+        None => Err(invalid_argument(function, "a String", value)),
+    }
+}
+
+fn datetime_argument(
+    function: FunctionType,
+    value: &DataValue,
+) -> Result<DateTime<Utc>, Box<dyn Error>> {
+    match convert(value, DataType::DateTime) {
+        Ok(DataValue::DateTime(value)) => Ok(value),
+        _ => Err(invalid_argument(function, "a DateTime", Some(value))),
+    }
+}
+
+fn interval_argument(
+    function: FunctionType,
+    value: Option<&DataValue>,
+) -> Result<TimeInterval, Box<dyn Error>> {
+    let name = string_argument(function, value)?;
+
+    TIME_INTERVALS
+        .iter()
+        .find(|interval| format!("{:?}", interval).eq_ignore_ascii_case(&name))
+        .copied()
+        .ok_or_else(|| invalid_argument(function, "a time interval", value))
+}
+
+// Parses a type name, e.g., "System.Int32" or "Int32", as used by the Convert function
+fn parse_type_name(type_name: &str) -> Result<DataType, Box<dyn Error>> {
+    let name = match type_name.get(..7) {
+        Some(prefix) if prefix.eq_ignore_ascii_case("System.") => &type_name[7..],
+        _ => type_name,
+    };
+
+    Ok(match name.to_ascii_lowercase().as_str() {
+        "string" => DataType::String,
+        "boolean" | "bool" => DataType::Boolean,
+        "datetime" => DataType::DateTime,
+        "single" => DataType::Single,
+        "double" => DataType::Double,
+        "decimal" => DataType::Decimal,
+        "guid" => DataType::Guid,
+        "sbyte" | "int8" => DataType::Int8,
+        "int16" => DataType::Int16,
+        "int32" => DataType::Int32,
+        "int64" => DataType::Int64,
+        "byte" | "uint8" => DataType::UInt8,
+        "uint16" => DataType::UInt16,
+        "uint32" => DataType::UInt32,
+        "uint64" => DataType::UInt64,
+        _ => {
+            return Err(format!(
+                "Convert function target type \"{}\" is not supported",
+                type_name
+            )
+            .into())
+        }
+    })
+}
+
+fn case(value: &str, ignore_case: bool) -> String {
+    if ignore_case {
+        value.to_lowercase()
+    } else {
+        value.to_string()
+    }
+}
+
+// Gets character indexes of all non-overlapping occurrences of test in source
+fn find_all(source: &str, test: &str, ignore_case: bool) -> Vec<usize> {
+    if test.is_empty() {
+        return Vec::new();
+    }
+
+    let source = case(source, ignore_case);
+    let test = case(test, ignore_case);
+
+    source
+        .match_indices(&test)
+        .map(|(index, _)| source[..index].chars().count())
+        .collect()
+}
+
+fn literal_regex(value: &str, ignore_case: bool) -> Result<Regex, Box<dyn Error>> {
+    Ok(RegexBuilder::new(&regex::escape(value))
+        .case_insensitive(ignore_case)
+        .build()?)
+}
+
+fn date_add(datetime: DateTime<Utc>, value: i64, interval: TimeInterval) -> Option<DateTime<Utc>> {
+    let add_months = |months: i64| {
+        let count = Months::new(u32::try_from(months.unsigned_abs()).ok()?);
+
+        if months < 0 {
+            datetime.checked_sub_months(count)
+        } else {
+            datetime.checked_add_months(count)
+        }
+    };
+
+    match interval {
+        TimeInterval::Year => add_months(value.checked_mul(12)?),
+        TimeInterval::Month => add_months(value),
+        TimeInterval::DayOfYear | TimeInterval::Day | TimeInterval::WeekDay => {
+            datetime.checked_add_signed(Duration::try_days(value)?)
+        }
+        TimeInterval::Week => datetime.checked_add_signed(Duration::try_weeks(value)?),
+        TimeInterval::Hour => datetime.checked_add_signed(Duration::try_hours(value)?),
+        TimeInterval::Minute => datetime.checked_add_signed(Duration::try_minutes(value)?),
+        TimeInterval::Second => datetime.checked_add_signed(Duration::try_seconds(value)?),
+        TimeInterval::Millisecond => {
+            datetime.checked_add_signed(Duration::try_milliseconds(value)?)
+        }
+    }
+}
+
+fn date_diff(left: DateTime<Utc>, right: DateTime<Utc>, interval: TimeInterval) -> i64 {
+    let duration = right - left;
+
+    match interval {
+        TimeInterval::Year => (right.year() - left.year()) as i64,
+        TimeInterval::Month => {
+            (right.year() - left.year()) as i64 * 12 + right.month() as i64 - left.month() as i64
+        }
+        TimeInterval::DayOfYear | TimeInterval::Day | TimeInterval::WeekDay => duration.num_days(),
+        TimeInterval::Week => duration.num_weeks(),
+        TimeInterval::Hour => duration.num_hours(),
+        TimeInterval::Minute => duration.num_minutes(),
+        TimeInterval::Second => duration.num_seconds(),
+        TimeInterval::Millisecond => duration.num_milliseconds(),
+    }
+}
@@ -0,0 +1,1416 @@
+//******************************************************************************************************
+//  filter_expression_parser.rs - Gbtc
+//
+//  Copyright © 2023, Grid Protection Alliance.  All Rights Reserved.
+//
+//  Licensed to the Grid Protection Alliance (GPA) under one or more contributor license agreements. See
+//  the NOTICE file distributed with this work for additional information regarding copyright ownership.
+//  The GPA licenses this file to you under the MIT License (MIT), the "License"; you may not use this
+//  file except in compliance with the License. You may obtain a copy of the License at:
+//
+//      http://opensource.org/licenses/MIT
+//
+//  Unless agreed to in writing, the subject software distributed under the License is distributed on an
+//  "AS-IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied. Refer to the
+//  License for the specific language governing permissions and limitations.
+//
+//  Code Modification History:
+//  ----------------------------------------------------------------------------------------------------
+//  04/01/2023 - J. Ritchie Carroll
+//       Generated original version of source code.
+//
+//******************************************************************************************************
+
+use crate::data::expression::{Expression, FunctionType, OperatorType, UnaryOperator};
+use crate::data::expression_tree::{bind, compare_nullable, evaluate};
+use crate::data::{DataRow, DataSet, DataTable, DataType, DataValue};
+use rust_decimal::Decimal;
+use std::collections::HashSet;
+use std::error::Error;
+use std::str::FromStr;
+use uuid::Uuid;
+
+/// Defines the default name of the table used for identifier and expression statements.
+pub const DEFAULT_PRIMARY_TABLE_NAME: &str = "ActiveMeasurements";
+
+/// Defines the field names of a table used to resolve identifier statements, i.e., signal ID Guid,
+/// measurement key and point tag literals, to the rows of a table.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TableIdFields {
+    /// Defines the name of the field that contains the signal ID Guid of a row.
+    pub signal_id_field_name: String,
+
+    /// Defines the name of the field that contains the measurement key, e.g., "PPA:12", of a row.
+    pub measurement_key_field_name: String,
+
+    /// Defines the name of the field that contains the point tag of a row.
+    pub point_tag_field_name: String,
+}
+
+impl Default for TableIdFields {
+    fn default() -> Self {
+        TableIdFields {
+            signal_id_field_name: "SignalID".to_string(),
+            measurement_key_field_name: "ID".to_string(),
+            point_tag_field_name: "PointTag".to_string(),
+        }
+    }
+}
+
+// Defines the tokens of the filter expression syntax
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Semicolon,
+    Comma,
+    LeftParen,
+    RightParen,
+    Symbol(&'static str),
+    Keyword(&'static str),
+    Identifier(String),
+    Integer(String),
+    Numeric(String),
+    String(String),
+    DateTime(String),
+    Guid(Uuid),
+    MeasurementKey(String),
+    PointTag(String),
+    Boolean(bool),
+}
+
+// Symbols ordered so that longer symbols are matched first
+const SYMBOLS: [&str; 25] = [
+    "===", "!==", "==", "!=", "<>", "<=", ">=", "<<", ">>", "&&", "||", "<", ">", "=", "!", "~",
+    "&", "|", "^", "*", "/", "%", "+", "-", ":",
+];
+
+const KEYWORDS: [&str; 16] = [
+    "FILTER", "TOP", "WHERE", "ORDER", "BY", "ASC", "DESC", "AND", "OR", "NOT", "IN", "IS", "NULL",
+    "LIKE", "BINARY", "XOR",
+];
+
+// Identifier statements select rows of the primary table by a unique key
+#[derive(Debug, Clone, PartialEq)]
+enum IdentifierStatement {
+    SignalId(Uuid),
+    MeasurementKey(String),
+    PointTag(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct OrderingTerm {
+    column_name: String,
+    descending: bool,
+    exact_match: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct FilterStatement {
+    table_name: String,
+    top_limit: Option<i64>,
+    where_expression: Expression,
+    ordering_terms: Vec<OrderingTerm>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Statement {
+    Identifier(IdentifierStatement),
+    Filter(FilterStatement),
+    Expression(Expression),
+}
+
+/// Represents a parser for STTP filter expressions, e.g.:
+///
+/// * `FILTER ActiveMeasurements WHERE SignalType = 'FREQ'`
+/// * `FILTER TOP 10 ActiveMeasurements WHERE Device LIKE 'SHELBY%' ORDER BY PointTag DESC`
+/// * `{E4BBFE6A-35BD-4E5B-92C9-11FF913E7877}; PPA:12; "SHELBY-FQ"`
+///
+/// Multiple statements can be separated by semicolons. Parsing validates the expression syntax,
+/// evaluating the statements against a `DataSet` selects the matching rows and signal IDs.
+#[derive(Debug, Clone)]
+pub struct FilterExpressionParser {
+    statements: Vec<Statement>,
+
+    /// Defines the name of the table used for identifier and expression statements.
+    pub primary_table_name: String,
+
+    /// Defines the field names used to resolve identifier statements.
+    pub table_id_fields: TableIdFields,
+}
+
+// Tracks selected rows, by table and row index, and their signal IDs in selection order
+#[derive(Default)]
+struct Selection {
+    rows: Vec<(usize, usize)>,
+    row_set: HashSet<(usize, usize)>,
+    signal_ids: Vec<Uuid>,
+    signal_id_set: HashSet<Uuid>,
+}
+
+impl Selection {
+    fn add_signal_id(&mut self, signal_id: Uuid) {
+        if self.signal_id_set.insert(signal_id) {
+            self.signal_ids.push(signal_id);
+        }
+    }
+}
+
+impl FilterExpressionParser {
+    /// Creates a new `FilterExpressionParser` for the specified filter expression. An error is
+    /// returned if the filter expression is not syntactically valid.
+    pub fn new(filter_expression: &str) -> Result<FilterExpressionParser, Box<dyn Error>> {
+        let tokens = tokenize(filter_expression)?;
+        let statements = Parser {
+            tokens,
+            position: 0,
+        }
+        .parse_statements()?;
+
+        Ok(FilterExpressionParser {
+            statements,
+            primary_table_name: DEFAULT_PRIMARY_TABLE_NAME.to_string(),
+            table_id_fields: TableIdFields::default(),
+        })
+    }
+
+    /// Gets the number of statements in the parsed filter expression.
+    pub fn statement_count(&self) -> usize {
+        self.statements.len()
+    }
+
+    /// Evaluates the filter expression against the specified `DataSet` returning the selected
+    /// rows, without duplicates, in the order selected.
+    pub fn filtered_rows<'a>(
+        &self,
+        data_set: &'a DataSet,
+    ) -> Result<Vec<&'a DataRow>, Box<dyn Error>> {
+        let selection = self.evaluate(data_set)?;
+
+        Ok(selection
+            .rows
+            .iter()
+            .map(|(table_index, row_index)| &data_set.tables()[*table_index].rows()[*row_index])
+            .collect())
+    }
+
+    /// Evaluates the filter expression against the specified `DataSet` returning the signal IDs
+    /// of the selected rows, without duplicates, in the order selected. Signal ID literals are
+    /// included even when they are not defined in the primary table.
+    pub fn filtered_signal_ids(&self, data_set: &DataSet) -> Result<Vec<Uuid>, Box<dyn Error>> {
+        Ok(self.evaluate(data_set)?.signal_ids)
+    }
+
+    fn evaluate(&self, data_set: &DataSet) -> Result<Selection, Box<dyn Error>> {
+        let mut selection = Selection::default();
+
+        for statement in &self.statements {
+            match statement {
+                Statement::Identifier(identifier) => {
+                    self.select_identifier(data_set, identifier, &mut selection)?
+                }
+                Statement::Filter(filter) => {
+                    let table_index = table_index(data_set, &filter.table_name)?;
+                    let table = &data_set.tables()[table_index];
+
+                    for row_index in select_rows(table, filter)? {
+                        self.add_row(data_set, table_index, row_index, &mut selection);
+                    }
+                }
+                Statement::Expression(expression) => {
+                    let table_index = table_index(data_set, &self.primary_table_name)?;
+                    let table = &data_set.tables()[table_index];
+
+                    let filter = FilterStatement {
+                        table_name: self.primary_table_name.clone(),
+                        top_limit: None,
+                        where_expression: expression.clone(),
+                        ordering_terms: Vec::new(),
+                    };
+
+                    for row_index in select_rows(table, &filter)? {
+                        self.add_row(data_set, table_index, row_index, &mut selection);
+                    }
+                }
+            }
+        }
+
+        Ok(selection)
+    }
+
+    fn select_identifier(
+        &self,
+        data_set: &DataSet,
+        identifier: &IdentifierStatement,
+        selection: &mut Selection,
+    ) -> Result<(), Box<dyn Error>> {
+        let table_index = data_set
+            .tables()
+            .iter()
+            .position(|table| table.name().eq_ignore_ascii_case(&self.primary_table_name));
+
+        // Signal IDs are selected even when the primary table is not available
+        let table_index = match (table_index, identifier) {
+            (Some(table_index), _) => table_index,
+            (None, IdentifierStatement::SignalId(signal_id)) => {
+                selection.add_signal_id(*signal_id);
+                return Ok(());
+            }
+            (None, _) => return Err(table_not_found(&self.primary_table_name)),
+        };
+
+        let table = &data_set.tables()[table_index];
+
+        let (field_name, key) = match identifier {
+            IdentifierStatement::SignalId(signal_id) => (
+                &self.table_id_fields.signal_id_field_name,
+                DataValue::Guid(*signal_id),
+            ),
+            IdentifierStatement::MeasurementKey(key) => (
+                &self.table_id_fields.measurement_key_field_name,
+                DataValue::String(key.clone()),
+            ),
+            IdentifierStatement::PointTag(point_tag) => (
+                &self.table_id_fields.point_tag_field_name,
+                DataValue::String(point_tag.clone()),
+            ),
+        };
+
+        let row_index = table.column_index(field_name).and_then(|column_index| {
+            table.rows().iter().position(|row| {
+                compare_nullable(row.value(column_index), Some(&key), false).is_eq()
+            })
+        });
+
+        match row_index {
+            Some(row_index) => self.add_row(data_set, table_index, row_index, selection),
+            None => {
+                if let IdentifierStatement::SignalId(signal_id) = identifier {
+                    selection.add_signal_id(*signal_id);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn add_row(
+        &self,
+        data_set: &DataSet,
+        table_index: usize,
+        row_index: usize,
+        selection: &mut Selection,
+    ) {
+        if !selection.row_set.insert((table_index, row_index)) {
+            return;
+        }
+
+        selection.rows.push((table_index, row_index));
+
+        let row = &data_set.tables()[table_index].rows()[row_index];
+
+        let signal_id = match row.value_by_name(&self.table_id_fields.signal_id_field_name) {
+            Some(DataValue::Guid(signal_id)) => Some(*signal_id),
+            Some(DataValue::String(signal_id)) => Uuid::parse_str(signal_id.trim()).ok(),
+            _ => None,
+        };
+
+        if let Some(signal_id) = signal_id {
+            selection.add_signal_id(signal_id);
+        }
+    }
+}
+
+impl DataTable {
+    /// Selects the rows of the `DataTable` that match the specified filter expression, e.g.,
+    /// `SignalType = 'FREQ'`, optionally sorted by the specified sort order, e.g., `PointTag DESC`,
+    /// and restricted to the specified number of rows.
+    pub fn select(
+        &self,
+        filter_expression: &str,
+        sort_order: &str,
+        limit: Option<usize>,
+    ) -> Result<Vec<&DataRow>, Box<dyn Error>> {
+        let mut tokens = tokenize(filter_expression)?;
+        tokens.insert(0, Token::Keyword("WHERE"));
+
+        if !sort_order.trim().is_empty() {
+            tokens.push(Token::Keyword("ORDER"));
+            tokens.push(Token::Keyword("BY"));
+            tokens.extend(tokenize(sort_order)?);
+        }
+
+        let mut parser = Parser {
+            tokens,
+            position: 0,
+        };
+        parser.expect(&Token::Keyword("WHERE"))?;
+
+        let filter = FilterStatement {
+            table_name: self.name().to_string(),
+            top_limit: limit.map(|limit| limit as i64),
+            where_expression: parser.parse_expression()?,
+            ordering_terms: parser.parse_ordering_terms()?,
+        };
+
+        parser.expect_end()?;
+
+        Ok(select_rows(self, &filter)?
+            .into_iter()
+            .map(|row_index| &self.rows()[row_index])
+            .collect())
+    }
+}
+
+fn table_not_found(table_name: &str) -> Box<dyn Error> {
+    format!("failed to find table \"{}\" in DataSet", table_name).into()
+}
+
+fn table_index(data_set: &DataSet, table_name: &str) -> Result<usize, Box<dyn Error>> {
+    data_set
+        .tables()
+        .iter()
+        .position(|table| table.name().eq_ignore_ascii_case(table_name))
+        .ok_or_else(|| table_not_found(table_name))
+}
+
+// Selects the indexes of table rows matching the filter statement
+fn select_rows(table: &DataTable, filter: &FilterStatement) -> Result<Vec<usize>, Box<dyn Error>> {
+    let where_expression = bind(&filter.where_expression, table)?;
+    let mut row_indexes = Vec::new();
+
+    for (row_index, row) in table.rows().iter().enumerate() {
+        match evaluate(&where_expression, Some(row))? {
+            Some(DataValue::Boolean(true)) => row_indexes.push(row_index),
+            Some(DataValue::Boolean(false)) | None => {}
+            Some(value) => {
+                return Err(format!(
+                    "filter expression for table \"{}\" must evaluate to a Boolean, received {}",
+                    table.name(),
+                    value.data_type()
+                )
+                .into())
+            }
+        }
+    }
+
+    if !filter.ordering_terms.is_empty() {
+        let mut ordering_columns = Vec::new();
+
+        for term in &filter.ordering_terms {
+            let column_index = table.column_index(&term.column_name).ok_or_else(|| {
+                format!(
+                    "failed to find ORDER BY column \"{}\" in table \"{}\"",
+                    term.column_name,
+                    table.name()
+                )
+            })?;
+
+            ordering_columns.push((column_index, term));
+        }
+
+        row_indexes.sort_by(|left, right| {
+            let left = &table.rows()[*left];
+            let right = &table.rows()[*right];
+
+            ordering_columns
+                .iter()
+                .map(|(column_index, term)| {
+                    let ordering = compare_nullable(
+                        left.value(*column_index),
+                        right.value(*column_index),
+                        term.exact_match,
+                    );
+
+                    if term.descending {
+                        ordering.reverse()
+                    } else {
+                        ordering
+                    }
+                })
+                .find(|ordering| ordering.is_ne())
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+    }
+
+    // Negative limits select all rows
+    if let Some(Ok(limit)) = filter.top_limit.map(usize::try_from) {
+        row_indexes.truncate(limit);
+    }
+
+    Ok(row_indexes)
+}
+
+fn syntax_error(message: &str) -> Box<dyn Error> {
+    format!("filter expression syntax error: {}", message).into()
+}
+
+fn is_identifier_char(char: char) -> bool {
+    char.is_ascii_alphanumeric() || char == '_'
+}
+
+// Determines if the chars at the specified position define a Guid, returning its length
+fn guid_length(chars: &[char], position: usize) -> Option<usize> {
+    const GROUPS: [usize; 5] = [8, 4, 4, 4, 12];
+
+    let braced = chars.get(position) == Some(&'{');
+    let mut index = position + braced as usize;
+
+    for (group, length) in GROUPS.iter().enumerate() {
+        if group > 0 {
+            if chars.get(index) != Some(&'-') {
+                return None;
+            }
+
+            index += 1;
+        }
+
+        for _ in 0..*length {
+            if !chars.get(index)?.is_ascii_hexdigit() {
+                return None;
+            }
+
+            index += 1;
+        }
+    }
+
+    if braced {
+        if chars.get(index) != Some(&'}') {
+            return None;
+        }
+
+        index += 1;
+    }
+
+    // Guid must not be followed by other identifier characters
+    match chars.get(index) {
+        Some(char) if is_identifier_char(*char) => None,
+        _ => Some(index - position),
+    }
+}
+
+// Reads a delimited literal, doubled end delimiters are treated as an escaped delimiter
+fn read_delimited(
+    chars: &[char],
+    position: &mut usize,
+    end: char,
+    allow_escape: bool,
+) -> Result<String, Box<dyn Error>> {
+    let start = *position;
+    let mut value = String::new();
+
+    *position += 1;
+
+    loop {
+        match chars.get(*position) {
+            None => {
+                return Err(syntax_error(&format!(
+                    "unterminated literal starting at position {}",
+                    start
+                )))
+            }
+            Some(char) if *char == end => {
+                if allow_escape && chars.get(*position + 1) == Some(&end) {
+                    value.push(end);
+                    *position += 2;
+                } else {
+                    *position += 1;
+                    return Ok(value);
+                }
+            }
+            Some(char) => {
+                value.push(*char);
+                *position += 1;
+            }
+        }
+    }
+}
+
+fn tokenize(filter_expression: &str) -> Result<Vec<Token>, Box<dyn Error>> {
+    let chars: Vec<char> = filter_expression.chars().collect();
+    let mut tokens = Vec::new();
+    let mut position = 0;
+
+    'tokens: while position < chars.len() {
+        let char = chars[position];
+
+        if char.is_whitespace() {
+            position += 1;
+            continue;
+        }
+
+        if let Some(length) = guid_length(&chars, position) {
+            let literal: String = chars[position..position + length].iter().collect();
+            tokens.push(Token::Guid(Uuid::parse_str(&literal)?));
+            position += length;
+            continue;
+        }
+
+        match char {
+            ';' => tokens.push(Token::Semicolon),
+            ',' => tokens.push(Token::Comma),
+            '(' => tokens.push(Token::LeftParen),
+            ')' => tokens.push(Token::RightParen),
+            '\'' => {
+                let literal = read_delimited(&chars, &mut position, '\'', true)?;
+
+                // Quoted Guid values are treated as Guid literals
+                let literal_chars: Vec<char> = literal.chars().collect();
+
+                if guid_length(&literal_chars, 0) == Some(literal_chars.len()) {
+                    tokens.push(Token::Guid(Uuid::parse_str(&literal)?));
+                } else {
+                    tokens.push(Token::String(literal));
+                }
+
+                continue;
+            }
+            '"' => {
+                let literal = read_delimited(&chars, &mut position, '"', false)?;
+                tokens.push(Token::PointTag(literal));
+                continue;
+            }
+            '#' => {
+                let literal = read_delimited(&chars, &mut position, '#', false)?;
+                tokens.push(Token::DateTime(literal));
+                continue;
+            }
+            '[' => {
+                let literal = read_delimited(&chars, &mut position, ']', false)?;
+                tokens.push(Token::Identifier(literal));
+                continue;
+            }
+            _ if char.is_ascii_digit()
+                || (char == '.' && chars.get(position + 1).is_some_and(char::is_ascii_digit)) =>
+            {
+                tokens.push(read_number(&chars, &mut position));
+                continue;
+            }
+            _ if char.is_ascii_alphabetic() || char == '_' => {
+                let start = position;
+
+                while position < chars.len() && is_identifier_char(chars[position]) {
+                    position += 1;
+                }
+
+                let identifier: String = chars[start..position].iter().collect();
+
+                // Measurement keys are defined as an identifier followed by a colon and an integer
+                if chars.get(position) == Some(&':')
+                    && chars.get(position + 1).is_some_and(char::is_ascii_digit)
+                {
+                    position += 1;
+
+                    while position < chars.len() && chars[position].is_ascii_digit() {
+                        position += 1;
+                    }
+
+                    tokens.push(Token::MeasurementKey(
+                        chars[start..position].iter().collect(),
+                    ));
+                    continue;
+                }
+
+                if identifier.eq_ignore_ascii_case("true") {
+                    tokens.push(Token::Boolean(true));
+                } else if identifier.eq_ignore_ascii_case("false") {
+                    tokens.push(Token::Boolean(false));
+                } else if let Some(keyword) = KEYWORDS
+                    .iter()
+                    .find(|keyword| keyword.eq_ignore_ascii_case(&identifier))
+                {
+                    tokens.push(Token::Keyword(keyword));
+                } else {
+                    tokens.push(Token::Identifier(identifier));
+                }
+
+                continue;
+            }
+            _ => {
+                for symbol in SYMBOLS {
+                    let length = symbol.len();
+
+                    if position + length <= chars.len()
+                        && chars[position..position + length]
+                            .iter()
+                            .copied()
+                            .eq(symbol.chars())
+                    {
+                        tokens.push(Token::Symbol(symbol));
+                        position += length;
+                        continue 'tokens;
+                    }
+                }
+
+                return Err(syntax_error(&format!(
+                    "unexpected character '{}' at position {}",
+                    char, position
+                )));
+            }
+        }
+
+        position += 1;
+    }
+
+    Ok(tokens)
+}
+
+fn read_number(chars: &[char], position: &mut usize) -> Token {
+    let start = *position;
+    let mut numeric = false;
+
+    while *position < chars.len() && chars[*position].is_ascii_digit() {
+        *position += 1;
+    }
+
+    if chars.get(*position) == Some(&'.')
+        && chars.get(*position + 1).is_some_and(char::is_ascii_digit)
+    {
+        numeric = true;
+        *position += 1;
+
+        while *position < chars.len() && chars[*position].is_ascii_digit() {
+            *position += 1;
+        }
+    }
+
+    if matches!(chars.get(*position), Some('e' | 'E')) {
+        let mut index = *position + 1;
+
+        if matches!(chars.get(index), Some('+' | '-')) {
+            index += 1;
+        }
+
+        if chars.get(index).is_some_and(char::is_ascii_digit) {
+            numeric = true;
+            *position = index;
+
+            while *position < chars.len() && chars[*position].is_ascii_digit() {
+                *position += 1;
+            }
+        }
+    }
+
+    let literal = chars[start..*position].iter().collect();
+
+    if numeric {
+        Token::Numeric(literal)
+    } else {
+        Token::Integer(literal)
+    }
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    position: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn peek_at(&self, offset: usize) -> Option<&Token> {
+        self.tokens.get(self.position + offset)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.position).cloned();
+
+        if token.is_some() {
+            self.position += 1;
+        }
+
+        token
+    }
+
+    fn accept(&mut self, token: &Token) -> bool {
+        if self.peek() == Some(token) {
+            self.position += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn accept_any(&mut self, tokens: &[Token]) -> bool {
+        tokens.iter().any(|token| self.accept(token))
+    }
+
+    fn expect(&mut self, token: &Token) -> Result<(), Box<dyn Error>> {
+        if self.accept(token) {
+            Ok(())
+        } else {
+            Err(self.unexpected(&describe(token)))
+        }
+    }
+
+    fn expect_end(&self) -> Result<(), Box<dyn Error>> {
+        match self.peek() {
+            None => Ok(()),
+            Some(_) => Err(self.unexpected("end of expression")),
+        }
+    }
+
+    fn unexpected(&self, expected: &str) -> Box<dyn Error> {
+        match self.peek() {
+            Some(token) => syntax_error(&format!(
+                "expected {} but found {} at token {}",
+                expected,
+                describe(token),
+                self.position + 1
+            )),
+            None => syntax_error(&format!(
+                "expected {} but reached end of expression",
+                expected
+            )),
+        }
+    }
+
+    fn parse_statements(&mut self) -> Result<Vec<Statement>, Box<dyn Error>> {
+        let mut statements = Vec::new();
+
+        loop {
+            while self.accept(&Token::Semicolon) {}
+
+            if self.peek().is_none() {
+                break;
+            }
+
+            statements.push(self.parse_statement()?);
+
+            if self.peek().is_some() && self.peek() != Some(&Token::Semicolon) {
+                return Err(self.unexpected("';' or end of expression"));
+            }
+        }
+
+        if statements.is_empty() {
+            return Err(syntax_error("no statements found"));
+        }
+
+        Ok(statements)
+    }
+
+    fn parse_statement(&mut self) -> Result<Statement, Box<dyn Error>> {
+        if self.accept(&Token::Keyword("FILTER")) {
+            return Ok(Statement::Filter(self.parse_filter_statement()?));
+        }
+
+        // Identifier literals are only valid as standalone statements
+        if matches!(self.peek_at(1), None | Some(Token::Semicolon)) {
+            let identifier = match self.peek() {
+                Some(Token::Guid(signal_id)) => Some(IdentifierStatement::SignalId(*signal_id)),
+                Some(Token::MeasurementKey(key)) => {
+                    Some(IdentifierStatement::MeasurementKey(key.clone()))
+                }
+                Some(Token::PointTag(point_tag)) => {
+                    Some(IdentifierStatement::PointTag(point_tag.clone()))
+                }
+                _ => None,
+            };
+
+            if let Some(identifier) = identifier {
+                self.position += 1;
+                return Ok(Statement::Identifier(identifier));
+            }
+        }
+
+        Ok(Statement::Expression(self.parse_expression()?))
+    }
+
+    fn parse_filter_statement(&mut self) -> Result<FilterStatement, Box<dyn Error>> {
+        let mut top_limit = None;
+
+        if self.accept(&Token::Keyword("TOP")) {
+            let negative = if self.accept(&Token::Symbol("-")) {
+                true
+            } else {
+                self.accept(&Token::Symbol("+"));
+                false
+            };
+
+            let limit = match self.next() {
+                Some(Token::Integer(limit)) => limit
+                    .parse::<i64>()
+                    .map_err(|_| syntax_error(&format!("invalid TOP limit \"{}\"", limit)))?,
+                _ => {
+                    self.position = self.position.saturating_sub(1);
+                    return Err(self.unexpected("integer TOP limit"));
+                }
+            };
+
+            top_limit = Some(if negative { -limit } else { limit });
+        }
+
+        let table_name = match self.next() {
+            Some(Token::Identifier(table_name)) => table_name,
+            _ => {
+                self.position = self.position.saturating_sub(1);
+                return Err(self.unexpected("table name"));
+            }
+        };
+
+        self.expect(&Token::Keyword("WHERE"))?;
+
+        let where_expression = self.parse_expression()?;
+        let ordering_terms = self.parse_ordering_terms()?;
+
+        Ok(FilterStatement {
+            table_name,
+            top_limit,
+            where_expression,
+            ordering_terms,
+        })
+    }
+
+    fn parse_ordering_terms(&mut self) -> Result<Vec<OrderingTerm>, Box<dyn Error>> {
+        let mut ordering_terms = Vec::new();
+
+        if !self.accept(&Token::Keyword("ORDER")) {
+            return Ok(ordering_terms);
+        }
+
+        self.expect(&Token::Keyword("BY"))?;
+
+        loop {
+            let exact_match = self.parse_exact_match_modifier();
+
+            let column_name = match self.next() {
+                Some(Token::Identifier(column_name)) => column_name,
+                _ => {
+                    self.position = self.position.saturating_sub(1);
+                    return Err(self.unexpected("ORDER BY column name"));
+                }
+            };
+
+            let descending = if self.accept(&Token::Keyword("DESC")) {
+                true
+            } else {
+                self.accept(&Token::Keyword("ASC"));
+                false
+            };
+
+            ordering_terms.push(OrderingTerm {
+                column_name,
+                descending,
+                exact_match,
+            });
+
+            if !self.accept(&Token::Comma) {
+                return Ok(ordering_terms);
+            }
+        }
+    }
+
+    fn parse_exact_match_modifier(&mut self) -> bool {
+        self.accept_any(&[Token::Keyword("BINARY"), Token::Symbol("===")])
+    }
+
+    fn parse_expression(&mut self) -> Result<Expression, Box<dyn Error>> {
+        let mut left = self.parse_and_expression()?;
+
+        while self.accept_any(&[Token::Keyword("OR"), Token::Symbol("||")]) {
+            let right = self.parse_and_expression()?;
+            left = Expression::Operator(OperatorType::Or, Box::new(left), Box::new(right));
+        }
+
+        Ok(left)
+    }
+
+    fn parse_and_expression(&mut self) -> Result<Expression, Box<dyn Error>> {
+        let mut left = self.parse_not_expression()?;
+
+        while self.accept_any(&[Token::Keyword("AND"), Token::Symbol("&&")]) {
+            let right = self.parse_not_expression()?;
+            left = Expression::Operator(OperatorType::And, Box::new(left), Box::new(right));
+        }
+
+        Ok(left)
+    }
+
+    fn parse_not_expression(&mut self) -> Result<Expression, Box<dyn Error>> {
+        if self.accept_any(&[Token::Keyword("NOT"), Token::Symbol("!")]) {
+            let value = self.parse_not_expression()?;
+            return Ok(Expression::Unary(UnaryOperator::Not, Box::new(value)));
+        }
+
+        self.parse_predicate_expression()
+    }
+
+    fn parse_predicate_expression(&mut self) -> Result<Expression, Box<dyn Error>> {
+        let mut left = self.parse_value_expression()?;
+
+        loop {
+            // NOT is only valid here when followed by IN or LIKE
+            let negated = matches!(
+                self.peek(),
+                Some(Token::Keyword("NOT")) | Some(Token::Symbol("!"))
+            ) && matches!(
+                self.peek_at(1),
+                Some(Token::Keyword("IN")) | Some(Token::Keyword("LIKE"))
+            );
+
+            if negated {
+                self.position += 1;
+            }
+
+            if self.accept(&Token::Keyword("IN")) {
+                let exact_match = self.parse_exact_match_modifier();
+                self.expect(&Token::LeftParen)?;
+
+                let arguments = self.parse_expression_list()?;
+
+                left = Expression::In {
+                    value: Box::new(left),
+                    arguments,
+                    negated,
+                    exact_match,
+                };
+
+                continue;
+            }
+
+            if self.accept(&Token::Keyword("LIKE")) {
+                let exact_match = self.parse_exact_match_modifier();
+                let right = self.parse_value_expression()?;
+
+                let operator = match (negated, exact_match) {
+                    (false, false) => OperatorType::Like,
+                    (false, true) => OperatorType::LikeExactMatch,
+                    (true, false) => OperatorType::NotLike,
+                    (true, true) => OperatorType::NotLikeExactMatch,
+                };
+
+                left = Expression::Operator(operator, Box::new(left), Box::new(right));
+                continue;
+            }
+
+            if self.accept(&Token::Keyword("IS")) {
+                let negated = self.accept_any(&[Token::Keyword("NOT"), Token::Symbol("!")]);
+                self.expect(&Token::Keyword("NULL"))?;
+
+                left = Expression::IsNull {
+                    value: Box::new(left),
+                    negated,
+                };
+
+                continue;
+            }
+
+            let operator = match self.peek() {
+                Some(Token::Symbol("<")) => OperatorType::LessThan,
+                Some(Token::Symbol("<=")) => OperatorType::LessThanOrEqual,
+                Some(Token::Symbol(">")) => OperatorType::GreaterThan,
+                Some(Token::Symbol(">=")) => OperatorType::GreaterThanOrEqual,
+                Some(Token::Symbol("=")) | Some(Token::Symbol("==")) => OperatorType::Equal,
+                Some(Token::Symbol("===")) => OperatorType::EqualExactMatch,
+                Some(Token::Symbol("!=")) | Some(Token::Symbol("<>")) => OperatorType::NotEqual,
+                Some(Token::Symbol("!==")) => OperatorType::NotEqualExactMatch,
+                _ => return Ok(left),
+            };
+
+            self.position += 1;
+
+            let right = self.parse_value_expression()?;
+            left = Expression::Operator(operator, Box::new(left), Box::new(right));
+        }
+    }
+
+    fn parse_expression_list(&mut self) -> Result<Vec<Expression>, Box<dyn Error>> {
+        let mut expressions = Vec::new();
+
+        if self.accept(&Token::RightParen) {
+            return Ok(expressions);
+        }
+
+        loop {
+            expressions.push(self.parse_expression()?);
+
+            if self.accept(&Token::RightParen) {
+                return Ok(expressions);
+            }
+
+            self.expect(&Token::Comma)?;
+        }
+    }
+
+    // Binary operator precedence, from lowest to highest
+    fn parse_value_expression(&mut self) -> Result<Expression, Box<dyn Error>> {
+        self.parse_binary_expression(0)
+    }
+
+    fn parse_binary_expression(&mut self, level: usize) -> Result<Expression, Box<dyn Error>> {
+        const LEVELS: [&[(&Token, OperatorType)]; 6] = [
+            &[(&Token::Symbol("|"), OperatorType::BitwiseOr)],
+            &[
+                (&Token::Symbol("^"), OperatorType::BitwiseXor),
+                (&Token::Keyword("XOR"), OperatorType::BitwiseXor),
+            ],
+            &[(&Token::Symbol("&"), OperatorType::BitwiseAnd)],
+            &[
+                (&Token::Symbol("<<"), OperatorType::BitShiftLeft),
+                (&Token::Symbol(">>"), OperatorType::BitShiftRight),
+            ],
+            &[
+                (&Token::Symbol("+"), OperatorType::Add),
+                (&Token::Symbol("-"), OperatorType::Subtract),
+            ],
+            &[
+                (&Token::Symbol("*"), OperatorType::Multiply),
+                (&Token::Symbol("/"), OperatorType::Divide),
+                (&Token::Symbol("%"), OperatorType::Modulus),
+            ],
+        ];
+
+        if level == LEVELS.len() {
+            return self.parse_unary_expression();
+        }
+
+        let mut left = self.parse_binary_expression(level + 1)?;
+
+        'operators: loop {
+            for (token, operator) in LEVELS[level] {
+                if self.accept(token) {
+                    let right = self.parse_binary_expression(level + 1)?;
+                    left = Expression::Operator(*operator, Box::new(left), Box::new(right));
+                    continue 'operators;
+                }
+            }
+
+            return Ok(left);
+        }
+    }
+
+    fn parse_unary_expression(&mut self) -> Result<Expression, Box<dyn Error>> {
+        let operator = match self.peek() {
+            Some(Token::Symbol("-")) => UnaryOperator::Minus,
+            Some(Token::Symbol("+")) => UnaryOperator::Plus,
+            Some(Token::Symbol("~")) => UnaryOperator::BitwiseComplement,
+            Some(Token::Symbol("!")) | Some(Token::Keyword("NOT")) => UnaryOperator::Not,
+            _ => return self.parse_primary_expression(),
+        };
+
+        self.position += 1;
+
+        let value = self.parse_unary_expression()?;
+        Ok(Expression::Unary(operator, Box::new(value)))
+    }
+
+    fn parse_primary_expression(&mut self) -> Result<Expression, Box<dyn Error>> {
+        let token = match self.next() {
+            Some(token) => token,
+            None => return Err(self.unexpected("expression")),
+        };
+
+        let value = match token {
+            Token::LeftParen => {
+                let expression = self.parse_expression()?;
+                self.expect(&Token::RightParen)?;
+                return Ok(expression);
+            }
+            Token::Identifier(name) => {
+                if self.peek() != Some(&Token::LeftParen) {
+                    return Ok(Expression::ColumnName(name));
+                }
+
+                return self.parse_function_expression(&name);
+            }
+            Token::Keyword("NULL") => None,
+            Token::Boolean(value) => Some(DataValue::Boolean(value)),
+            Token::Guid(value) => Some(DataValue::Guid(value)),
+            Token::String(value) => Some(DataValue::String(value)),
+            Token::DateTime(value) => Some(
+                DataValue::parse(DataType::DateTime, &value)
+                    .map_err(|_| syntax_error(&format!("invalid date/time literal #{}#", value)))?,
+            ),
+            Token::Integer(value) => Some(parse_integer(&value)?),
+            Token::Numeric(value) => Some(parse_numeric(&value)?),
+            _ => {
+                self.position -= 1;
+                return Err(self.unexpected("expression"));
+            }
+        };
+
+        Ok(Expression::Value(value))
+    }
+
+    fn parse_function_expression(&mut self, name: &str) -> Result<Expression, Box<dyn Error>> {
+        let function = FunctionType::from_name(name)
+            .ok_or_else(|| syntax_error(&format!("unknown function \"{}\"", name)))?;
+
+        self.expect(&Token::LeftParen)?;
+
+        let arguments = self.parse_expression_list()?;
+        let (minimum, maximum) = function.argument_range();
+
+        if arguments.len() < minimum || arguments.len() > maximum {
+            let expected = if minimum == maximum {
+                format!("{}", minimum)
+            } else if maximum == usize::MAX {
+                format!("at least {}", minimum)
+            } else {
+                format!("{} to {}", minimum, maximum)
+            };
+
+            return Err(syntax_error(&format!(
+                "{:?} function expects {} arguments, received {}",
+                function,
+                expected,
+                arguments.len()
+            )));
+        }
+
+        Ok(Expression::Function(function, arguments))
+    }
+}
+
+// Integer literals use the smallest of Int32, Int64 or Decimal that can hold the value
+fn parse_integer(value: &str) -> Result<DataValue, Box<dyn Error>> {
+    if let Ok(value) = value.parse::<i32>() {
+        return Ok(DataValue::Int32(value));
+    }
+
+    if let Ok(value) = value.parse::<i64>() {
+        return Ok(DataValue::Int64(value));
+    }
+
+    parse_numeric(value)
+}
+
+// Numeric literals with an exponent are Double values, others are Decimal values
+fn parse_numeric(value: &str) -> Result<DataValue, Box<dyn Error>> {
+    if value.contains(['e', 'E']) {
+        return Ok(DataValue::Double(value.parse()?));
+    }
+
+    match Decimal::from_str(value) {
+        Ok(decimal) => Ok(DataValue::Decimal(decimal)),
+        Err(_) => Ok(DataValue::Double(value.parse()?)),
+    }
+}
+
+fn describe(token: &Token) -> String {
+    match token {
+        Token::Semicolon => "';'".to_string(),
+        Token::Comma => "','".to_string(),
+        Token::LeftParen => "'('".to_string(),
+        Token::RightParen => "')'".to_string(),
+        Token::Symbol(symbol) => format!("'{}'", symbol),
+        Token::Keyword(keyword) => keyword.to_string(),
+        Token::Identifier(identifier) => format!("identifier \"{}\"", identifier),
+        Token::Integer(value) | Token::Numeric(value) => format!("number {}", value),
+        Token::String(value) => format!("string '{}'", value),
+        Token::DateTime(value) => format!("date/time #{}#", value),
+        Token::Guid(value) => format!("Guid {}", value),
+        Token::MeasurementKey(value) => format!("measurement key {}", value),
+        Token::PointTag(value) => format!("point tag \"{}\"", value),
+        Token::Boolean(value) => format!("{}", value),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::DataColumn;
+
+    const SIGNAL_IDS: [&str; 4] = [
+        "6f8e8b61-7c31-4fd5-8f5e-6b0c1f4a0e2d",
+        "29a2f3d8-e6b1-4a5a-9a2c-0c4c4d0c7d51",
+        "e4bbfe6a-35bd-4e5b-92c9-11ff913e7877",
+        "038a47b0-f10b-4143-9a0a-0dbc4ffef1e8",
+    ];
+
+    fn signal_id(index: usize) -> Uuid {
+        Uuid::parse_str(SIGNAL_IDS[index]).unwrap()
+    }
+
+    fn active_measurements() -> DataSet {
+        let mut table = DataTable::new("ActiveMeasurements");
+
+        for (name, data_type) in [
+            ("SignalID", DataType::Guid),
+            ("ID", DataType::String),
+            ("PointTag", DataType::String),
+            ("Device", DataType::String),
+            ("SignalType", DataType::String),
+            ("Phase", DataType::String),
+            ("Adder", DataType::Double),
+            ("UpdatedOn", DataType::DateTime),
+        ] {
+            table
+                .add_column(DataColumn::new(name, data_type, ""))
+                .unwrap();
+        }
+
+        let records = [
+            ("PPA:1", "SHELBY-FQ", "SHELBY", "FREQ", None, 0.0),
+            ("PPA:2", "SHELBY-PA1:VH", "SHELBY", "VPHM", Some("+"), 1.5),
+            ("PPA:3", "GPLAINS-FQ", "GPLAINS", "FREQ", None, -2.0),
+            (
+                "PPA:4",
+                "GPLAINS-PA1:VH",
+                "GPLAINS",
+                "VPHM",
+                Some("A"),
+                0.25,
+            ),
+        ];
+
+        for (index, (id, point_tag, device, signal_type, phase, adder)) in
+            records.into_iter().enumerate()
+        {
+            let mut row = table.create_row();
+            let values = [
+                Some(DataValue::Guid(signal_id(index))),
+                Some(DataValue::String(id.to_string())),
+                Some(DataValue::String(point_tag.to_string())),
+                Some(DataValue::String(device.to_string())),
+                Some(DataValue::String(signal_type.to_string())),
+                phase.map(|phase| DataValue::String(phase.to_string())),
+                Some(DataValue::Double(adder)),
+                Some(DataValue::parse(DataType::DateTime, "2023-03-25 12:00:00").unwrap()),
+            ];
+
+            for (column_index, value) in values.into_iter().enumerate() {
+                row.set_value(column_index, value).unwrap();
+            }
+
+            table.add_row(row).unwrap();
+        }
+
+        let mut data_set = DataSet::new();
+        data_set.add_table(table);
+        data_set
+    }
+
+    fn point_tags(rows: &[&DataRow]) -> Vec<String> {
+        rows.iter()
+            .map(|row| row.value_by_name("PointTag").unwrap().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_filter_statement_selects_sorted_rows() {
+        let data_set = active_measurements();
+
+        let parser = FilterExpressionParser::new(
+            "FILTER TOP 2 ActiveMeasurements WHERE SignalType = 'vphm' OR Device LIKE 'GP%' ORDER BY PointTag DESC",
+        )
+        .unwrap();
+
+        let rows = parser.filtered_rows(&data_set).unwrap();
+
+        assert_eq!(point_tags(&rows), vec!["SHELBY-PA1:VH", "GPLAINS-PA1:VH"]);
+        assert_eq!(
+            parser.filtered_signal_ids(&data_set).unwrap(),
+            vec![signal_id(1), signal_id(3)]
+        );
+
+        // Exact match comparisons are case-sensitive
+        let parser =
+            FilterExpressionParser::new("FILTER ActiveMeasurements WHERE SignalType === 'vphm'")
+                .unwrap();
+
+        assert!(parser.filtered_rows(&data_set).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_identifier_statements_select_signal_ids() {
+        let data_set = active_measurements();
+        let unknown = Uuid::parse_str("d6b0a0b2-4c2f-4e8a-8b9e-3f1d2c4b5a69").unwrap();
+
+        let parser = FilterExpressionParser::new(&format!(
+            "{{{}}}; PPA:3; \"shelby-fq\"; {}; '{}'; PPA:99",
+            SIGNAL_IDS[3].to_uppercase(),
+            unknown,
+            SIGNAL_IDS[2]
+        ))
+        .unwrap();
+
+        assert_eq!(parser.statement_count(), 6);
+        assert_eq!(
+            parser.filtered_signal_ids(&data_set).unwrap(),
+            vec![signal_id(3), signal_id(2), signal_id(0), unknown]
+        );
+        assert_eq!(parser.filtered_rows(&data_set).unwrap().len(), 3);
+    }
+
+    #[test]
+    fn test_expressions_evaluate_operators_and_functions() {
+        let data_set = active_measurements();
+        let table = data_set.table("ActiveMeasurements").unwrap();
+
+        let select = |expression: &str| -> Vec<String> {
+            point_tags(&table.select(expression, "ID", None).unwrap())
+        };
+
+        assert_eq!(
+            select("Coalesce(Phase, 'N') = 'N' AND NOT Device = 'GPLAINS'"),
+            vec!["SHELBY-FQ"]
+        );
+        assert_eq!(select("Phase IS NULL"), vec!["SHELBY-FQ", "GPLAINS-FQ"]);
+        assert_eq!(
+            select("IsRegExMatch('^GPLAINS', PointTag) && Len(PointTag) > 10"),
+            vec!["GPLAINS-PA1:VH"]
+        );
+        assert_eq!(
+            select("Adder * 4 + 1 >= 2 AND Adder NOT IN (1.5)"),
+            vec!["GPLAINS-PA1:VH"]
+        );
+        assert_eq!(
+            select("SubStr(ID, IndexOf(ID, ':') + 1) IN (2, 3)"),
+            vec!["SHELBY-PA1:VH", "GPLAINS-FQ"]
+        );
+        assert_eq!(
+            select("Convert(Abs(Adder), 'System.Int32') = 2"),
+            vec!["GPLAINS-FQ"]
+        );
+        assert_eq!(
+            select("DateAdd(UpdatedOn, 1, 'Day') = #2023-03-26 12:00:00# AND SignalType = 'FREQ'"),
+            vec!["SHELBY-FQ", "GPLAINS-FQ"]
+        );
+        assert_eq!(
+            select("IIf(Phase = '+', 1, 0) + (3 << 1 | 1) = 8"),
+            vec!["SHELBY-PA1:VH"]
+        );
+        assert_eq!(
+            select("Upper(Split(PointTag, '-pa1', 0, true)) + '!' == 'gplains!'"),
+            vec!["GPLAINS-PA1:VH"]
+        );
+
+        // Limits apply after sorting
+        let rows = table.select("true", "Adder DESC", Some(1)).unwrap();
+        assert_eq!(point_tags(&rows), vec!["SHELBY-PA1:VH"]);
+    }
+
+    #[test]
+    fn test_invalid_expressions_are_rejected() {
+        for expression in [
+            "",
+            "FILTER ActiveMeasurements SignalType = 'FREQ'",
+            "FILTER ActiveMeasurements WHERE SignalType = 'FREQ",
+            "FILTER ActiveMeasurements WHERE Len(PointTag, 1) > 0",
+            "FILTER ActiveMeasurements WHERE Unknown(PointTag)",
+            "FILTER ActiveMeasurements WHERE (Adder > 1",
+            "SignalType = 'FREQ' Device = 'SHELBY'",
+            "PPA:1 = 1",
+        ] {
+            assert!(
+                FilterExpressionParser::new(expression).is_err(),
+                "{}",
+                expression
+            );
+        }
+
+        let data_set = active_measurements();
+
+        // Semantic errors are reported during evaluation
+        for expression in [
+            "FILTER MissingTable WHERE true",
+            "FILTER ActiveMeasurements WHERE MissingColumn = 1",
+            "FILTER ActiveMeasurements WHERE Adder",
+            "FILTER ActiveMeasurements WHERE SignalID = 1",
+        ] {
+            let parser = FilterExpressionParser::new(expression).unwrap();
+            assert!(parser.filtered_rows(&data_set).is_err(), "{}", expression);
+        }
+    }
+}
@@ -26,15 +26,12 @@
     dead_code,
     arithmetic_overflow,
     invalid_type_param_default,
-    missing_fragment_specifier,
     mutable_transmutes,
     no_mangle_const_items,
     overflowing_literals,
     patterns_in_fns_without_body,
     pub_use_of_private_extern_crate,
     unknown_crate_types,
-    order_dependent_trait_objects,
-    illegal_floating_point_literal_pattern,
     improper_ctypes,
     late_bound_lifetime_arguments,
     non_camel_case_types,
@@ -42,7 +39,6 @@
     non_snake_case,
     non_upper_case_globals,
     no_mangle_generic_items,
-    private_in_public,
     stable_features,
     type_alias_bounds,
     tyvar_behind_raw_pointer,
@@ -73,9 +69,16 @@
 mod config;
 pub use crate::config::Config; // >> sttp::Config
 
+mod measurement_queue;
+pub use crate::measurement_queue::OverflowPolicy; // >> sttp::OverflowPolicy
+
 mod settings;
 pub use crate::settings::Settings; // >> sttp::Settings
 
+mod subscriber;
+pub use crate::subscriber::MeasurementBatches; // >> sttp::MeasurementBatches
+pub use crate::subscriber::Subscriber; // >> sttp::Subscriber
+
 mod ticks;
 pub use crate::ticks::Ticks; // >> sttp::Ticks
 
@@ -87,30 +90,3 @@ pub mod data;
 
 /// Represents transport functionality of the STTP library.
 pub mod transport;
-
-// use std::sync::Arc;
-// use std::time::SystemTime;
-
-/// Represents an STTP data subscriber.
-///
-/// The `Subscriber` exists as a simplified implementation of the `DataSubscriber` found
-/// in the transport module. The `Subscriber` is intended to simplify common uses of
-/// STTP data reception and maintains an internal instance of the `DataSubscriber` for
-/// subscription based functionality.
-#[derive(Clone, Copy)]
-pub struct Subscriber {
-    // // Configuration reference
-    // config: Arc<Config>,
-
-    // // DataSubscriber reference
-    // //ds: Arc<transport::DataSubscriber>,
-
-    // // Callback references
-    // status_message_logger: Option<Box<dyn Fn(String) + Send + Sync>>,
-    // error_message_logger: Option<Box<dyn Fn(String) + Send + Sync>>,
-    // //metadata_receiver: Option<Box<dyn Fn(data::DataSet) + Send + Sync>>,
-    // data_start_time_receiver: Option<Box<dyn Fn(SystemTime) + Send + Sync>>,
-    // configuration_changed_receiver: Option<Box<dyn Fn() + Send + Sync>>,
-    // historical__complete_receiver: Option<Box<dyn Fn() + Send + Sync>>,
-    // connection_established_receiver: Option<Box<dyn Fn() + Send + Sync>>,
-}
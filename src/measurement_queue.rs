@@ -0,0 +1,234 @@
+//******************************************************************************************************
+//  measurement_queue.rs - Gbtc
+//
+//  Copyright © 2023, Grid Protection Alliance.  All Rights Reserved.
+//
+//  Licensed to the Grid Protection Alliance (GPA) under one or more contributor license agreements. See
+//  the NOTICE file distributed with this work for additional information regarding copyright ownership.
+//  The GPA licenses this file to you under the MIT License (MIT), the "License"; you may not use this
+//  file except in compliance with the License. You may obtain a copy of the License at:
+//
+//      http://opensource.org/licenses/MIT
+//
+//  Unless agreed to in writing, the subject software distributed under the License is distributed on an
+//  "AS-IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied. Refer to the
+//  License for the specific language governing permissions and limitations.
+//
+//  Code Modification History:
+//  ----------------------------------------------------------------------------------------------------
+//  04/01/2023 - J. Ritchie Carroll
+//       Generated original version of source code.
+//
+//******************************************************************************************************
+
+use crate::transport::BasicMeasurement;
+use std::collections::VecDeque;
+use std::error::Error;
+use std::sync::{Condvar, Mutex};
+use std::time::{Duration, Instant};
+
+/// Enumeration of the possible behaviors when a `Subscriber` measurement queue is full.
+#[derive(Default, Debug, PartialEq, Eq, Clone, Copy)]
+pub enum OverflowPolicy {
+    /// Discards the oldest queued batch to make room for the newly received batch.
+    #[default]
+    DropOldest,
+    /// Discards the newly received batch, keeping queued batches intact.
+    DropNewest,
+    /// Blocks reception until the reader makes room in the queue. Note that this stalls
+    /// the data channel so the publisher will eventually experience back-pressure.
+    Block,
+}
+
+struct QueueState {
+    batches: VecDeque<Vec<BasicMeasurement>>,
+    dropped: u64,
+    closed: bool,
+}
+
+// Bounded queue of received measurement batches that are pulled by `Subscriber::read_measurements`
+pub(crate) struct MeasurementQueue {
+    state: Mutex<QueueState>,
+    readable: Condvar,
+    writable: Condvar,
+    capacity: usize,
+    overflow_policy: OverflowPolicy,
+}
+
+impl MeasurementQueue {
+    pub(crate) fn new(capacity: usize, overflow_policy: OverflowPolicy) -> Self {
+        MeasurementQueue {
+            state: Mutex::new(QueueState {
+                batches: VecDeque::with_capacity(capacity),
+                dropped: 0,
+                closed: false,
+            }),
+            readable: Condvar::new(),
+            writable: Condvar::new(),
+            capacity: capacity.max(1),
+            overflow_policy,
+        }
+    }
+
+    // Queues a batch of measurements, applying the overflow policy when the queue is full
+    pub(crate) fn push(&self, batch: Vec<BasicMeasurement>) {
+        let mut state = self.state.lock().unwrap();
+
+        while state.batches.len() >= self.capacity && !state.closed {
+            match self.overflow_policy {
+                OverflowPolicy::DropOldest => {
+                    if let Some(oldest) = state.batches.pop_front() {
+                        state.dropped += oldest.len() as u64;
+                    }
+                }
+                OverflowPolicy::DropNewest => {
+                    state.dropped += batch.len() as u64;
+                    return;
+                }
+                OverflowPolicy::Block => {
+                    state = self.writable.wait(state).unwrap();
+                }
+            }
+        }
+
+        if state.closed {
+            return;
+        }
+
+        state.batches.push_back(batch);
+        self.readable.notify_one();
+    }
+
+    // Dequeues the next batch of measurements, waiting up to the specified timeout when the queue
+    // is empty. A `None` timeout waits until a batch is available or the queue is closed.
+    pub(crate) fn pop(
+        &self,
+        timeout: Option<Duration>,
+    ) -> Result<Option<Vec<BasicMeasurement>>, Box<dyn Error>> {
+        let deadline = timeout.map(|timeout| Instant::now() + timeout);
+        let mut state = self.state.lock().unwrap();
+
+        loop {
+            if let Some(batch) = state.batches.pop_front() {
+                self.writable.notify_one();
+                return Ok(Some(batch));
+            }
+
+            if state.closed {
+                return Err("subscriber is closed; no more measurements can be read".into());
+            }
+
+            state = match deadline {
+                Some(deadline) => {
+                    let now = Instant::now();
+
+                    if now >= deadline {
+                        return Ok(None);
+                    }
+
+                    self.readable.wait_timeout(state, deadline - now).unwrap().0
+                }
+                None => self.readable.wait(state).unwrap(),
+            };
+        }
+    }
+
+    // Gets the total number of measurements discarded due to queue overflow
+    pub(crate) fn dropped(&self) -> u64 {
+        self.state.lock().unwrap().dropped
+    }
+
+    // Closes the queue, releasing any blocked readers or writers; queued batches remain readable
+    pub(crate) fn close(&self) {
+        self.state.lock().unwrap().closed = true;
+        self.readable.notify_all();
+        self.writable.notify_all();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transport::Measurement;
+    use std::sync::Arc;
+    use std::thread;
+
+    fn batch(values: &[f64]) -> Vec<BasicMeasurement> {
+        values
+            .iter()
+            .map(|value| {
+                let mut measurement = BasicMeasurement::default();
+                measurement.set_value(*value);
+                measurement
+            })
+            .collect()
+    }
+
+    fn values(batch: Option<Vec<BasicMeasurement>>) -> Vec<f64> {
+        batch.unwrap().iter().map(|m| m.value()).collect()
+    }
+
+    #[test]
+    fn test_overflow_policies_drop_batches() {
+        let queue = MeasurementQueue::new(2, OverflowPolicy::DropOldest);
+
+        queue.push(batch(&[1.0]));
+        queue.push(batch(&[2.0, 3.0]));
+        queue.push(batch(&[4.0]));
+
+        assert_eq!(queue.dropped(), 1);
+        assert_eq!(values(queue.pop(None).unwrap()), [2.0, 3.0]);
+        assert_eq!(values(queue.pop(None).unwrap()), [4.0]);
+
+        let queue = MeasurementQueue::new(2, OverflowPolicy::DropNewest);
+
+        queue.push(batch(&[1.0]));
+        queue.push(batch(&[2.0]));
+        queue.push(batch(&[3.0, 4.0]));
+
+        assert_eq!(queue.dropped(), 2);
+        assert_eq!(values(queue.pop(None).unwrap()), [1.0]);
+        assert_eq!(values(queue.pop(None).unwrap()), [2.0]);
+        assert!(queue
+            .pop(Some(Duration::from_millis(10)))
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn test_blocked_writer_resumes_after_read() {
+        let queue = Arc::new(MeasurementQueue::new(1, OverflowPolicy::Block));
+
+        queue.push(batch(&[1.0]));
+
+        let writer = {
+            let queue = Arc::clone(&queue);
+            thread::spawn(move || queue.push(batch(&[2.0])))
+        };
+
+        assert_eq!(
+            values(queue.pop(Some(Duration::from_secs(5))).unwrap()),
+            [1.0]
+        );
+        assert_eq!(
+            values(queue.pop(Some(Duration::from_secs(5))).unwrap()),
+            [2.0]
+        );
+
+        writer.join().unwrap();
+
+        assert_eq!(queue.dropped(), 0);
+    }
+
+    #[test]
+    fn test_close_drains_queue_then_fails_reads() {
+        let queue = MeasurementQueue::new(4, OverflowPolicy::Block);
+
+        queue.push(batch(&[1.0]));
+        queue.close();
+        queue.push(batch(&[2.0]));
+
+        assert_eq!(values(queue.pop(None).unwrap()), [1.0]);
+        assert!(queue.pop(None).is_err());
+    }
+}
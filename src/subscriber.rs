@@ -0,0 +1,783 @@
+//******************************************************************************************************
+//  subscriber.rs - Gbtc
+//
+//  Copyright © 2023, Grid Protection Alliance.  All Rights Reserved.
+//
+//  Licensed to the Grid Protection Alliance (GPA) under one or more contributor license agreements. See
+//  the NOTICE file distributed with this work for additional information regarding copyright ownership.
+//  The GPA licenses this file to you under the MIT License (MIT), the "License"; you may not use this
+//  file except in compliance with the License. You may obtain a copy of the License at:
+//
+//      http://opensource.org/licenses/MIT
+//
+//  Unless agreed to in writing, the subject software distributed under the License is distributed on an
+//  "AS-IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied. Refer to the
+//  License for the specific language governing permissions and limitations.
+//
+//  Code Modification History:
+//  ----------------------------------------------------------------------------------------------------
+//  04/01/2023 - J. Ritchie Carroll
+//       Generated original version of source code.
+//
+//******************************************************************************************************
+
+use crate::data::DataSet;
+use crate::measurement_queue::MeasurementQueue;
+use crate::transport::{
+    BasicMeasurement, BufferBlock, ConnectStatus, DataSubscriber, Measurement, MeasurementMetadata,
+    SubscriberConnector, SubscriptionInfo,
+};
+use crate::{Config, Settings, Ticks};
+use std::error::Error;
+use std::sync::{Arc, Mutex, Weak};
+use std::time::{Duration, SystemTime};
+
+type Callback<F> = Mutex<Option<Arc<F>>>;
+type NewMeasurementsHandler = dyn Fn(Vec<&dyn Measurement>) + Send + Sync;
+type NewBufferBlocksHandler = dyn Fn(Vec<&BufferBlock>) + Send + Sync;
+
+// Defines the user callbacks of a `Subscriber`. Callbacks are shared with the handlers registered
+// on the internal `DataSubscriber` so they can be assigned before or after a connection is dialed.
+#[derive(Default)]
+struct Receivers {
+    status_message_logger: Callback<dyn Fn(&str) + Send + Sync>,
+    error_message_logger: Callback<dyn Fn(&str) + Send + Sync>,
+    metadata_receiver: Callback<dyn Fn(&DataSet) + Send + Sync>,
+    data_start_time_receiver: Callback<dyn Fn(SystemTime) + Send + Sync>,
+    configuration_changed_receiver: Callback<dyn Fn() + Send + Sync>,
+    historical_read_complete_receiver: Callback<dyn Fn() + Send + Sync>,
+    notification_receiver: Callback<dyn Fn(&str) + Send + Sync>,
+    connection_established_receiver: Callback<dyn Fn() + Send + Sync>,
+    connection_terminated_receiver: Callback<dyn Fn() + Send + Sync>,
+    new_measurements_receiver: Callback<NewMeasurementsHandler>,
+    new_buffer_blocks_receiver: Callback<NewBufferBlocksHandler>,
+}
+
+impl Receivers {
+    fn status_message(&self, message: &str) {
+        if let Some(logger) = current(&self.status_message_logger) {
+            logger(message);
+        }
+    }
+
+    fn error_message(&self, message: &str) {
+        if let Some(logger) = current(&self.error_message_logger) {
+            logger(message);
+        }
+    }
+}
+
+/// Represents an STTP data subscriber.
+///
+/// The `Subscriber` exists as a simplified implementation of the `DataSubscriber` found
+/// in the transport module. The `Subscriber` is intended to simplify common uses of
+/// STTP data reception and maintains an internal instance of the `DataSubscriber` for
+/// subscription based functionality.
+///
+/// By default, status messages are written to standard output and error messages are
+/// written to standard error; use the logger setters to redirect them.
+pub struct Subscriber {
+    // Configuration applied on last dial
+    config: Mutex<Config>,
+
+    // Subscription applied to current and future connections
+    subscription: Mutex<SubscriptionInfo>,
+
+    // DataSubscriber reference, created on dial
+    ds: Mutex<Option<Arc<DataSubscriber>>>,
+
+    // Queue of received measurements for pull-based reading, created on dial when enabled
+    measurement_queue: Mutex<Option<Arc<MeasurementQueue>>>,
+
+    // Callback references
+    receivers: Arc<Receivers>,
+}
+
+impl Subscriber {
+    /// Creates a new `Subscriber`.
+    pub fn new() -> Self {
+        let subscriber = Subscriber {
+            config: Mutex::new(Config::default()),
+            subscription: Mutex::new(SubscriptionInfo::default()),
+            ds: Mutex::new(None),
+            measurement_queue: Mutex::new(None),
+            receivers: Arc::new(Receivers::default()),
+        };
+
+        subscriber.set_status_message_logger(|message| println!("{}", message));
+        subscriber.set_error_message_logger(|message| eprintln!("{}", message));
+
+        subscriber
+    }
+
+    /// Starts the client-based connection cycle to an STTP publisher. Address should be in the
+    /// format of "hostname:port", e.g., "localhost:7165".
+    ///
+    /// Upon connection, metadata is requested and/or a subscription is established according to
+    /// the `auto_request_metadata` and `auto_subscribe` settings of the provided `config`.
+    ///
+    /// Failed connection attempts are retried according to the `max_retries` and `retry_interval`
+    /// settings of the provided `config`, so this call blocks until a connection is established,
+    /// retries are exhausted or the `Subscriber` is closed.
+    pub fn dial(&self, address: &str, config: Config) -> Result<(), Box<dyn Error>> {
+        self.start(address, config, false)
+    }
+
+    /// Starts the listening-based connection cycle where an STTP publisher initiates the
+    /// connection, i.e., a reverse connection. Address should be in the format of
+    /// "interface:port", e.g., "0.0.0.0:7175" to listen on all IPv4 interfaces.
+    ///
+    /// Each time a publisher connects, metadata is requested and/or a subscription is
+    /// established according to the settings of the provided `config`.
+    pub fn listen(&self, address: &str, config: Config) -> Result<(), Box<dyn Error>> {
+        self.start(address, config, true)
+    }
+
+    fn start(&self, address: &str, config: Config, listen: bool) -> Result<(), Box<dyn Error>> {
+        let (hostname, port) = parse_address(address)?;
+
+        if self.is_connected() {
+            return Err("subscriber is already connected; close connection first".into());
+        }
+
+        // Release any previous connection before starting a new one
+        self.close();
+
+        let mut ds = DataSubscriber::new();
+
+        ds.compress_payload_data = config.compress_payload_data;
+        ds.compress_metadata = config.compress_metadata;
+        ds.compress_signal_index_cache = config.compress_signal_index_cache;
+        ds.version = config.version;
+        ds.security_mode = config.security_mode;
+        ds.tls_config = config.tls_config.clone();
+        ds.set_subscription(self.subscription.lock().unwrap().clone());
+
+        let ds = Arc::new(ds);
+
+        let measurement_queue = match config.measurement_queue_capacity {
+            0 => None,
+            capacity => Some(Arc::new(MeasurementQueue::new(
+                capacity,
+                config.measurement_queue_overflow,
+            ))),
+        };
+
+        self.register_callbacks(&ds, &config, measurement_queue.clone());
+        *self.measurement_queue.lock().unwrap() = measurement_queue;
+        *self.config.lock().unwrap() = config;
+        *self.ds.lock().unwrap() = Some(Arc::clone(&ds));
+
+        if listen {
+            return ds.listen(&hostname, port);
+        }
+
+        if let Ok(mut connector) = ds.connector.lock() {
+            connector.hostname = hostname;
+            connector.port = port;
+        }
+
+        // Connector retries failed attempts according to the configured retry settings
+        match SubscriberConnector::connect(&ds) {
+            ConnectStatus::Success => Ok(()),
+            ConnectStatus::Failed => Err(format!("failed to connect to \"{}\"", address).into()),
+            ConnectStatus::Canceled => Err("connection attempt was canceled".into()),
+        }
+    }
+
+    fn register_callbacks(
+        &self,
+        ds: &Arc<DataSubscriber>,
+        config: &Config,
+        measurement_queue: Option<Arc<MeasurementQueue>>,
+    ) {
+        let weak_ds = Arc::downgrade(ds);
+
+        match ds.connector.lock() {
+            Ok(mut connector) => {
+                connector.max_retries = config.max_retries;
+                connector.retry_interval = config.retry_interval;
+                connector.max_retry_interval = config.max_retry_interval;
+                connector.auto_reconnect = config.auto_reconnect;
+
+                let receivers = Arc::clone(&self.receivers);
+
+                connector.error_message_callback =
+                    Arc::new(move |message: String| receivers.error_message(&message));
+
+                let receivers = Arc::clone(&self.receivers);
+
+                // Metadata and subscription are requested again by the connection established
+                // handler, so a reconnect only needs to handle exhausted retry attempts
+                connector.reconnect_callback = Arc::new(move |ds: Arc<DataSubscriber>| {
+                    if !ds.is_connected() {
+                        ds.disconnect();
+                        receivers.status_message("Connection retry attempts exceeded.");
+                    }
+                });
+            }
+            Err(e) => self
+                .receivers
+                .error_message(&format!("failed to lock connector: {}", e)),
+        }
+
+        let receivers = Arc::clone(&self.receivers);
+
+        *ds.status_message_callback.lock().unwrap() = Some(Arc::new(move |message: &str| {
+            receivers.status_message(message)
+        }));
+
+        let receivers = Arc::clone(&self.receivers);
+
+        *ds.error_message_callback.lock().unwrap() = Some(Arc::new(move |message: &str| {
+            receivers.error_message(message)
+        }));
+
+        let receivers = Arc::clone(&self.receivers);
+        let auto_request_metadata = config.auto_request_metadata;
+        let auto_subscribe = config.auto_subscribe;
+        let metadata_filters = config.metadata_filters.clone();
+        let connected_ds = Weak::clone(&weak_ds);
+
+        *ds.connection_established_callback.lock().unwrap() = Some(Arc::new(move || {
+            if let Some(receiver) = current(&receivers.connection_established_receiver) {
+                receiver();
+            }
+
+            let ds = match connected_ds.upgrade() {
+                Some(ds) => ds,
+                None => return,
+            };
+
+            if auto_request_metadata {
+                ds.request_metadata(&metadata_filters);
+            } else if auto_subscribe {
+                subscribe_data(&ds, &receivers);
+            }
+        }));
+
+        let receivers = Arc::clone(&self.receivers);
+
+        *ds.connection_terminated_callback.lock().unwrap() = Some(Arc::new(move || {
+            if let Some(receiver) = current(&receivers.connection_terminated_receiver) {
+                receiver();
+            }
+        }));
+
+        let receivers = Arc::clone(&self.receivers);
+
+        *ds.metadata_parsed_callback.lock().unwrap() = Some(Arc::new(move |data_set: &DataSet| {
+            if let Some(receiver) = current(&receivers.metadata_receiver) {
+                receiver(data_set);
+            }
+        }));
+
+        // Subscription after metadata reception occurs even when metadata fails to parse
+        if auto_request_metadata && auto_subscribe {
+            let receivers = Arc::clone(&self.receivers);
+            let received_ds = Weak::clone(&weak_ds);
+
+            *ds.metadata_received_callback.lock().unwrap() = Some(Arc::new(move |_: &[u8]| {
+                if let Some(ds) = received_ds.upgrade() {
+                    subscribe_data(&ds, &receivers);
+                }
+            }));
+        }
+
+        let receivers = Arc::clone(&self.receivers);
+
+        *ds.data_start_time_callback.lock().unwrap() = Some(Arc::new(move |start_time: Ticks| {
+            if let Some(receiver) = current(&receivers.data_start_time_receiver) {
+                receiver(SystemTime::from(start_time.to_datetime()));
+            }
+        }));
+
+        let receivers = Arc::clone(&self.receivers);
+        let metadata_filters = config.metadata_filters.clone();
+
+        *ds.configuration_changed_callback.lock().unwrap() = Some(Arc::new(move || {
+            if let Some(receiver) = current(&receivers.configuration_changed_receiver) {
+                receiver();
+            }
+
+            // Refreshed metadata picks up any devices added or removed by the publisher
+            if auto_request_metadata {
+                if let Some(ds) = weak_ds.upgrade() {
+                    ds.request_metadata(&metadata_filters);
+                }
+            }
+        }));
+
+        let receivers = Arc::clone(&self.receivers);
+
+        *ds.processing_complete_callback.lock().unwrap() = Some(Arc::new(move |message: &str| {
+            receivers.status_message(message);
+
+            if let Some(receiver) = current(&receivers.historical_read_complete_receiver) {
+                receiver();
+            }
+        }));
+
+        let receivers = Arc::clone(&self.receivers);
+
+        *ds.notification_received_callback.lock().unwrap() =
+            Some(Arc::new(move |message: &str| {
+                if let Some(receiver) = current(&receivers.notification_receiver) {
+                    receiver(message);
+                }
+            }));
+
+        let receivers = Arc::clone(&self.receivers);
+
+        *ds.new_measurements_callback.lock().unwrap() =
+            Some(Arc::new(move |measurements: Vec<&dyn Measurement>| {
+                if let Some(queue) = &measurement_queue {
+                    queue.push(
+                        measurements
+                            .iter()
+                            .map(|m| BasicMeasurement::from(*m))
+                            .collect(),
+                    );
+                }
+
+                if let Some(receiver) = current(&receivers.new_measurements_receiver) {
+                    receiver(measurements);
+                }
+            }));
+
+        let receivers = Arc::clone(&self.receivers);
+
+        *ds.new_buffer_blocks_callback.lock().unwrap() =
+            Some(Arc::new(move |buffer_blocks: Vec<&BufferBlock>| {
+                if let Some(receiver) = current(&receivers.new_buffer_blocks_receiver) {
+                    receiver(buffer_blocks);
+                }
+            }));
+    }
+
+    /// Disconnects from the STTP publisher and releases the internal `DataSubscriber`. Any
+    /// measurements still queued for reading remain available to `read_measurements`.
+    pub fn close(&self) {
+        if let Some(queue) = self.measurement_queue.lock().unwrap().as_ref() {
+            queue.close();
+        }
+
+        let ds = self.ds.lock().unwrap().take();
+
+        if let Some(ds) = ds {
+            ds.dispose();
+        }
+    }
+
+    /// Subscribes to the STTP publisher using the specified filter expression and settings. When
+    /// not yet connected, the subscription is applied on the next successful `dial`.
+    pub fn subscribe(
+        &self,
+        filter_expression: &str,
+        settings: &Settings,
+    ) -> Result<(), Box<dyn Error>> {
+        let subscription = subscription_info(filter_expression, settings);
+
+        *self.subscription.lock().unwrap() = subscription.clone();
+
+        let ds = match self.data_subscriber() {
+            Some(ds) => ds,
+            None => return Ok(()),
+        };
+
+        ds.set_subscription(subscription);
+
+        if ds.is_connected() {
+            ds.subscribe()?;
+        }
+
+        Ok(())
+    }
+
+    /// Unsubscribes from the STTP publisher.
+    pub fn unsubscribe(&self) {
+        if let Some(ds) = self.data_subscriber() {
+            ds.unsubscribe();
+        }
+    }
+
+    /// Sends a request to the STTP publisher for metadata, applying any configured `metadata_filters`.
+    pub fn request_metadata(&self) {
+        if let Some(ds) = self.data_subscriber() {
+            ds.request_metadata(&self.config.lock().unwrap().metadata_filters);
+        }
+    }
+
+    /// Determines if the `Subscriber` is currently connected to an STTP publisher.
+    pub fn is_connected(&self) -> bool {
+        self.data_subscriber()
+            .map(|ds| ds.is_connected())
+            .unwrap_or(false)
+    }
+
+    /// Determines if the `Subscriber` is currently listening for a publisher connection.
+    pub fn is_listening(&self) -> bool {
+        self.data_subscriber()
+            .map(|ds| ds.is_listening())
+            .unwrap_or(false)
+    }
+
+    /// Determines if the `Subscriber` is currently subscribed to a data stream.
+    pub fn is_subscribed(&self) -> bool {
+        self.data_subscriber()
+            .map(|ds| ds.is_subscribed())
+            .unwrap_or(false)
+    }
+
+    /// Gets the `MeasurementMetadata` for the specified measurement as received in the latest metadata.
+    pub fn metadata(&self, measurement: &dyn Measurement) -> MeasurementMetadata {
+        match self.data_subscriber() {
+            Some(ds) => ds.metadata(measurement),
+            None => MeasurementMetadata::new(measurement.signal_id(), "", 0),
+        }
+    }
+
+    /// Gets the value of the specified measurement with the `adder` and `multiplier` of its metadata applied.
+    pub fn adjusted_value(&self, measurement: &dyn Measurement) -> f64 {
+        self.metadata(measurement)
+            .adjusted_value(measurement.value())
+    }
+
+    /// Reads the next batch of received measurements, waiting up to the specified `timeout`
+    /// for a batch to arrive. A `None` timeout waits indefinitely.
+    ///
+    /// Returns `Ok(None)` when the timeout expires, and an error when measurement queuing is not
+    /// enabled with `Config::measurement_queue_capacity` or once the `Subscriber` has been closed
+    /// and all queued measurements have been read.
+    pub fn read_measurements(
+        &self,
+        timeout: Option<Duration>,
+    ) -> Result<Option<Vec<BasicMeasurement>>, Box<dyn Error>> {
+        let queue = self.measurement_queue.lock().unwrap().clone();
+
+        match queue {
+            Some(queue) => queue.pop(timeout),
+            None => Err(
+                "measurement queue is not enabled; set measurement_queue_capacity before dial"
+                    .into(),
+            ),
+        }
+    }
+
+    /// Gets a blocking iterator over batches of received measurements. Iteration ends once the
+    /// `Subscriber` has been closed and all queued measurements have been read.
+    pub fn measurements(&self) -> MeasurementBatches<'_> {
+        MeasurementBatches { subscriber: self }
+    }
+
+    /// Gets the total number of received measurements discarded due to measurement queue overflow.
+    pub fn dropped_measurements(&self) -> u64 {
+        match self.measurement_queue.lock().unwrap().as_ref() {
+            Some(queue) => queue.dropped(),
+            None => 0,
+        }
+    }
+
+    fn data_subscriber(&self) -> Option<Arc<DataSubscriber>> {
+        self.ds.lock().unwrap().clone()
+    }
+
+    /// Defines the callback that handles informational message logging.
+    pub fn set_status_message_logger<F>(&self, logger: F)
+    where
+        F: Fn(&str) + Send + Sync + 'static,
+    {
+        *self.receivers.status_message_logger.lock().unwrap() = Some(Arc::new(logger));
+    }
+
+    /// Defines the callback that handles error message logging.
+    pub fn set_error_message_logger<F>(&self, logger: F)
+    where
+        F: Fn(&str) + Send + Sync + 'static,
+    {
+        *self.receivers.error_message_logger.lock().unwrap() = Some(Arc::new(logger));
+    }
+
+    /// Defines the callback that handles reception of the metadata response.
+    pub fn set_metadata_receiver<F>(&self, receiver: F)
+    where
+        F: Fn(&DataSet) + Send + Sync + 'static,
+    {
+        *self.receivers.metadata_receiver.lock().unwrap() = Some(Arc::new(receiver));
+    }
+
+    /// Defines the callback that handles reception of the start time of the data stream.
+    pub fn set_data_start_time_receiver<F>(&self, receiver: F)
+    where
+        F: Fn(SystemTime) + Send + Sync + 'static,
+    {
+        *self.receivers.data_start_time_receiver.lock().unwrap() = Some(Arc::new(receiver));
+    }
+
+    /// Defines the callback that handles notifications that the publisher configuration has changed.
+    pub fn set_configuration_changed_receiver<F>(&self, receiver: F)
+    where
+        F: Fn() + Send + Sync + 'static,
+    {
+        *self
+            .receivers
+            .configuration_changed_receiver
+            .lock()
+            .unwrap() = Some(Arc::new(receiver));
+    }
+
+    /// Defines the callback that handles notification that temporal processing has completed, i.e.,
+    /// the end of a historical playback data stream has been reached.
+    pub fn set_historical_read_complete_receiver<F>(&self, receiver: F)
+    where
+        F: Fn() + Send + Sync + 'static,
+    {
+        *self
+            .receivers
+            .historical_read_complete_receiver
+            .lock()
+            .unwrap() = Some(Arc::new(receiver));
+    }
+
+    /// Defines the callback that handles notifications sent by the publisher, e.g., a message
+    /// intended for display to the user. Receipt of each notification is confirmed automatically.
+    pub fn set_notification_receiver<F>(&self, receiver: F)
+    where
+        F: Fn(&str) + Send + Sync + 'static,
+    {
+        *self.receivers.notification_receiver.lock().unwrap() = Some(Arc::new(receiver));
+    }
+
+    /// Defines the callback that handles notification that a connection has been established.
+    pub fn set_connection_established_receiver<F>(&self, receiver: F)
+    where
+        F: Fn() + Send + Sync + 'static,
+    {
+        *self
+            .receivers
+            .connection_established_receiver
+            .lock()
+            .unwrap() = Some(Arc::new(receiver));
+    }
+
+    /// Defines the callback that handles notification that a connection has been terminated.
+    pub fn set_connection_terminated_receiver<F>(&self, receiver: F)
+    where
+        F: Fn() + Send + Sync + 'static,
+    {
+        *self
+            .receivers
+            .connection_terminated_receiver
+            .lock()
+            .unwrap() = Some(Arc::new(receiver));
+    }
+
+    /// Defines the callback that handles reception of new measurements.
+    pub fn set_new_measurements_receiver<F>(&self, receiver: F)
+    where
+        F: Fn(Vec<&dyn Measurement>) + Send + Sync + 'static,
+    {
+        *self.receivers.new_measurements_receiver.lock().unwrap() = Some(Arc::new(receiver));
+    }
+
+    /// Defines the callback that handles reception of new buffer blocks, i.e., binary data
+    /// published for a signal, in the order they were published.
+    pub fn set_new_buffer_blocks_receiver<F>(&self, receiver: F)
+    where
+        F: Fn(Vec<&BufferBlock>) + Send + Sync + 'static,
+    {
+        *self.receivers.new_buffer_blocks_receiver.lock().unwrap() = Some(Arc::new(receiver));
+    }
+}
+
+impl Default for Subscriber {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for Subscriber {
+    fn drop(&mut self) {
+        self.close();
+    }
+}
+
+/// Represents a blocking iterator over batches of measurements received by a `Subscriber`.
+pub struct MeasurementBatches<'a> {
+    subscriber: &'a Subscriber,
+}
+
+impl Iterator for MeasurementBatches<'_> {
+    type Item = Vec<BasicMeasurement>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.subscriber.read_measurements(None).ok().flatten()
+    }
+}
+
+// Gets the currently assigned callback, if any, so it can be invoked without holding its lock
+fn current<F: ?Sized>(callback: &Callback<F>) -> Option<Arc<F>> {
+    callback.lock().unwrap().clone()
+}
+
+fn subscribe_data(ds: &Arc<DataSubscriber>, receivers: &Receivers) {
+    if let Err(e) = ds.subscribe() {
+        receivers.error_message(&format!("failed to subscribe: {}", e));
+    }
+}
+
+// Maps simplified `Settings` onto the `SubscriptionInfo` used by a `DataSubscriber`
+fn subscription_info(filter_expression: &str, settings: &Settings) -> SubscriptionInfo {
+    SubscriptionInfo {
+        filter_expression: filter_expression.to_string(),
+        throttled: settings.throttled,
+        publish_interval: settings.publish_interval,
+        udp_data_channel: settings.udp_port > 0,
+        data_channel_local_port: settings.udp_port,
+        include_time: settings.include_time,
+        enable_time_reasonability_check: settings.enable_time_reasonability_check,
+        lag_time: settings.lag_time,
+        lead_time: settings.lead_time,
+        use_local_clock_as_real_time: settings.use_local_clock_as_real_time,
+        use_millisecond_resolution: settings.use_millisecond_resolution,
+        request_nan_value_filter: settings.request_nan_value_filter,
+        start_time: settings.start_time.clone(),
+        stop_time: settings.stop_time.clone(),
+        constraint_parameters: settings.constraint_parameters.clone(),
+        processing_interval: settings.processing_interval,
+        extra_connection_string_parameters: settings.extra_connection_string_parameters.clone(),
+        ..SubscriptionInfo::default()
+    }
+}
+
+// Parses an address in the format of "hostname:port" where an IPv6 hostname may be enclosed in brackets
+fn parse_address(address: &str) -> Result<(String, u16), Box<dyn Error>> {
+    let (hostname, port) = match address.trim().rsplit_once(':') {
+        Some(parts) => parts,
+        None => {
+            return Err(format!("address \"{}\" is missing a port number", address).into());
+        }
+    };
+
+    let hostname = hostname.trim_start_matches('[').trim_end_matches(']');
+
+    if hostname.is_empty() {
+        return Err(format!("address \"{}\" is missing a hostname", address).into());
+    }
+
+    let port = port
+        .parse::<u16>()
+        .map_err(|e| format!("address \"{}\" has an invalid port number: {}", address, e))?;
+
+    Ok((hostname.to_string(), port))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transport::ServerCommand;
+    use std::io::Read;
+    use std::net::TcpListener;
+    use std::sync::mpsc;
+    use std::time::Duration;
+
+    #[test]
+    fn test_settings_map_to_subscription_info() {
+        let settings = Settings {
+            udp_port: 9600,
+            throttled: true,
+            start_time: "*-5m".to_string(),
+            ..Settings::default()
+        };
+
+        let info = subscription_info(
+            "FILTER ActiveMeasurements WHERE SignalType = 'FREQ'",
+            &settings,
+        );
+
+        assert_eq!(
+            info.filter_expression,
+            "FILTER ActiveMeasurements WHERE SignalType = 'FREQ'"
+        );
+        assert!(info.udp_data_channel);
+        assert_eq!(info.data_channel_local_port, 9600);
+        assert!(info.throttled);
+        assert_eq!(info.start_time, "*-5m");
+
+        let info = subscription_info("PPA:1", &Settings::default());
+
+        assert!(!info.udp_data_channel);
+
+        assert_eq!(
+            parse_address("[::1]:7165").unwrap(),
+            ("::1".to_string(), 7165)
+        );
+        assert!(parse_address("localhost").is_err());
+    }
+
+    #[test]
+    fn test_dial_requests_filtered_metadata() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        // Publisher stand-in that reports the command code and payload of each received command
+        let (sender, receiver) = mpsc::channel();
+
+        let publisher = std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+
+            for _ in 0..2 {
+                let mut header = [0u8; 4];
+                stream.read_exact(&mut header).unwrap();
+
+                let mut packet = vec![0u8; u32::from_be_bytes(header) as usize];
+                stream.read_exact(&mut packet).unwrap();
+
+                sender.send(packet).unwrap();
+            }
+        });
+
+        let subscriber = Subscriber::new();
+        let (established_sender, established_receiver) = mpsc::channel();
+
+        subscriber.set_status_message_logger(|_| {});
+        subscriber.set_connection_established_receiver(move || {
+            established_sender.send(()).unwrap();
+        });
+
+        let config = Config {
+            metadata_filters: "FILTER MeasurementDetail WHERE SignalAcronym <> 'STAT'".to_string(),
+            ..Config::default()
+        };
+
+        subscriber
+            .dial(&format!("127.0.0.1:{}", port), config)
+            .unwrap();
+
+        let timeout = Duration::from_secs(5);
+
+        established_receiver.recv_timeout(timeout).unwrap();
+
+        let operational_modes = receiver.recv_timeout(timeout).unwrap();
+        assert_eq!(
+            operational_modes[0],
+            ServerCommand::DefineOperationalModes as u8
+        );
+
+        let metadata_refresh = receiver.recv_timeout(timeout).unwrap();
+        assert_eq!(metadata_refresh[0], ServerCommand::MetadataRefresh as u8);
+
+        let filters = &metadata_refresh[5..];
+        assert_eq!(
+            u32::from_be_bytes(metadata_refresh[1..5].try_into().unwrap()) as usize,
+            filters.len()
+        );
+        assert_eq!(
+            filters,
+            b"FILTER MeasurementDetail WHERE SignalAcronym <> 'STAT'"
+        );
+
+        publisher.join().unwrap();
+        subscriber.close();
+    }
+}
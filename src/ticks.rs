@@ -44,7 +44,7 @@ impl Ticks {
     pub const PER_SECOND: u64 = 10_000_000;
 
     /// Number of `Ticks` that occur in a millisecond.
-    pub const PER_MILLISECOND: u64 = Self::PER_SECOND;
+    pub const PER_MILLISECOND: u64 = Self::PER_SECOND / 1_000;
 
     /// Number of `Ticks` that occur in a microsecond.
     pub const PER_MICROSECOND: u64 = Self::PER_SECOND / 1_000_000;
@@ -75,6 +75,11 @@ impl Ticks {
         Self { val }
     }
 
+    /// Gets the full 64-bit `Ticks` value, including any leap second flags.
+    pub fn value(&self) -> u64 {
+        self.val
+    }
+
     /// Gets the timestamp portion of the `Ticks` value, i.e.,
     /// the 62-bit time value excluding any leap second flags.
     pub fn timestamp_value(&self) -> u64 {
@@ -87,7 +92,7 @@ impl Ticks {
         DateTime<Tz>: From<SystemTime>,
     {
         let duration = dt
-            .signed_duration_since::<Tz>(UNIX_EPOCH.into())
+            .signed_duration_since::<Tz>(DateTime::<Tz>::from(UNIX_EPOCH))
             .to_std()
             .unwrap_or_default();
 
@@ -158,26 +163,22 @@ impl Ticks {
         Self::from_datetime(now)
     }
 
-    /// Standard timestamp representation for a `Ticks` value, e.g., 2006-01-02 15:04:05.999999999.
-    pub fn to_string(&self) -> String {
-        let result = self
-            .to_datetime()
-            .to_rfc3339_opts(chrono::SecondsFormat::Nanos, true)
-            .replace("T", " ");
-        result[..result.len() - 1].to_string()
-    }
-
     /// Shows just the timestamp portion of a `Ticks` value with milliseconds, e.g., 15:04:05.999.
     pub fn to_short_string(&self) -> String {
         let datetime_str = self.to_string();
-        let result = datetime_str.split(" ").nth(1).unwrap_or("").to_string();
+        let result = datetime_str.split(' ').nth(1).unwrap_or("").to_string();
         result[..result.len() - 6].to_string()
     }
 }
 
+/// Standard timestamp representation for a `Ticks` value, e.g., 2006-01-02 15:04:05.999999999.
 impl Display for Ticks {
     fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
-        write!(f, "{}", self.to_string())
+        let result = self
+            .to_datetime()
+            .to_rfc3339_opts(chrono::SecondsFormat::Nanos, true)
+            .replace('T', " ");
+        write!(f, "{}", &result[..result.len() - 1])
     }
 }
 
@@ -30,20 +30,43 @@ pub use crate::transport::constants::*;
 mod measurement;
 pub use crate::transport::measurement::*;
 
+mod measurement_metadata;
+pub use crate::transport::measurement_metadata::MeasurementMetadata; // >> sttp::transport::MeasurementMetadata
+
 mod basic_measurement;
 pub use crate::transport::basic_measurement::BasicMeasurement; // >> sttp::transport::BasicMeasurement
 
 mod compact_measurement;
 pub use crate::transport::compact_measurement::CompactMeasurement; // >> sttp::transport::CompactMeasurement
 
+mod buffer_block;
+pub use crate::transport::buffer_block::BufferBlock; // >> sttp::transport::BufferBlock
+
 mod signal_index_cache;
 pub use crate::transport::signal_index_cache::SignalIndexCache; // >> sttp::transport::SignalIndexCache
 
+mod command_result;
+pub use crate::transport::command_result::CommandResult; // >> sttp::transport::CommandResult
+
+pub mod tssc;
+
+pub mod compression;
+
+pub mod cipher;
+
+mod tls_config;
+pub use crate::transport::tls_config::TlsConfig; // >> sttp::transport::TlsConfig
+
+mod tls;
+
 mod subscription_info;
 pub use crate::transport::subscription_info::SubscriptionInfo; // >> sttp::transport::SubscriptionInfo
 
 mod data_subscriber;
 pub use crate::transport::data_subscriber::DataSubscriber; // >> sttp::transport::DataSubscriber
 
+mod data_publisher;
+pub use crate::transport::data_publisher::DataPublisher; // >> sttp::transport::DataPublisher
+
 mod subscriber_connector;
 pub use crate::transport::subscriber_connector::SubscriberConnector; // >> sttp::transport::SubscriberConnector
@@ -78,6 +78,17 @@ impl Measurement for BasicMeasurement {
     }
 }
 
+impl From<&dyn Measurement> for BasicMeasurement {
+    fn from(measurement: &dyn Measurement) -> Self {
+        BasicMeasurement {
+            signal_id: measurement.signal_id(),
+            value: measurement.value(),
+            timestamp: measurement.timestamp(),
+            flags: measurement.flags(),
+        }
+    }
+}
+
 impl Display for BasicMeasurement {
     fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
         Measurement::fmt(self, f)
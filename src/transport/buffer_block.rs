@@ -0,0 +1,42 @@
+//******************************************************************************************************
+//  buffer_block.rs - Gbtc
+//
+//  Copyright © 2023, Grid Protection Alliance.  All Rights Reserved.
+//
+//  Licensed to the Grid Protection Alliance (GPA) under one or more contributor license agreements. See
+//  the NOTICE file distributed with this work for additional information regarding copyright ownership.
+//  The GPA licenses this file to you under the MIT License (MIT), the "License"; you may not use this
+//  file except in compliance with the License. You may obtain a copy of the License at:
+//
+//      http://opensource.org/licenses/MIT
+//
+//  Unless agreed to in writing, the subject software distributed under the License is distributed on an
+//  "AS-IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied. Refer to the
+//  License for the specific language governing permissions and limitations.
+//
+//  Code Modification History:
+//  ----------------------------------------------------------------------------------------------------
+//  04/01/2023 - J. Ritchie Carroll
+//       Generated original version of source code.
+//
+//******************************************************************************************************
+
+use std::fmt::{Display, Formatter, Result as FmtResult};
+use uuid::Uuid;
+
+/// Represents a block of opaque binary data, e.g., a file or a data record, published for a
+/// signal by a `DataPublisher`. Buffer blocks are delivered in the order they were published.
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+pub struct BufferBlock {
+    /// Gets the signal ID of the measurement the buffer block was published for.
+    pub signal_id: Uuid,
+
+    /// Gets the binary data of the buffer block.
+    pub buffer: Vec<u8>,
+}
+
+impl Display for BufferBlock {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(f, "{} [{} bytes]", self.signal_id, self.buffer.len())
+    }
+}
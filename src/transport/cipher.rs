@@ -0,0 +1,152 @@
+//******************************************************************************************************
+//  cipher.rs - Gbtc
+//
+//  Copyright © 2023, Grid Protection Alliance.  All Rights Reserved.
+//
+//  Licensed to the Grid Protection Alliance (GPA) under one or more contributor license agreements. See
+//  the NOTICE file distributed with this work for additional information regarding copyright ownership.
+//  The GPA licenses this file to you under the MIT License (MIT), the "License"; you may not use this
+//  file except in compliance with the License. You may obtain a copy of the License at:
+//
+//      http://opensource.org/licenses/MIT
+//
+//  Unless agreed to in writing, the subject software distributed under the License is distributed on an
+//  "AS-IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied. Refer to the
+//  License for the specific language governing permissions and limitations.
+//
+//  Code Modification History:
+//  ----------------------------------------------------------------------------------------------------
+//  04/01/2023 - J. Ritchie Carroll
+//       Generated original version of source code.
+//
+//******************************************************************************************************
+//! AES encryption used by STTP for data packets sent over a UDP data channel.
+//!
+//! Data packets are encrypted using AES in CBC mode with PKCS#7 padding, where the key size, i.e.,
+//! 128, 192 or 256 bits, is determined by the length of the key provided by the publisher. Cipher
+//! support requires the `aes` cargo feature, enabled by default. When the feature is disabled, all
+//! functions in this module return an error.
+
+use std::error::Error;
+
+/// Decrypts an AES-CBC encrypted buffer using the specified key and initialization vector.
+pub fn aes_decrypt(key: &[u8], iv: &[u8], data: &[u8]) -> Result<Vec<u8>, Box<dyn Error>> {
+    imp::aes_decrypt(key, iv, data)
+}
+
+/// Encrypts a buffer with AES-CBC using the specified key and initialization vector.
+pub fn aes_encrypt(key: &[u8], iv: &[u8], data: &[u8]) -> Result<Vec<u8>, Box<dyn Error>> {
+    imp::aes_encrypt(key, iv, data)
+}
+
+#[cfg(feature = "aes")]
+mod imp {
+    use aes::{Aes128, Aes192, Aes256};
+    use cbc::cipher::block_padding::Pkcs7;
+    use cbc::cipher::{BlockDecryptMut, BlockEncryptMut, KeyIvInit};
+    use cbc::{Decryptor, Encryptor};
+    use std::error::Error;
+
+    pub(super) fn aes_decrypt(
+        key: &[u8],
+        iv: &[u8],
+        data: &[u8],
+    ) -> Result<Vec<u8>, Box<dyn Error>> {
+        match key.len() {
+            16 => decrypt::<Decryptor<Aes128>>(key, iv, data),
+            24 => decrypt::<Decryptor<Aes192>>(key, iv, data),
+            32 => decrypt::<Decryptor<Aes256>>(key, iv, data),
+            length => Err(invalid_key_length(length)),
+        }
+    }
+
+    pub(super) fn aes_encrypt(
+        key: &[u8],
+        iv: &[u8],
+        data: &[u8],
+    ) -> Result<Vec<u8>, Box<dyn Error>> {
+        match key.len() {
+            16 => encrypt::<Encryptor<Aes128>>(key, iv, data),
+            24 => encrypt::<Encryptor<Aes192>>(key, iv, data),
+            32 => encrypt::<Encryptor<Aes256>>(key, iv, data),
+            length => Err(invalid_key_length(length)),
+        }
+    }
+
+    fn decrypt<D>(key: &[u8], iv: &[u8], data: &[u8]) -> Result<Vec<u8>, Box<dyn Error>>
+    where
+        D: KeyIvInit + BlockDecryptMut,
+    {
+        let decryptor = D::new_from_slices(key, iv)
+            .map_err(|_| format!("invalid AES initialization vector length: {}", iv.len()))?;
+
+        decryptor
+            .decrypt_padded_vec_mut::<Pkcs7>(data)
+            .map_err(|_| "failed to decrypt AES payload: invalid length or padding".into())
+    }
+
+    fn encrypt<E>(key: &[u8], iv: &[u8], data: &[u8]) -> Result<Vec<u8>, Box<dyn Error>>
+    where
+        E: KeyIvInit + BlockEncryptMut,
+    {
+        let encryptor = E::new_from_slices(key, iv)
+            .map_err(|_| format!("invalid AES initialization vector length: {}", iv.len()))?;
+
+        Ok(encryptor.encrypt_padded_vec_mut::<Pkcs7>(data))
+    }
+
+    fn invalid_key_length(length: usize) -> Box<dyn Error> {
+        format!(
+            "invalid AES key length: {} bytes, expected 16, 24 or 32",
+            length
+        )
+        .into()
+    }
+}
+
+#[cfg(not(feature = "aes"))]
+mod imp {
+    use std::error::Error;
+
+    const FEATURE_DISABLED: &str =
+        "STTP data packet encryption support requires the \"aes\" feature";
+
+    pub(super) fn aes_decrypt(
+        _key: &[u8],
+        _iv: &[u8],
+        _data: &[u8],
+    ) -> Result<Vec<u8>, Box<dyn Error>> {
+        Err(FEATURE_DISABLED.into())
+    }
+
+    pub(super) fn aes_encrypt(
+        _key: &[u8],
+        _iv: &[u8],
+        _data: &[u8],
+    ) -> Result<Vec<u8>, Box<dyn Error>> {
+        Err(FEATURE_DISABLED.into())
+    }
+}
+
+#[cfg(all(test, feature = "aes"))]
+mod tests {
+    use super::*;
+
+    const TEST_DATA: &[u8] = b"\x00\x00\x00\x02compact measurement payload";
+
+    #[test]
+    fn test_aes_round_trip() {
+        let iv = [0x5Au8; 16];
+
+        for key_length in [16, 24, 32] {
+            let key = vec![0xA5u8; key_length];
+            let encrypted = aes_encrypt(&key, &iv, TEST_DATA).unwrap();
+
+            assert_eq!(encrypted.len() % 16, 0);
+            assert_ne!(&encrypted[..TEST_DATA.len()], TEST_DATA);
+            assert_eq!(aes_decrypt(&key, &iv, &encrypted).unwrap(), TEST_DATA);
+        }
+
+        assert!(aes_decrypt(&[0u8; 20], &iv, TEST_DATA).is_err());
+    }
+}
@@ -0,0 +1,67 @@
+//******************************************************************************************************
+//  command_result.rs - Gbtc
+//
+//  Copyright © 2023, Grid Protection Alliance.  All Rights Reserved.
+//
+//  Licensed to the Grid Protection Alliance (GPA) under one or more contributor license agreements. See
+//  the NOTICE file distributed with this work for additional information regarding copyright ownership.
+//  The GPA licenses this file to you under the MIT License (MIT), the "License"; you may not use this
+//  file except in compliance with the License. You may obtain a copy of the License at:
+//
+//      http://opensource.org/licenses/MIT
+//
+//  Unless agreed to in writing, the subject software distributed under the License is distributed on an
+//  "AS-IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied. Refer to the
+//  License for the specific language governing permissions and limitations.
+//
+//  Code Modification History:
+//  ----------------------------------------------------------------------------------------------------
+//  04/01/2023 - J. Ritchie Carroll
+//       Generated original version of source code.
+//
+//******************************************************************************************************
+
+use crate::transport::ServerCommand;
+use std::fmt::{Display, Formatter, Result as FmtResult};
+
+/// Represents the outcome of a `ServerCommand` as reported by a `DataPublisher` with a
+/// `ServerResponse::Succeeded` or `ServerResponse::Failed` response code.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CommandResult {
+    /// Gets the server command that the `DataPublisher` responded to.
+    pub command: ServerCommand,
+
+    /// Gets flag that determines if the `DataPublisher` reported success for the command.
+    pub succeeded: bool,
+
+    /// Gets the message that accompanied the response, if any. For a successful `MetadataRefresh`
+    /// the message is empty since the response payload is delivered as metadata instead.
+    pub message: String,
+}
+
+impl CommandResult {
+    /// Converts the `CommandResult` into a standard `Result`, with the failure message as the error.
+    pub fn into_result(self) -> Result<String, String> {
+        if self.succeeded {
+            Ok(self.message)
+        } else {
+            Err(self.message)
+        }
+    }
+}
+
+impl Display for CommandResult {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        let outcome = if self.succeeded {
+            "succeeded"
+        } else {
+            "failed"
+        };
+
+        if self.message.is_empty() {
+            write!(f, "{:?} {}", self.command, outcome)
+        } else {
+            write!(f, "{:?} {}: {}", self.command, outcome, self.message)
+        }
+    }
+}
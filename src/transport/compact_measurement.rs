@@ -144,7 +144,8 @@ impl CompactStateFlags {
     }
 }
 
-const FIXED_LENGTH: usize = 9;
+// Gets the encoded length of a compact measurement without any timestamp
+pub(crate) const FIXED_LENGTH: usize = 9;
 
 /// Represents a compact `Measurement` for transmission or reception in the STTP API.
 #[derive(Debug, Clone)]
@@ -173,15 +174,21 @@ impl CompactMeasurement {
             value: 0.0,
             timestamp: Ticks::default(),
             flags: StateFlags::NORMAL,
-            signal_index_cache: signal_index_cache,
-            include_time: include_time,
+            signal_index_cache,
+            include_time,
             base_time_offsets: [0, 0],
             time_index: 0,
-            use_millisecond_resolution: use_millisecond_resolution,
+            use_millisecond_resolution,
             using_base_time_offset: false,
         }
     }
 
+    /// Sets the base time offsets, in ticks, that offset compressed timestamps are relative to.
+    /// The compact state flags of each measurement select which of the two offsets applies.
+    pub fn set_base_time_offsets(&mut self, base_time_offsets: [u64; 2]) {
+        self.base_time_offsets = base_time_offsets;
+    }
+
     /// Gets flag that determines if time is serialized into measurement binary image.
     pub fn include_time(&self) -> bool {
         self.include_time
@@ -278,6 +285,32 @@ impl CompactMeasurement {
         self.signal_id = self.signal_index_cache.signal_id(signal_index);
     }
 
+    /// Serializes the `CompactMeasurement` into a byte buffer for transmission.
+    pub fn encode(&mut self) -> Vec<u8> {
+        // Binary length also determines if timestamp can be offset from a base time
+        let mut buffer = Vec::with_capacity(self.get_binary_length());
+
+        buffer.push(self.get_compact_state_flags());
+        buffer.extend_from_slice(&self.get_runtime_id().to_be_bytes());
+        buffer.extend_from_slice(&(self.value as f32).to_be_bytes());
+
+        if !self.include_time {
+            return buffer;
+        }
+
+        if self.using_base_time_offset {
+            if self.use_millisecond_resolution {
+                buffer.extend_from_slice(&self.get_timestamp_c2().to_be_bytes());
+            } else {
+                buffer.extend_from_slice(&self.get_timestamp_c4().to_be_bytes());
+            }
+        } else {
+            buffer.extend_from_slice(&self.timestamp.value().to_be_bytes());
+        }
+
+        buffer
+    }
+
     /// parses a CompactMeasurement from the specified byte buffer.
     pub fn decode(&mut self, buffer: &[u8]) -> Result<usize, Box<dyn Error>> {
         if buffer.len() < FIXED_LENGTH {
@@ -298,12 +331,12 @@ impl CompactMeasurement {
         index += 1;
 
         // Decode runtime ID
-        let bytes: [u8; 4] = buffer[index..].try_into()?;
+        let bytes: [u8; 4] = buffer[index..index + 4].try_into()?;
         self.set_runtime_id(i32::from_be_bytes(bytes));
         index += 4;
 
         // Decode value
-        let bytes: [u8; 4] = buffer[index..].try_into()?;
+        let bytes: [u8; 4] = buffer[index..index + 4].try_into()?;
         self.value = f32::from_be_bytes(bytes) as f64;
         index += 4;
 
@@ -317,26 +350,41 @@ impl CompactMeasurement {
             if self.use_millisecond_resolution {
                 // Decode 2-byte millisecond offset timestamp
                 if base_time_offset > 0 {
-                    let bytes: [u8; 2] = buffer[index..].try_into()?;
+                    let bytes: [u8; 2] = buffer
+                        .get(index..index + 2)
+                        .ok_or("Not enough buffer available to deserialize compact measurement timestamp")?
+                        .try_into()?;
                     self.timestamp = Ticks::new(
                         base_time_offset
-                            + (u64::from(u16::from_be_bytes(bytes)) * Ticks::PER_MILLISECOND),
+                            .checked_add(
+                                u64::from(u16::from_be_bytes(bytes)) * Ticks::PER_MILLISECOND,
+                            )
+                            .ok_or("Compact measurement timestamp offset overflows base time")?,
                     );
                 }
                 index += 2;
             } else {
                 // Decode 4-byte tick offset timestamp
                 if base_time_offset > 0 {
-                    let bytes: [u8; 4] = buffer[index..].try_into()?;
-                    self.timestamp =
-                        Ticks::new(base_time_offset + u64::from(u32::from_be_bytes(bytes)));
+                    let bytes: [u8; 4] = buffer
+                        .get(index..index + 4)
+                        .ok_or("Not enough buffer available to deserialize compact measurement timestamp")?
+                        .try_into()?;
+                    self.timestamp = Ticks::new(
+                        base_time_offset
+                            .checked_add(u64::from(u32::from_be_bytes(bytes)))
+                            .ok_or("Compact measurement timestamp offset overflows base time")?,
+                    );
                 }
                 index += 4;
             }
         } else {
             // Decode 8-byte full fidelity timestamp
             // Note that only a full fidelity timestamp can carry leap second flags
-            let bytes: [u8; 8] = buffer[index..].try_into()?;
+            let bytes: [u8; 8] = buffer
+                .get(index..index + 8)
+                .ok_or("Not enough buffer available to deserialize compact measurement timestamp")?
+                .try_into()?;
             self.timestamp = Ticks::new(u64::from_be_bytes(bytes));
             index += 8;
         }
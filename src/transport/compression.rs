@@ -0,0 +1,134 @@
+//******************************************************************************************************
+//  compression.rs - Gbtc
+//
+//  Copyright © 2023, Grid Protection Alliance.  All Rights Reserved.
+//
+//  Licensed to the Grid Protection Alliance (GPA) under one or more contributor license agreements. See
+//  the NOTICE file distributed with this work for additional information regarding copyright ownership.
+//  The GPA licenses this file to you under the MIT License (MIT), the "License"; you may not use this
+//  file except in compliance with the License. You may obtain a copy of the License at:
+//
+//      http://opensource.org/licenses/MIT
+//
+//  Unless agreed to in writing, the subject software distributed under the License is distributed on an
+//  "AS-IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied. Refer to the
+//  License for the specific language governing permissions and limitations.
+//
+//  Code Modification History:
+//  ----------------------------------------------------------------------------------------------------
+//  04/01/2023 - J. Ritchie Carroll
+//       Generated original version of source code.
+//
+//******************************************************************************************************
+
+//! GZip and Deflate compression used by STTP for metadata and signal index cache payloads.
+//!
+//! Compression support requires the `gzip` cargo feature, enabled by default, which uses a pure-Rust
+//! backend. When the feature is disabled, all functions in this module return an error.
+
+use std::error::Error;
+
+/// Determines if the provided buffer starts with a GZip header.
+pub fn is_gzip(data: &[u8]) -> bool {
+    data.len() > 2 && data[0] == 0x1F && data[1] == 0x8B
+}
+
+/// Decompresses a GZip compressed buffer.
+pub fn gzip_decompress(data: &[u8]) -> Result<Vec<u8>, Box<dyn Error>> {
+    imp::gzip_decompress(data)
+}
+
+/// Compresses a buffer using GZip.
+pub fn gzip_compress(data: &[u8]) -> Result<Vec<u8>, Box<dyn Error>> {
+    imp::gzip_compress(data)
+}
+
+/// Decompresses a raw Deflate compressed buffer.
+pub fn deflate_decompress(data: &[u8]) -> Result<Vec<u8>, Box<dyn Error>> {
+    imp::deflate_decompress(data)
+}
+
+/// Compresses a buffer using raw Deflate.
+pub fn deflate_compress(data: &[u8]) -> Result<Vec<u8>, Box<dyn Error>> {
+    imp::deflate_compress(data)
+}
+
+#[cfg(feature = "gzip")]
+mod imp {
+    use flate2::read::{DeflateDecoder, GzDecoder};
+    use flate2::write::{DeflateEncoder, GzEncoder};
+    use flate2::Compression;
+    use std::error::Error;
+    use std::io::{Read, Write};
+
+    pub(super) fn gzip_decompress(data: &[u8]) -> Result<Vec<u8>, Box<dyn Error>> {
+        let mut buffer = Vec::new();
+        GzDecoder::new(data).read_to_end(&mut buffer)?;
+        Ok(buffer)
+    }
+
+    pub(super) fn gzip_compress(data: &[u8]) -> Result<Vec<u8>, Box<dyn Error>> {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(data)?;
+        Ok(encoder.finish()?)
+    }
+
+    pub(super) fn deflate_decompress(data: &[u8]) -> Result<Vec<u8>, Box<dyn Error>> {
+        let mut buffer = Vec::new();
+        DeflateDecoder::new(data).read_to_end(&mut buffer)?;
+        Ok(buffer)
+    }
+
+    pub(super) fn deflate_compress(data: &[u8]) -> Result<Vec<u8>, Box<dyn Error>> {
+        let mut encoder = DeflateEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(data)?;
+        Ok(encoder.finish()?)
+    }
+}
+
+#[cfg(not(feature = "gzip"))]
+mod imp {
+    use std::error::Error;
+
+    const FEATURE_DISABLED: &str = "STTP compression support requires the \"gzip\" feature";
+
+    pub(super) fn gzip_decompress(_data: &[u8]) -> Result<Vec<u8>, Box<dyn Error>> {
+        Err(FEATURE_DISABLED.into())
+    }
+
+    pub(super) fn gzip_compress(_data: &[u8]) -> Result<Vec<u8>, Box<dyn Error>> {
+        Err(FEATURE_DISABLED.into())
+    }
+
+    pub(super) fn deflate_decompress(_data: &[u8]) -> Result<Vec<u8>, Box<dyn Error>> {
+        Err(FEATURE_DISABLED.into())
+    }
+
+    pub(super) fn deflate_compress(_data: &[u8]) -> Result<Vec<u8>, Box<dyn Error>> {
+        Err(FEATURE_DISABLED.into())
+    }
+}
+
+#[cfg(all(test, feature = "gzip"))]
+mod tests {
+    use super::*;
+
+    const TEST_DATA: &[u8] =
+        b"<DataSet><MeasurementDetail><SignalID>...</SignalID></MeasurementDetail></DataSet>";
+
+    #[test]
+    fn test_gzip_round_trip() {
+        let compressed = gzip_compress(TEST_DATA).unwrap();
+
+        assert!(is_gzip(&compressed));
+        assert_eq!(gzip_decompress(&compressed).unwrap(), TEST_DATA);
+    }
+
+    #[test]
+    fn test_deflate_round_trip() {
+        let compressed = deflate_compress(TEST_DATA).unwrap();
+
+        assert!(!is_gzip(&compressed));
+        assert_eq!(deflate_decompress(&compressed).unwrap(), TEST_DATA);
+    }
+}
@@ -21,6 +21,9 @@
 //
 //******************************************************************************************************
 
+// CompressionModes is deprecated, but still defined here for backwards compatibility
+#![allow(deprecated)]
+
 use bitflags::bitflags;
 
 /// Defines default values for various STTP settings.
@@ -61,6 +64,12 @@ impl Defaults {
     /// Default for socket timeout in seconds.
     pub const SOCKET_TIMEOUT: f64 = 2.0;
 
+    /// Default for receive internal metadata flag.
+    pub const RECEIVE_INTERNAL_METADATA: bool = true;
+
+    /// Default for receive external metadata flag.
+    pub const RECEIVE_EXTERNAL_METADATA: bool = false;
+
     /// Default for STTP version.
     pub const VERSION: u8 = 2;
 
@@ -263,7 +272,7 @@ bitflags! {
 /// associated success or failure message. Message type for successful responses will be based  on server command - for example,
 /// server response for a successful MetaDataRefresh command will return a serialized `DataSet` of the available server metadata.
 /// Message type for failed responses will always be a string of text representing the error message.
-#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
 #[repr(u8)]
 pub enum ServerCommand {
     // Although the server commands and responses will be on two different paths, the response enumeration values
@@ -384,6 +393,58 @@ pub enum ServerCommand {
     UserCommand15 = 0xDF,
 }
 
+impl ServerCommand {
+    /// Gets the user-defined `ServerCommand` for the specified index, 0 through 15.
+    pub fn user_command(index: u8) -> Option<ServerCommand> {
+        if index > 15 {
+            return None;
+        }
+
+        ServerCommand::try_from(ServerCommand::UserCommand00 as u8 + index).ok()
+    }
+}
+
+impl TryFrom<u8> for ServerCommand {
+    type Error = String;
+
+    /// Attempts to convert a wire-level code into a `ServerCommand`.
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0x00 => Ok(ServerCommand::Connect),
+            0x01 => Ok(ServerCommand::MetadataRefresh),
+            0x02 => Ok(ServerCommand::Subscribe),
+            0x03 => Ok(ServerCommand::Unsubscribe),
+            0x04 => Ok(ServerCommand::RotateCipherKeys),
+            0x05 => Ok(ServerCommand::UpdateProcessingInterval),
+            0x06 => Ok(ServerCommand::DefineOperationalModes),
+            0x07 => Ok(ServerCommand::ConfirmNotification),
+            0x08 => Ok(ServerCommand::ConfirmBufferBlock),
+            0x09 => Ok(ServerCommand::ConfirmUpdateBaseTimes),
+            0x0A => Ok(ServerCommand::ConfirmUpdateSignalIndexCache),
+            0x0B => Ok(ServerCommand::ConfirmUpdateCipherKeys),
+            0x0C => Ok(ServerCommand::GetPrimaryMetadataSchema),
+            0x0D => Ok(ServerCommand::GetSignalSelectionSchema),
+            0xD0 => Ok(ServerCommand::UserCommand00),
+            0xD1 => Ok(ServerCommand::UserCommand01),
+            0xD2 => Ok(ServerCommand::UserCommand02),
+            0xD3 => Ok(ServerCommand::UserCommand03),
+            0xD4 => Ok(ServerCommand::UserCommand04),
+            0xD5 => Ok(ServerCommand::UserCommand05),
+            0xD6 => Ok(ServerCommand::UserCommand06),
+            0xD7 => Ok(ServerCommand::UserCommand07),
+            0xD8 => Ok(ServerCommand::UserCommand08),
+            0xD9 => Ok(ServerCommand::UserCommand09),
+            0xDA => Ok(ServerCommand::UserCommand10),
+            0xDB => Ok(ServerCommand::UserCommand11),
+            0xDC => Ok(ServerCommand::UserCommand12),
+            0xDD => Ok(ServerCommand::UserCommand13),
+            0xDE => Ok(ServerCommand::UserCommand14),
+            0xDF => Ok(ServerCommand::UserCommand15),
+            _ => Err(format!("unrecognized server command code: 0x{:02X}", value)),
+        }
+    }
+}
+
 /// Enumeration of the possible server responses sent by `DataPublisher` and received by `DataSubscriber`
 /// during an STTP session.
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
@@ -503,6 +564,62 @@ pub enum ServerResponse {
     NoOP = 0xFF,
 }
 
+impl ServerResponse {
+    /// Gets the index, 0 through 15, of a user-defined `ServerResponse`; otherwise, `None`.
+    pub fn user_response_index(self) -> Option<u8> {
+        let code = self as u8;
+        let first = ServerResponse::UserResponse00 as u8;
+
+        if (first..=ServerResponse::UserResponse15 as u8).contains(&code) {
+            Some(code - first)
+        } else {
+            None
+        }
+    }
+}
+
+impl TryFrom<u8> for ServerResponse {
+    type Error = String;
+
+    /// Attempts to convert a wire-level code into a `ServerResponse`.
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0x80 => Ok(ServerResponse::Succeeded),
+            0x81 => Ok(ServerResponse::Failed),
+            0x82 => Ok(ServerResponse::DataPacket),
+            0x83 => Ok(ServerResponse::UpdateSignalIndexCache),
+            0x84 => Ok(ServerResponse::UpdateBaseTimes),
+            0x85 => Ok(ServerResponse::UpdateCipherKeys),
+            0x86 => Ok(ServerResponse::DataStartTime),
+            0x87 => Ok(ServerResponse::ProcessingComplete),
+            0x88 => Ok(ServerResponse::BufferBlock),
+            0x89 => Ok(ServerResponse::Notify),
+            0x8A => Ok(ServerResponse::ConfigurationChanged),
+            0xE0 => Ok(ServerResponse::UserResponse00),
+            0xE1 => Ok(ServerResponse::UserResponse01),
+            0xE2 => Ok(ServerResponse::UserResponse02),
+            0xE3 => Ok(ServerResponse::UserResponse03),
+            0xE4 => Ok(ServerResponse::UserResponse04),
+            0xE5 => Ok(ServerResponse::UserResponse05),
+            0xE6 => Ok(ServerResponse::UserResponse06),
+            0xE7 => Ok(ServerResponse::UserResponse07),
+            0xE8 => Ok(ServerResponse::UserResponse08),
+            0xE9 => Ok(ServerResponse::UserResponse09),
+            0xEA => Ok(ServerResponse::UserResponse10),
+            0xEB => Ok(ServerResponse::UserResponse11),
+            0xEC => Ok(ServerResponse::UserResponse12),
+            0xED => Ok(ServerResponse::UserResponse13),
+            0xEE => Ok(ServerResponse::UserResponse14),
+            0xEF => Ok(ServerResponse::UserResponse15),
+            0xFF => Ok(ServerResponse::NoOP),
+            _ => Err(format!(
+                "unrecognized server response code: 0x{:02X}",
+                value
+            )),
+        }
+    }
+}
+
 bitflags! {
     /// Enumeration of the possible modes that affect how `DataPublisher` and `DataSubscriber` communicate during as STTP session.
     ///
@@ -559,6 +676,13 @@ bitflags! {
     }
 }
 
+// Gets the protocol version from operational modes, excluding legacy compression mode bits
+pub(crate) fn operational_modes_version(operational_modes: OperationalModes) -> u8 {
+    (operational_modes.bits()
+        & OperationalModes::VersionMask.bits()
+        & !CompressionModes::all().bits()) as u8
+}
+
 /// Enumeration of the possible string encoding options of an STTP session.
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
 #[repr(u32)]
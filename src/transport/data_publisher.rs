@@ -0,0 +1,1254 @@
+//******************************************************************************************************
+//  data_publisher.rs - Gbtc
+//
+//  Copyright © 2023, Grid Protection Alliance.  All Rights Reserved.
+//
+//  Licensed to the Grid Protection Alliance (GPA) under one or more contributor license agreements. See
+//  the NOTICE file distributed with this work for additional information regarding copyright ownership.
+//  The GPA licenses this file to you under the MIT License (MIT), the "License"; you may not use this
+//  file except in compliance with the License. You may obtain a copy of the License at:
+//
+//      http://opensource.org/licenses/MIT
+//
+//  Unless agreed to in writing, the subject software distributed under the License is distributed on an
+//  "AS-IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied. Refer to the
+//  License for the specific language governing permissions and limitations.
+//
+//  Code Modification History:
+//  ----------------------------------------------------------------------------------------------------
+//  04/01/2023 - J. Ritchie Carroll
+//       Generated original version of source code.
+//
+//******************************************************************************************************
+
+use crate::data::DataSet;
+use crate::data::DataValue;
+use crate::data::FilterExpressionParser;
+use crate::data::DEFAULT_PRIMARY_TABLE_NAME;
+use crate::transport::compression;
+use crate::transport::constants::Defaults;
+use crate::transport::operational_modes_version;
+use crate::transport::tssc::Encoder;
+use crate::transport::CompactMeasurement;
+use crate::transport::DataPacketFlags;
+use crate::transport::Measurement;
+use crate::transport::OperationalEncoding;
+use crate::transport::OperationalModes;
+use crate::transport::ServerCommand;
+use crate::transport::ServerResponse;
+use crate::transport::SignalIndexCache;
+use std::collections::HashMap;
+use std::error::Error;
+use std::io::{self, BufReader, ErrorKind, Read, Write};
+use std::net::{Shutdown, SocketAddr, TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::thread::JoinHandle;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use uuid::Uuid;
+
+const MAX_PACKET_SIZE: usize = 32768;
+const MAX_COMMAND_SIZE: usize = 1024 * 1024;
+const LISTEN_POLL_INTERVAL: Duration = Duration::from_millis(100);
+const PAYLOAD_HEADER_SIZE: usize = 4;
+const RESPONSE_HEADER_SIZE: usize = 6;
+const DATA_PACKET_HEADER_SIZE: usize = 5;
+const TSSC_HEADER_SIZE: usize = 3;
+const MIN_SUPPORTED_VERSION: u8 = 1;
+const MAX_SUPPORTED_VERSION: u8 = 3;
+const TSSC_VERSION: u8 = 85;
+
+type Callback<F> = Mutex<Option<Arc<F>>>;
+type ClientConnectionHandler = dyn Fn(Uuid, &str) + Send + Sync;
+
+// Metadata sent to subscribers along with the measurement keys used to build signal index caches
+#[derive(Default)]
+struct Metadata {
+    data_set: DataSet,
+    xml: Vec<u8>,
+    measurement_keys: HashMap<Uuid, (String, u64)>,
+}
+
+// Protocol and subscription state negotiated with a connected subscriber
+struct SubscriptionState {
+    version: u8,
+    validated: bool,
+    compress_payload_data: bool,
+    compress_metadata: bool,
+    compress_signal_index_cache: bool,
+    subscribed: bool,
+    include_time: bool,
+    use_millisecond_resolution: bool,
+    signal_index_cache: Arc<SignalIndexCache>,
+    cache_index: usize,
+    tssc_encoder: Encoder,
+}
+
+// Command channel connection of a `DataSubscriber` accepted by the `DataPublisher`
+struct SubscriberConnection {
+    subscriber_id: Uuid,
+    connection_id: String,
+    stream: Mutex<TcpStream>,
+    state: Mutex<SubscriptionState>,
+    thread: Mutex<Option<JoinHandle<()>>>,
+}
+
+impl SubscriberConnection {
+    fn send_response(
+        &self,
+        response: ServerResponse,
+        command_code: u8,
+        data: &[u8],
+    ) -> io::Result<()> {
+        let mut buffer =
+            Vec::with_capacity(PAYLOAD_HEADER_SIZE + RESPONSE_HEADER_SIZE + data.len());
+
+        // Insert payload header, response code, command code and data length
+        buffer.extend_from_slice(&((RESPONSE_HEADER_SIZE + data.len()) as u32).to_be_bytes());
+        buffer.push(response as u8);
+        buffer.push(command_code);
+        buffer.extend_from_slice(&(data.len() as u32).to_be_bytes());
+        buffer.extend_from_slice(data);
+
+        self.stream.lock().unwrap().write_all(&buffer)
+    }
+
+    fn send_succeeded(&self, command_code: u8, message: &str) -> io::Result<()> {
+        self.send_response(ServerResponse::Succeeded, command_code, message.as_bytes())
+    }
+
+    fn send_failed(&self, command_code: u8, message: &str) -> io::Result<()> {
+        self.send_response(ServerResponse::Failed, command_code, message.as_bytes())
+    }
+
+    fn shutdown(&self) {
+        // Connection may already be closed by the peer, so any shutdown failure is ignored
+        let _ = self.stream.lock().unwrap().shutdown(Shutdown::Both);
+    }
+}
+
+/// Represents a publisher of measurements to `DataSubscriber` connections in an STTP session.
+///
+/// A `DataPublisher` listens for subscriber connections on TCP, negotiates operational modes, provides
+/// metadata and publishes the measurements selected by the filter expression of each subscription in
+/// compact or, when requested, TSSC compressed form over the command channel. UDP data channels,
+/// metadata filters, TLS and cipher key rotation are not supported.
+///
+/// A `DataPublisher` is shared with its background threads, so it is expected to be wrapped in
+/// an `Arc` before it is started, e.g.: `Arc::new(DataPublisher::new()).start(...)`.
+pub struct DataPublisher {
+    metadata: Mutex<Arc<Metadata>>,
+    started: AtomicBool,
+    local_address: Mutex<Option<SocketAddr>>,
+    listening_thread: Mutex<Option<JoinHandle<()>>>,
+    connections: Mutex<HashMap<Uuid, Arc<SubscriberConnection>>>,
+    total_connections: AtomicU64,
+
+    /// Called when a informational message should be logged.
+    pub status_message_callback: Callback<dyn Fn(&str) + Send + Sync>,
+
+    /// Called when an error message should be logged.
+    pub error_message_callback: Callback<dyn Fn(&str) + Send + Sync>,
+
+    /// Called with the subscriber ID and connection ID of a newly connected `DataSubscriber`.
+    pub client_connected_callback: Callback<ClientConnectionHandler>,
+
+    /// Called with the subscriber ID and connection ID of a `DataSubscriber` that has disconnected.
+    pub client_disconnected_callback: Callback<ClientConnectionHandler>,
+
+    /// Determines whether payload data is TSSC compressed when requested by a subscriber, defaults to true.
+    pub compress_payload_data: bool,
+
+    /// Determines whether metadata is GZip compressed when requested by a subscriber, defaults to true.
+    pub compress_metadata: bool,
+
+    /// Determines whether the signal index cache is GZip compressed when requested by a subscriber, defaults to true.
+    pub compress_signal_index_cache: bool,
+}
+
+impl DataPublisher {
+    /// Creates a new `DataPublisher`.
+    pub fn new() -> Self {
+        DataPublisher {
+            metadata: Mutex::new(Arc::new(Metadata::default())),
+            started: AtomicBool::new(false),
+            local_address: Mutex::new(None),
+            listening_thread: Mutex::new(None),
+            connections: Mutex::new(HashMap::new()),
+            total_connections: AtomicU64::new(0),
+            status_message_callback: Mutex::new(None),
+            error_message_callback: Mutex::new(None),
+            client_connected_callback: Mutex::new(None),
+            client_disconnected_callback: Mutex::new(None),
+            compress_payload_data: Defaults::COMPRESS_PAYLOAD_DATA,
+            compress_metadata: Defaults::COMPRESS_METADATA,
+            compress_signal_index_cache: Defaults::COMPRESS_SIGNAL_INDEX_CACHE,
+        }
+    }
+
+    /// Gets flag that determines if the `DataPublisher` is listening for subscriber connections.
+    pub fn is_started(&self) -> bool {
+        self.started.load(Ordering::SeqCst)
+    }
+
+    /// Gets the local address the `DataPublisher` is listening on, if started.
+    pub fn local_address(&self) -> Option<SocketAddr> {
+        *self.local_address.lock().unwrap()
+    }
+
+    /// Gets the number of currently connected subscribers.
+    pub fn subscriber_count(&self) -> usize {
+        self.connections.lock().unwrap().len()
+    }
+
+    /// Defines the metadata, an XML serialized `DataSet`, provided to subscribers that request it.
+    ///
+    /// Subscription filter expressions are evaluated against the metadata, where the `ActiveMeasurements`
+    /// table defines the available measurements by their `SignalID` and `ID`, e.g., `PPA:12`, fields.
+    /// Connected subscribers are notified that the configuration has changed.
+    pub fn define_metadata(&self, xml: &[u8]) -> Result<(), Box<dyn Error>> {
+        let data_set = DataSet::from_xml(xml)?;
+        let mut measurement_keys = HashMap::new();
+
+        if let Some(table) = data_set.table(DEFAULT_PRIMARY_TABLE_NAME) {
+            for row in table.rows() {
+                let signal_id = match row.value_by_name("SignalID") {
+                    Some(DataValue::Guid(signal_id)) => *signal_id,
+                    Some(DataValue::String(signal_id)) => match Uuid::parse_str(signal_id.trim()) {
+                        Ok(signal_id) => signal_id,
+                        Err(_) => continue,
+                    },
+                    _ => continue,
+                };
+
+                let measurement_key = match row.value_by_name("ID") {
+                    Some(DataValue::String(key)) => parse_measurement_key(key),
+                    _ => None,
+                };
+
+                measurement_keys.insert(signal_id, measurement_key.unwrap_or_default());
+            }
+        }
+
+        *self.metadata.lock().unwrap() = Arc::new(Metadata {
+            data_set,
+            xml: xml.to_vec(),
+            measurement_keys,
+        });
+
+        for connection in self.connections() {
+            if !connection.state.lock().unwrap().validated {
+                continue;
+            }
+
+            let command_code = ServerCommand::MetadataRefresh as u8;
+
+            if let Err(e) =
+                connection.send_response(ServerResponse::ConfigurationChanged, command_code, &[])
+            {
+                self.terminate_connection(&connection, e);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Starts listening for `DataSubscriber` connections on the specified address and port.
+    /// An empty address listens on all interfaces, a zero port listens on any available port.
+    pub fn start(self: &Arc<Self>, address: &str, port: u16) -> Result<(), Box<dyn Error>> {
+        if self.started.load(Ordering::SeqCst) {
+            return Err("publisher is already started".into());
+        }
+
+        let address = if address.is_empty() {
+            "0.0.0.0"
+        } else {
+            address
+        };
+
+        let listener = TcpListener::bind((address, port))
+            .map_err(|e| format!("failed to listen on {}:{}: {}", address, port, e))?;
+
+        // Accepts are polled so that the listening thread can observe when the publisher stops
+        listener.set_nonblocking(true)?;
+
+        let local_address = listener.local_addr()?;
+
+        *self.local_address.lock().unwrap() = Some(local_address);
+        self.started.store(true, Ordering::SeqCst);
+
+        let publisher = Arc::clone(self);
+
+        *self.listening_thread.lock().unwrap() = Some(thread::spawn(move || {
+            publisher.run_listening_thread(listener);
+        }));
+
+        self.dispatch_status_message(&format!(
+            "Listening for subscriber connections on {}",
+            local_address
+        ));
+
+        Ok(())
+    }
+
+    /// Stops listening for `DataSubscriber` connections and disconnects all connected subscribers.
+    pub fn stop(&self) {
+        self.started.store(false, Ordering::SeqCst);
+
+        let listening_thread = self.listening_thread.lock().unwrap().take();
+
+        if let Some(thread) = listening_thread {
+            if thread.thread().id() != thread::current().id() && thread.join().is_err() {
+                self.dispatch_error_message("failed to join listening thread");
+            }
+        }
+
+        *self.local_address.lock().unwrap() = None;
+
+        let connections: Vec<Arc<SubscriberConnection>> = self
+            .connections
+            .lock()
+            .unwrap()
+            .drain()
+            .map(|(_, connection)| connection)
+            .collect();
+
+        for connection in &connections {
+            connection.shutdown();
+        }
+
+        for connection in connections {
+            let connection_thread = connection.thread.lock().unwrap().take();
+
+            if let Some(thread) = connection_thread {
+                if thread.thread().id() != thread::current().id() && thread.join().is_err() {
+                    self.dispatch_error_message("failed to join subscriber connection thread");
+                }
+            }
+        }
+    }
+
+    /// Publishes measurements to each subscriber whose subscription includes their signal IDs.
+    pub fn publish_measurements<M: Measurement>(&self, measurements: &[M]) {
+        for connection in self.connections() {
+            if let Err(e) = self.publish_to_subscriber(&connection, measurements) {
+                self.terminate_connection(&connection, e);
+            }
+        }
+    }
+
+    fn connections(&self) -> Vec<Arc<SubscriberConnection>> {
+        self.connections.lock().unwrap().values().cloned().collect()
+    }
+
+    fn run_listening_thread(self: &Arc<Self>, listener: TcpListener) {
+        while self.started.load(Ordering::SeqCst) {
+            let (stream, address) = match listener.accept() {
+                Ok(accepted) => accepted,
+                Err(e) => {
+                    if e.kind() != ErrorKind::WouldBlock {
+                        self.dispatch_error_message(&format!(
+                            "error accepting subscriber connection: {}",
+                            e
+                        ));
+                    }
+
+                    thread::sleep(LISTEN_POLL_INTERVAL);
+                    continue;
+                }
+            };
+
+            if let Err(e) = self.accept_connection(stream, address) {
+                self.dispatch_error_message(&format!(
+                    "failed to accept subscriber connection: {}",
+                    e
+                ));
+            }
+        }
+    }
+
+    fn accept_connection(
+        self: &Arc<Self>,
+        stream: TcpStream,
+        address: SocketAddr,
+    ) -> Result<(), Box<dyn Error>> {
+        // Accepted sockets can inherit the non-blocking mode of the listener on some platforms
+        stream.set_nonblocking(false)?;
+        stream.set_nodelay(true)?;
+
+        // Slow subscribers are disconnected rather than stalling publication to other subscribers
+        stream.set_write_timeout(Some(Duration::from_secs_f64(Defaults::SOCKET_TIMEOUT)))?;
+
+        let read_stream = stream.try_clone()?;
+        let subscriber_id = self.new_subscriber_id();
+        let connection_id = address.to_string();
+
+        let connection = Arc::new(SubscriberConnection {
+            subscriber_id,
+            connection_id: connection_id.clone(),
+            stream: Mutex::new(stream),
+            state: Mutex::new(SubscriptionState {
+                version: 0,
+                validated: false,
+                compress_payload_data: false,
+                compress_metadata: false,
+                compress_signal_index_cache: false,
+                subscribed: false,
+                include_time: true,
+                use_millisecond_resolution: false,
+                signal_index_cache: Arc::new(SignalIndexCache::new()),
+                cache_index: 0,
+                tssc_encoder: Encoder::new(),
+            }),
+            thread: Mutex::new(None),
+        });
+
+        self.connections
+            .lock()
+            .unwrap()
+            .insert(subscriber_id, Arc::clone(&connection));
+
+        let publisher = Arc::clone(self);
+        let thread_connection = Arc::clone(&connection);
+
+        *connection.thread.lock().unwrap() = Some(thread::spawn(move || {
+            publisher.run_connection_thread(thread_connection, read_stream);
+        }));
+
+        self.dispatch_status_message(&format!("Subscriber connected from {}", connection_id));
+
+        let callback = self.client_connected_callback.lock().unwrap().clone();
+
+        if let Some(callback) = callback {
+            callback(subscriber_id, &connection_id);
+        }
+
+        Ok(())
+    }
+
+    // Subscriber IDs only need to be unique for the lifetime of the publisher
+    fn new_subscriber_id(&self) -> Uuid {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_nanos() as u64)
+            .unwrap_or_default();
+
+        Uuid::from_u64_pair(
+            timestamp,
+            self.total_connections.fetch_add(1, Ordering::SeqCst),
+        )
+    }
+
+    fn run_connection_thread(
+        self: &Arc<Self>,
+        connection: Arc<SubscriberConnection>,
+        stream: TcpStream,
+    ) {
+        let mut reader = BufReader::new(stream);
+        let mut header = [0u8; PAYLOAD_HEADER_SIZE];
+        let mut packet = Vec::new();
+
+        // Read errors are expected when the subscriber disconnects or the publisher stops
+        while reader.read_exact(&mut header).is_ok() {
+            let packet_size = u32::from_be_bytes(header) as usize;
+
+            if packet_size == 0 || packet_size > MAX_COMMAND_SIZE {
+                self.dispatch_error_message(&format!(
+                    "received command of {} bytes from subscriber {} which is outside the valid size range; disconnecting",
+                    packet_size, connection.connection_id
+                ));
+                break;
+            }
+
+            packet.resize(packet_size, 0);
+
+            if reader.read_exact(&mut packet).is_err() {
+                break;
+            }
+
+            if let Err(e) = self.handle_command(&connection, packet[0], &packet[1..]) {
+                self.dispatch_error_message(&format!(
+                    "failed to send response to subscriber {}: {}",
+                    connection.connection_id, e
+                ));
+                break;
+            }
+        }
+
+        connection.shutdown();
+
+        self.connections
+            .lock()
+            .unwrap()
+            .remove(&connection.subscriber_id);
+
+        self.dispatch_status_message(&format!(
+            "Subscriber {} disconnected",
+            connection.connection_id
+        ));
+
+        let callback = self.client_disconnected_callback.lock().unwrap().clone();
+
+        if let Some(callback) = callback {
+            callback(connection.subscriber_id, &connection.connection_id);
+        }
+    }
+
+    fn terminate_connection(&self, connection: &SubscriberConnection, error: io::Error) {
+        self.dispatch_error_message(&format!(
+            "failed to send to subscriber {} - disconnecting: {}",
+            connection.connection_id, error
+        ));
+
+        // Connection thread observes the shutdown and completes the disconnect
+        connection.shutdown();
+    }
+
+    fn handle_command(
+        &self,
+        connection: &SubscriberConnection,
+        command_code: u8,
+        data: &[u8],
+    ) -> io::Result<()> {
+        let command = match ServerCommand::try_from(command_code) {
+            Ok(command) => command,
+            Err(e) => return connection.send_failed(command_code, &e),
+        };
+
+        match command {
+            ServerCommand::DefineOperationalModes => {
+                return self.handle_define_operational_modes(connection, data)
+            }
+            ServerCommand::ConfirmNotification
+            | ServerCommand::ConfirmBufferBlock
+            | ServerCommand::ConfirmUpdateSignalIndexCache
+            | ServerCommand::ConfirmUpdateBaseTimes
+            | ServerCommand::ConfirmUpdateCipherKeys => {
+                // Confirmations require no response, publisher does not await receipt
+                return Ok(());
+            }
+            _ => {}
+        }
+
+        if !connection.state.lock().unwrap().validated {
+            return connection.send_failed(
+                command_code,
+                "operational modes must be defined before any other command",
+            );
+        }
+
+        match command {
+            ServerCommand::MetadataRefresh => self.handle_metadata_refresh(connection, data),
+            ServerCommand::Subscribe => self.handle_subscribe(connection, data),
+            ServerCommand::Unsubscribe => {
+                connection.state.lock().unwrap().subscribed = false;
+                connection.send_succeeded(command_code, "Client unsubscribed.")
+            }
+            _ => connection.send_failed(
+                command_code,
+                &format!("{:?} command is not supported by this publisher", command),
+            ),
+        }
+    }
+
+    fn handle_define_operational_modes(
+        &self,
+        connection: &SubscriberConnection,
+        data: &[u8],
+    ) -> io::Result<()> {
+        let command_code = ServerCommand::DefineOperationalModes as u8;
+
+        let requested_modes = match data.try_into() {
+            Ok(bytes) => OperationalModes::from_bits_retain(u32::from_be_bytes(bytes)),
+            Err(_) => {
+                return connection.send_failed(
+                    command_code,
+                    "operational modes command must have a 4 byte payload",
+                )
+            }
+        };
+
+        let version = operational_modes_version(requested_modes);
+
+        if !(MIN_SUPPORTED_VERSION..=MAX_SUPPORTED_VERSION).contains(&version) {
+            return connection.send_failed(
+                command_code,
+                &format!(
+                    "STTP protocol version {} is not supported, publisher supports versions {} to {}",
+                    version, MIN_SUPPORTED_VERSION, MAX_SUPPORTED_VERSION
+                ),
+            );
+        }
+
+        if requested_modes.bits() & OperationalModes::EncodingMask.bits()
+            != OperationalEncoding::UTF8 as u32
+        {
+            return connection
+                .send_failed(command_code, "publisher only supports UTF8 string encoding");
+        }
+
+        // Compression is only accepted when requested and enabled, GZip needs the compression feature
+        let compress_payload_data = self.compress_payload_data
+            && requested_modes.contains(OperationalModes::CompressPayloadData);
+
+        let compress_metadata = cfg!(feature = "gzip")
+            && self.compress_metadata
+            && requested_modes.contains(OperationalModes::CompressMetadata);
+
+        let compress_signal_index_cache = cfg!(feature = "gzip")
+            && self.compress_signal_index_cache
+            && requested_modes.contains(OperationalModes::CompressSignalIndexCache);
+
+        let mut state = connection.state.lock().unwrap();
+
+        state.version = version;
+        state.validated = true;
+        state.compress_payload_data = compress_payload_data;
+        state.compress_metadata = compress_metadata;
+        state.compress_signal_index_cache = compress_signal_index_cache;
+
+        drop(state);
+
+        self.dispatch_status_message(&format!(
+            "Subscriber {} negotiated STTP protocol version {}",
+            connection.connection_id, version
+        ));
+
+        // Like reference publishers, accepted operational modes are not acknowledged
+        Ok(())
+    }
+
+    fn handle_metadata_refresh(
+        &self,
+        connection: &SubscriberConnection,
+        data: &[u8],
+    ) -> io::Result<()> {
+        let command_code = ServerCommand::MetadataRefresh as u8;
+
+        // Metadata refresh format: optional metadata filters length and metadata filters
+        if !data.is_empty() {
+            let length = match data.get(0..4) {
+                Some(bytes) => u32::from_be_bytes(bytes.try_into().unwrap()) as usize,
+                None => {
+                    return connection.send_failed(
+                        command_code,
+                        "metadata refresh command is too small to parse",
+                    )
+                }
+            };
+
+            if data.len() < 4 + length {
+                return connection.send_failed(
+                    command_code,
+                    "metadata refresh command is too small to parse",
+                );
+            }
+
+            if length > 0 {
+                return connection.send_failed(
+                    command_code,
+                    "metadata filters are not supported by this publisher",
+                );
+            }
+        }
+
+        let metadata = Arc::clone(&self.metadata.lock().unwrap());
+
+        if metadata.xml.is_empty() {
+            return connection.send_failed(command_code, "publisher has not defined any metadata");
+        }
+
+        let compress_metadata = connection.state.lock().unwrap().compress_metadata;
+
+        let data = if compress_metadata {
+            compress(&metadata.xml)
+        } else {
+            metadata.xml.clone()
+        };
+
+        connection.send_response(ServerResponse::Succeeded, command_code, &data)
+    }
+
+    fn handle_subscribe(&self, connection: &SubscriberConnection, data: &[u8]) -> io::Result<()> {
+        let command_code = ServerCommand::Subscribe as u8;
+
+        // Subscribe format: data packet flags, connection string length and connection string
+        let length = match data.get(1..DATA_PACKET_HEADER_SIZE) {
+            Some(bytes) => u32::from_be_bytes(bytes.try_into().unwrap()) as usize,
+            None => {
+                return connection
+                    .send_failed(command_code, "subscribe command is too small to parse")
+            }
+        };
+
+        let connection_string =
+            match data.get(DATA_PACKET_HEADER_SIZE..DATA_PACKET_HEADER_SIZE + length) {
+                Some(bytes) => String::from_utf8_lossy(bytes),
+                None => {
+                    return connection
+                        .send_failed(command_code, "subscribe command is too small to parse")
+                }
+            };
+
+        let settings = parse_connection_string(&connection_string);
+
+        if settings.contains_key("datachannel") {
+            return connection.send_failed(
+                command_code,
+                "UDP data channel is not supported by this publisher",
+            );
+        }
+
+        let metadata = Arc::clone(&self.metadata.lock().unwrap());
+
+        let signal_ids = match settings.get("filterexpression") {
+            Some(filter_expression) if !filter_expression.is_empty() => {
+                match FilterExpressionParser::new(filter_expression)
+                    .and_then(|parser| parser.filtered_signal_ids(&metadata.data_set))
+                {
+                    Ok(signal_ids) => signal_ids,
+                    Err(e) => {
+                        return connection.send_failed(
+                            command_code,
+                            &format!("failed to evaluate subscription filter expression: {}", e),
+                        )
+                    }
+                }
+            }
+            _ => Vec::new(),
+        };
+
+        let mut signal_index_cache = SignalIndexCache::new();
+
+        for (signal_index, signal_id) in signal_ids.iter().enumerate() {
+            let (source, id) = metadata
+                .measurement_keys
+                .get(signal_id)
+                .cloned()
+                .unwrap_or_default();
+
+            signal_index_cache.add_record(signal_index as i32, *signal_id, source, id);
+        }
+
+        let mut state = connection.state.lock().unwrap();
+
+        state.include_time = parse_boolean(&settings, "includetime", true);
+        state.use_millisecond_resolution =
+            parse_boolean(&settings, "usemillisecondresolution", false);
+
+        // STTP version 2 or greater alternates cache slots so that packets encoded with the prior
+        // cache can still be decoded while the new cache is being received
+        if state.version > 1 {
+            state.cache_index ^= 1;
+        }
+
+        state.signal_index_cache = Arc::new(signal_index_cache);
+        state.tssc_encoder = Encoder::new();
+        state.subscribed = true;
+
+        let mut payload = Vec::new();
+
+        if state.version > 1 {
+            payload.push(state.cache_index as u8);
+        }
+
+        let cache = state.signal_index_cache.encode(connection.subscriber_id);
+
+        if state.compress_signal_index_cache {
+            payload.extend_from_slice(&compress(&cache));
+        } else {
+            payload.extend_from_slice(&cache);
+        }
+
+        // Cache update is sent while holding the state lock so that it precedes any data packets
+        connection.send_response(
+            ServerResponse::UpdateSignalIndexCache,
+            command_code,
+            &payload,
+        )?;
+
+        let count = state.signal_index_cache.count();
+
+        drop(state);
+
+        self.dispatch_status_message(&format!(
+            "Subscriber {} subscribed to {} signals",
+            connection.connection_id, count
+        ));
+
+        connection.send_succeeded(
+            command_code,
+            &format!("Client subscribed with {} signals.", count),
+        )
+    }
+
+    fn publish_to_subscriber<M: Measurement>(
+        &self,
+        connection: &SubscriberConnection,
+        measurements: &[M],
+    ) -> io::Result<()> {
+        let mut state = connection.state.lock().unwrap();
+
+        if !state.subscribed {
+            return Ok(());
+        }
+
+        let subscribed: Vec<(i32, &M)> = measurements
+            .iter()
+            .map(|measurement| {
+                (
+                    state
+                        .signal_index_cache
+                        .signal_index(measurement.signal_id()),
+                    measurement,
+                )
+            })
+            .filter(|(signal_index, _)| *signal_index >= 0)
+            .collect();
+
+        if subscribed.is_empty() {
+            return Ok(());
+        }
+
+        let mut flags = DataPacketFlags::COMPACT;
+
+        if state.cache_index > 0 {
+            flags |= DataPacketFlags::CACHEINDEX;
+        }
+
+        if state.compress_payload_data {
+            publish_tssc_measurements(connection, &mut state, flags, &subscribed)
+        } else {
+            publish_compact_measurements(connection, &state, flags, &subscribed)
+        }
+    }
+
+    fn dispatch_status_message(&self, message: &str) {
+        let callback = self.status_message_callback.lock().unwrap().clone();
+
+        if let Some(callback) = callback {
+            callback(message);
+        }
+    }
+
+    fn dispatch_error_message(&self, message: &str) {
+        let callback = self.error_message_callback.lock().unwrap().clone();
+
+        if let Some(callback) = callback {
+            callback(message);
+        }
+    }
+}
+
+impl Default for DataPublisher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn publish_compact_measurements<M: Measurement>(
+    connection: &SubscriberConnection,
+    state: &SubscriptionState,
+    flags: DataPacketFlags,
+    measurements: &[(i32, &M)],
+) -> io::Result<()> {
+    let mut packet = Vec::with_capacity(MAX_PACKET_SIZE);
+    let mut count = 0u32;
+
+    for (_, measurement) in measurements {
+        let mut compact = CompactMeasurement::new(
+            Arc::clone(&state.signal_index_cache),
+            state.include_time,
+            state.use_millisecond_resolution,
+        );
+
+        compact.set_signal_id(measurement.signal_id());
+        compact.set_value(measurement.value());
+        compact.set_timestamp(measurement.timestamp());
+        compact.set_flags(measurement.flags());
+
+        let encoded = compact.encode();
+
+        if count > 0 && DATA_PACKET_HEADER_SIZE + packet.len() + encoded.len() > MAX_PACKET_SIZE {
+            send_data_packet(connection, flags, count, &packet)?;
+            packet.clear();
+            count = 0;
+        }
+
+        packet.extend_from_slice(&encoded);
+        count += 1;
+    }
+
+    send_data_packet(connection, flags, count, &packet)
+}
+
+fn publish_tssc_measurements<M: Measurement>(
+    connection: &SubscriberConnection,
+    state: &mut SubscriptionState,
+    flags: DataPacketFlags,
+    measurements: &[(i32, &M)],
+) -> io::Result<()> {
+    let flags = flags | DataPacketFlags::COMPRESSED;
+    let block_size = MAX_PACKET_SIZE - DATA_PACKET_HEADER_SIZE - TSSC_HEADER_SIZE;
+    let encoder = &mut state.tssc_encoder;
+    let mut count = 0u32;
+
+    encoder.set_buffer(block_size);
+
+    for (signal_index, measurement) in measurements {
+        let value = measurement.value() as f32;
+
+        if !encoder.try_add_measurement(
+            *signal_index,
+            measurement.timestamp(),
+            measurement.flags(),
+            value,
+        ) {
+            send_tssc_block(connection, encoder, flags, count)?;
+            encoder.set_buffer(block_size);
+            count = 0;
+
+            // A new block always has room for at least one measurement
+            encoder.try_add_measurement(
+                *signal_index,
+                measurement.timestamp(),
+                measurement.flags(),
+                value,
+            );
+        }
+
+        count += 1;
+    }
+
+    send_tssc_block(connection, encoder, flags, count)
+}
+
+fn send_tssc_block(
+    connection: &SubscriberConnection,
+    encoder: &mut Encoder,
+    flags: DataPacketFlags,
+    count: u32,
+) -> io::Result<()> {
+    let block = encoder.finish_block();
+    let mut packet = Vec::with_capacity(TSSC_HEADER_SIZE + block.len());
+
+    packet.push(TSSC_VERSION);
+    packet.extend_from_slice(&encoder.sequence_number.to_be_bytes());
+    packet.extend_from_slice(&block);
+
+    encoder.sequence_number = encoder.sequence_number.wrapping_add(1);
+
+    // Do not increment to 0 on roll-over, zero sequence number signals an algorithm reset
+    if encoder.sequence_number == 0 {
+        encoder.sequence_number = 1;
+    }
+
+    send_data_packet(connection, flags, count, &packet)
+}
+
+fn send_data_packet(
+    connection: &SubscriberConnection,
+    flags: DataPacketFlags,
+    count: u32,
+    payload: &[u8],
+) -> io::Result<()> {
+    let mut packet = Vec::with_capacity(DATA_PACKET_HEADER_SIZE + payload.len());
+
+    packet.push(flags.bits());
+    packet.extend_from_slice(&count.to_be_bytes());
+    packet.extend_from_slice(payload);
+
+    connection.send_response(
+        ServerResponse::DataPacket,
+        ServerCommand::Subscribe as u8,
+        &packet,
+    )
+}
+
+// Compresses a payload with GZip, falling back on the uncompressed payload if compression fails,
+// subscribers pass through payloads that do not have a GZip header
+fn compress(data: &[u8]) -> Vec<u8> {
+    compression::gzip_compress(data).unwrap_or_else(|_| data.to_vec())
+}
+
+// Parses a measurement key, e.g., "PPA:12", into its source and ID
+fn parse_measurement_key(key: &str) -> Option<(String, u64)> {
+    let (source, id) = key.trim().split_once(':')?;
+    Some((source.to_string(), id.trim().parse().ok()?))
+}
+
+// Parses a connection string of semicolon separated key/value pairs into settings keyed by lower
+// case name. Values that contain semicolons are wrapped in braces, which are removed.
+fn parse_connection_string(connection_string: &str) -> HashMap<String, String> {
+    let mut settings = HashMap::new();
+    let mut depth = 0;
+    let mut start = 0;
+
+    let mut add_setting = |setting: &str| {
+        if let Some((key, value)) = setting.split_once('=') {
+            let value = value.trim();
+
+            let value = value
+                .strip_prefix('{')
+                .and_then(|value| value.strip_suffix('}'))
+                .unwrap_or(value);
+
+            settings.insert(key.trim().to_lowercase(), value.trim().to_string());
+        }
+    };
+
+    for (index, character) in connection_string.char_indices() {
+        match character {
+            '{' => depth += 1,
+            '}' if depth > 0 => depth -= 1,
+            ';' if depth == 0 => {
+                add_setting(&connection_string[start..index]);
+                start = index + 1;
+            }
+            _ => {}
+        }
+    }
+
+    add_setting(&connection_string[start..]);
+
+    settings
+}
+
+fn parse_boolean(settings: &HashMap<String, String>, key: &str, default: bool) -> bool {
+    match settings.get(key) {
+        Some(value) => value.eq_ignore_ascii_case("true"),
+        None => default,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transport::BasicMeasurement;
+    use crate::transport::DataSubscriber;
+    use crate::transport::SubscriptionInfo;
+    use crate::Ticks;
+    use std::sync::mpsc;
+
+    const FREQ_SIGNAL_ID: &str = "8b3c7e0a-4d3f-4b8e-9f2a-6a1d2c3b4e5f";
+    const VPHM_SIGNAL_ID: &str = "1f6e2d4c-9a8b-4c7d-8e5f-0a1b2c3d4e5f";
+
+    const METADATA_XML: &str = r#"<?xml version="1.0" standalone="yes"?>
+<DataSet>
+  <xs:schema id="DataSet" xmlns="" xmlns:xs="http://www.w3.org/2001/XMLSchema" xmlns:msdata="urn:schemas-microsoft-com:xml-msdata">
+    <xs:element name="DataSet" msdata:IsDataSet="true" msdata:UseCurrentLocale="true">
+      <xs:complexType>
+        <xs:choice minOccurs="0" maxOccurs="unbounded">
+          <xs:element name="ActiveMeasurements">
+            <xs:complexType>
+              <xs:sequence>
+                <xs:element name="ID" type="xs:string" minOccurs="0" />
+                <xs:element name="SignalID" msdata:DataType="System.Guid, mscorlib, Version=4.0.0.0, Culture=neutral, PublicKeyToken=b77a5c561934e089" type="xs:string" minOccurs="0" />
+                <xs:element name="PointTag" type="xs:string" minOccurs="0" />
+                <xs:element name="SignalType" type="xs:string" minOccurs="0" />
+              </xs:sequence>
+            </xs:complexType>
+          </xs:element>
+        </xs:choice>
+      </xs:complexType>
+    </xs:element>
+  </xs:schema>
+  <ActiveMeasurements>
+    <ID>PPA:1</ID>
+    <SignalID>8b3c7e0a-4d3f-4b8e-9f2a-6a1d2c3b4e5f</SignalID>
+    <PointTag>SHELBY:FREQ</PointTag>
+    <SignalType>FREQ</SignalType>
+  </ActiveMeasurements>
+  <ActiveMeasurements>
+    <ID>PPA:2</ID>
+    <SignalID>1f6e2d4c-9a8b-4c7d-8e5f-0a1b2c3d4e5f</SignalID>
+    <PointTag>SHELBY:VPHM</PointTag>
+    <SignalType>VPHM</SignalType>
+  </ActiveMeasurements>
+</DataSet>"#;
+
+    fn start_publisher() -> Arc<DataPublisher> {
+        let publisher = Arc::new(DataPublisher::new());
+
+        publisher.define_metadata(METADATA_XML.as_bytes()).unwrap();
+        publisher.start("127.0.0.1", 0).unwrap();
+
+        publisher
+    }
+
+    fn connect_subscriber(
+        publisher: &DataPublisher,
+        compress_payload_data: bool,
+    ) -> Arc<DataSubscriber> {
+        let mut ds = DataSubscriber::new();
+        ds.compress_payload_data = compress_payload_data;
+
+        let ds = Arc::new(ds);
+
+        ds.connect("127.0.0.1", publisher.local_address().unwrap().port())
+            .unwrap();
+
+        ds
+    }
+
+    fn measurement(signal_id: &str, value: f64, timestamp: u64) -> BasicMeasurement {
+        let mut measurement = BasicMeasurement::default();
+
+        measurement.set_signal_id(Uuid::parse_str(signal_id).unwrap());
+        measurement.set_value(value);
+        measurement.set_timestamp(Ticks::new(timestamp));
+
+        measurement
+    }
+
+    #[test]
+    fn test_subscriber_receives_filtered_measurements() {
+        let publisher = start_publisher();
+
+        for compress_payload_data in [false, true] {
+            let ds = connect_subscriber(&publisher, compress_payload_data);
+            let (sender, receiver) = mpsc::channel();
+
+            *ds.new_measurements_callback.lock().unwrap() =
+                Some(Arc::new(move |measurements: Vec<&dyn Measurement>| {
+                    for measurement in measurements {
+                        sender
+                            .send((
+                                measurement.signal_id(),
+                                measurement.value(),
+                                measurement.timestamp().value(),
+                            ))
+                            .unwrap();
+                    }
+                }));
+
+            ds.set_subscription(SubscriptionInfo {
+                filter_expression: "FILTER ActiveMeasurements WHERE SignalType = 'FREQ'"
+                    .to_string(),
+                ..SubscriptionInfo::default()
+            });
+
+            let subscribed = ds.expect_command_result(ServerCommand::Subscribe);
+            ds.subscribe().unwrap();
+
+            assert!(
+                subscribed
+                    .recv_timeout(Duration::from_secs(5))
+                    .unwrap()
+                    .succeeded
+            );
+
+            let timestamp = 638_000_000_000_000_000;
+
+            for offset in 0..3 {
+                publisher.publish_measurements(&[
+                    measurement(FREQ_SIGNAL_ID, 59.98 + offset as f64, timestamp + offset),
+                    measurement(VPHM_SIGNAL_ID, 132_000.0, timestamp + offset),
+                ]);
+            }
+
+            for offset in 0..3 {
+                assert_eq!(
+                    receiver.recv_timeout(Duration::from_secs(5)).unwrap(),
+                    (
+                        Uuid::parse_str(FREQ_SIGNAL_ID).unwrap(),
+                        (59.98 + offset as f64) as f32 as f64,
+                        timestamp + offset
+                    )
+                );
+            }
+
+            assert!(receiver.recv_timeout(Duration::from_millis(100)).is_err());
+
+            ds.dispose();
+        }
+
+        publisher.stop();
+
+        assert!(!publisher.is_started());
+        assert_eq!(publisher.subscriber_count(), 0);
+    }
+
+    #[test]
+    fn test_subscriber_receives_metadata_and_configuration_changes() {
+        let publisher = start_publisher();
+        let ds = connect_subscriber(&publisher, true);
+        let (sender, receiver) = mpsc::channel();
+
+        let metadata_sender = sender.clone();
+
+        *ds.metadata_parsed_callback.lock().unwrap() = Some(Arc::new(move |data_set: &DataSet| {
+            let row_count = data_set
+                .table(DEFAULT_PRIMARY_TABLE_NAME)
+                .unwrap()
+                .row_count();
+            metadata_sender
+                .send(format!("rows: {}", row_count))
+                .unwrap();
+        }));
+
+        *ds.configuration_changed_callback.lock().unwrap() = Some(Arc::new(move || {
+            sender.send("configuration changed".to_string()).unwrap();
+        }));
+
+        ds.request_metadata("");
+
+        assert_eq!(
+            receiver.recv_timeout(Duration::from_secs(5)).unwrap(),
+            "rows: 2"
+        );
+
+        publisher.define_metadata(METADATA_XML.as_bytes()).unwrap();
+
+        assert_eq!(
+            receiver.recv_timeout(Duration::from_secs(5)).unwrap(),
+            "configuration changed"
+        );
+
+        // Metadata filters are rejected rather than ignored
+        let refreshed = ds.expect_command_result(ServerCommand::MetadataRefresh);
+        ds.request_metadata("FILTER ActiveMeasurements WHERE SignalType = 'FREQ'");
+
+        let result = refreshed.recv_timeout(Duration::from_secs(5)).unwrap();
+
+        assert!(!result.succeeded);
+        assert!(result
+            .message
+            .contains("metadata filters are not supported"));
+        assert!(receiver.recv_timeout(Duration::from_millis(100)).is_err());
+
+        // Subscriptions with invalid filter expressions are rejected
+        ds.set_subscription(SubscriptionInfo {
+            filter_expression: "FILTER MissingTable WHERE SignalType = 'FREQ'".to_string(),
+            ..SubscriptionInfo::default()
+        });
+
+        let subscribed = ds.expect_command_result(ServerCommand::Subscribe);
+        ds.subscribe().unwrap();
+
+        assert!(
+            !subscribed
+                .recv_timeout(Duration::from_secs(5))
+                .unwrap()
+                .succeeded
+        );
+
+        ds.dispose();
+        publisher.stop();
+    }
+
+    #[test]
+    fn test_parse_connection_string_unwraps_nested_values() {
+        let settings = parse_connection_string(
+            "includeTime=false;filterExpression={FILTER ActiveMeasurements WHERE ID = 'PPA:1'};assemblyInfo={source=sttp;version=0.1.0}",
+        );
+
+        assert_eq!(settings["includetime"], "false");
+        assert_eq!(
+            settings["filterexpression"],
+            "FILTER ActiveMeasurements WHERE ID = 'PPA:1'"
+        );
+        assert_eq!(settings["assemblyinfo"], "source=sttp;version=0.1.0");
+    }
+}
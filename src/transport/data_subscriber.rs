@@ -24,34 +24,60 @@
 // TODO: Remove later
 #![allow(dead_code)]
 
+use crate::data::DataSet;
+use crate::transport::cipher;
+use crate::transport::compact_measurement::FIXED_LENGTH;
+use crate::transport::compression;
 use crate::transport::constants::Defaults;
+use crate::transport::measurement_metadata::load_measurement_metadata;
+use crate::transport::operational_modes_version;
+use crate::transport::tls::{self, TlsStream};
+use crate::transport::tssc::Decoder;
+use crate::transport::BasicMeasurement;
+use crate::transport::BufferBlock;
+use crate::transport::CommandResult;
+use crate::transport::CompactMeasurement;
+#[allow(deprecated)]
+use crate::transport::CompressionModes;
+use crate::transport::MeasurementMetadata;
 use crate::transport::OperationalEncoding;
+use crate::transport::OperationalModes;
+use crate::transport::ServerResponse;
 use crate::transport::SignalIndexCache;
 use crate::transport::SubscriberConnector;
 use crate::transport::SubscriptionInfo;
+use crate::transport::TlsConfig;
+use crate::Ticks;
 use crate::Version;
 
 use chrono::DateTime;
 use chrono::Utc;
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap};
 use std::error::Error;
-use std::fmt;
-use std::io::{BufReader, BufWriter, Read, Write};
-use std::net::Shutdown;
-use std::net::TcpStream;
+use std::io::{self, BufReader, ErrorKind, Read, Write};
+use std::net::UdpSocket;
+use std::net::{Shutdown, SocketAddr, TcpListener, TcpStream};
 use std::option::Option;
-use std::str::from_utf8;
-use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
-use std::sync::{Arc, Mutex, RwLock};
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicU64, AtomicUsize, Ordering};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::sync::{Arc, Mutex};
 use std::thread;
 use std::thread::JoinHandle;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use std::vec;
 use uuid::Uuid;
 
 use super::DataPacketFlags;
 use super::Measurement;
+use super::SecurityMode;
 use super::ServerCommand;
 
 const MAX_PACKET_SIZE: usize = 32768;
+const MAX_RESPONSE_SIZE: usize = 128 * 1024 * 1024;
+const MAX_DATAGRAM_SIZE: usize = 65536;
+const DATA_CHANNEL_READ_TIMEOUT: Duration = Duration::from_millis(250);
+const LISTEN_POLL_INTERVAL: Duration = Duration::from_millis(100);
 const PAYLOAD_HEADER_SIZE: usize = 4;
 const RESPONSE_HEADER_SIZE: usize = 6;
 const EVEN_KEY: usize = 0;
@@ -59,26 +85,161 @@ const ODD_KEY: usize = 1;
 const KEY_INDEX: usize = 0;
 const IV_INDEX: usize = 1;
 const MISSING_CACHE_WARNING_INTERVAL: f64 = 20.0;
+const MAX_BUFFER_BLOCK_REORDER_WINDOW: u32 = 1024;
+const MIN_SUPPORTED_VERSION: u8 = 1;
+const MAX_SUPPORTED_VERSION: u8 = 3;
+const TSSC_VERSION: u8 = 85;
+const TSSC_OUT_OF_SEQUENCE_REPORT_INTERVAL: f64 = 2.0;
+
+type Callback<F> = Mutex<Option<Arc<F>>>;
+type KeyIVs = [[Vec<u8>; 2]; 2];
+type MetadataReceivedHandler = dyn Fn(&[u8]) + Send + Sync;
+type MetadataParsedHandler = dyn Fn(&DataSet) + Send + Sync;
+type NewMeasurementsHandler = dyn Fn(Vec<&dyn Measurement>) + Send + Sync;
+type NewBufferBlocksHandler = dyn Fn(Vec<&BufferBlock>) + Send + Sync;
+type UserResponseHandler = dyn Fn(u8, &[u8]) + Send + Sync;
+type UserResponseWaiter = (u64, Sender<Vec<u8>>);
+
+// Command channel connection to a `DataPublisher`, optionally secured with TLS
+enum CommandChannel {
+    Tcp(TcpStream),
+    Tls(Arc<TlsStream>),
+}
+
+impl CommandChannel {
+    fn write_all(&self, data: &[u8]) -> io::Result<()> {
+        match self {
+            CommandChannel::Tcp(socket) => (&*socket).write_all(data),
+            CommandChannel::Tls(stream) => stream.write_all(data),
+        }
+    }
+
+    fn peer_addr(&self) -> io::Result<SocketAddr> {
+        match self {
+            CommandChannel::Tcp(socket) => socket.peer_addr(),
+            CommandChannel::Tls(stream) => stream.peer_addr(),
+        }
+    }
+
+    fn shutdown(&self) -> io::Result<()> {
+        match self {
+            CommandChannel::Tcp(socket) => socket.shutdown(Shutdown::Both),
+            CommandChannel::Tls(stream) => stream.shutdown(),
+        }
+    }
+
+    // Gets an independent reader for the command channel response thread
+    fn reader(&self) -> io::Result<Box<dyn Read + Send>> {
+        match self {
+            CommandChannel::Tcp(socket) => Ok(Box::new(socket.try_clone()?)),
+            CommandChannel::Tls(stream) => Ok(Box::new(TlsReader(Arc::clone(stream)))),
+        }
+    }
+}
+
+struct TlsReader(Arc<TlsStream>);
+
+impl Read for TlsReader {
+    fn read(&mut self, buffer: &mut [u8]) -> io::Result<usize> {
+        self.0.read(buffer)
+    }
+}
+
+// Callers awaiting user-defined responses by index. Requests that timed out are counted per index
+// so that their late replies are discarded instead of being delivered to a subsequent request.
+#[derive(Default)]
+struct UserResponseWaiters {
+    next_id: u64,
+    waiters: HashMap<u8, Vec<UserResponseWaiter>>,
+    abandoned: HashMap<u8, usize>,
+}
+
+impl UserResponseWaiters {
+    fn add(&mut self, index: u8, sender: Sender<Vec<u8>>) -> u64 {
+        let id = self.next_id;
+
+        self.next_id += 1;
+        self.waiters.entry(index).or_default().push((id, sender));
+
+        id
+    }
+
+    // Removes a waiter that gave up on its response, returns `false` if a response was already delivered
+    fn abandon(&mut self, index: u8, id: u64) -> bool {
+        let waiters = match self.waiters.get_mut(&index) {
+            Some(waiters) => waiters,
+            None => return false,
+        };
+
+        let count = waiters.len();
+        waiters.retain(|(waiter_id, _)| *waiter_id != id);
+
+        if waiters.len() == count {
+            return false;
+        }
+
+        if waiters.is_empty() {
+            self.waiters.remove(&index);
+        }
+
+        *self.abandoned.entry(index).or_default() += 1;
+        true
+    }
+
+    // Takes the waiters for a received response, none when the response belongs to an abandoned request
+    fn take(&mut self, index: u8) -> Vec<Sender<Vec<u8>>> {
+        if let Some(abandoned) = self.abandoned.get_mut(&index) {
+            *abandoned -= 1;
+
+            if *abandoned == 0 {
+                self.abandoned.remove(&index);
+            }
+
+            return Vec::new();
+        }
+
+        self.waiters
+            .remove(&index)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(_, sender)| sender)
+            .collect()
+    }
+
+    fn clear(&mut self) {
+        self.waiters.clear();
+        self.abandoned.clear();
+    }
+}
 
 /// Represents a subscription for an STTP connection.
+///
+/// A `DataSubscriber` is shared with its background threads, so it is expected to be wrapped in
+/// an `Arc` before a connection is initiated, e.g.: `Arc::new(DataSubscriber::new()).connect(...)`.
 //#[derive(Debug)]
-pub struct DataSubscriber<'a> {
-    subscription: SubscriptionInfo,
-    subscriber_id: Uuid,
+pub struct DataSubscriber {
+    subscription: Mutex<SubscriptionInfo>,
+    subscriber_id: Mutex<Uuid>,
     encoding: OperationalEncoding,
+    operational_modes: Mutex<OperationalModes>,
     pub(crate) connector: Arc<Mutex<SubscriberConnector>>,
     connected: AtomicBool,
     validated: AtomicBool,
     listening: AtomicBool,
+    listening_thread: Mutex<Option<JoinHandle<()>>>,
     subscribed: AtomicBool,
 
-    command_channel_socket: Mutex<Option<TcpStream>>,
-    command_channel_response_thread: Option<JoinHandle<()>>,
-    data_channel_socket: Mutex<Option<TcpStream>>,
-    data_channel_response_thread: Option<JoinHandle<()>>,
-    connection_id: String,
+    command_channel_socket: Mutex<Option<CommandChannel>>,
+    command_channel_response_thread: Mutex<Option<JoinHandle<()>>>,
+    data_channel_socket: Mutex<Option<UdpSocket>>,
+    data_channel_response_thread: Mutex<Option<JoinHandle<()>>>,
+    data_channel_active: AtomicBool,
+    connection_id: Mutex<String>,
 
     connect_action_mutex: Mutex<()>,
+    connection_generation: AtomicU64,
+    command_result_waiters: Mutex<HashMap<ServerCommand, Vec<Sender<CommandResult>>>>,
+    user_response_waiters: Mutex<UserResponseWaiters>,
     connection_termination_thread_mutex: Mutex<Option<JoinHandle<()>>>,
 
     disconnect_thread_mutex: Mutex<Option<JoinHandle<()>>>,
@@ -92,46 +253,50 @@ pub struct DataSubscriber<'a> {
     total_measurements_received: AtomicU64,
 
     /// Called when a informational message should be logged.
-    pub status_message_callback: Mutex<Option<Arc<dyn Fn(&str) + Send + Sync + 'a>>>,
+    pub status_message_callback: Callback<dyn Fn(&str) + Send + Sync>,
 
     /// Called when an error message should be logged.
-    pub error_message_callback: Mutex<Option<Arc<dyn Fn(&str) + Send + Sync + 'a>>>,
+    pub error_message_callback: Callback<dyn Fn(&str) + Send + Sync>,
 
     /// Called when a DataSubscriber connection has been established.
-    pub connection_established_callback: Mutex<Option<Arc<dyn Fn() + Send + Sync + 'a>>>,
+    pub connection_established_callback: Callback<dyn Fn() + Send + Sync>,
 
     /// Called when `DataSubscriber` terminates its connection.
-    pub connection_terminated_callback: Mutex<Option<Arc<dyn Fn() + Send + Sync + 'a>>>,
+    pub connection_terminated_callback: Callback<dyn Fn() + Send + Sync>,
 
     /// Called when `DataSubscriber` automatically reconnects.
-    pub auto_reconnect_callback: Mutex<Option<Arc<dyn Fn() + Send + Sync + 'a>>>,
+    pub auto_reconnect_callback: Callback<dyn Fn() + Send + Sync>,
 
     /// Called when `DataSubscriber` receives a metadata response.
-    pub metadata_received_callback: Mutex<Option<Arc<dyn Fn(&[u8]) + Send + Sync + 'a>>>,
+    pub metadata_received_callback: Callback<MetadataReceivedHandler>,
+
+    /// Called when a metadata response received by `DataSubscriber` has been parsed into a `DataSet`.
+    pub metadata_parsed_callback: Callback<MetadataParsedHandler>,
 
     /// Called when `DataSubscriber` receives a new signal index cache response.
-    pub subscription_updated_callback:
-        Mutex<Option<Arc<dyn Fn(&SignalIndexCache) + Send + Sync + 'a>>>,
+    pub subscription_updated_callback: Callback<dyn Fn(&SignalIndexCache) + Send + Sync>,
 
     /// Called with timestamp of first received measurement in a subscription.
-    pub data_start_time_callback: Mutex<Option<Arc<dyn Fn(u64) + Send + Sync + 'a>>>,
+    pub data_start_time_callback: Callback<dyn Fn(Ticks) + Send + Sync>,
 
     /// Called when the `DataPublisher` sends a notification that configuration has changed.
-    pub configuration_changed_callback: Mutex<Option<Arc<dyn Fn() + Send + Sync + 'a>>>,
+    pub configuration_changed_callback: Callback<dyn Fn() + Send + Sync>,
 
     /// Called when `DataSubscriber` receives a set of new measurements from the `DataPublisher`.
-    pub new_measurements_callback:
-        Mutex<Option<Arc<dyn Fn(Vec<&dyn Measurement>) + Send + Sync + 'a>>>,
+    pub new_measurements_callback: Callback<NewMeasurementsHandler>,
 
-    // /// Called when `DataSubscriber` receives a set of new buffer block measurements from the `DataPublisher`.
-    //pub new_bufferblocks_callback: Mutex<Option<Arc<dyn Fn(Vec<&BufferBlock>) + Send + Sync>>>,
+    /// Called when `DataSubscriber` receives a set of new buffer block measurements from the `DataPublisher`.
+    pub new_buffer_blocks_callback: Callback<NewBufferBlocksHandler>,
 
     //
     /// Called when the `DataPublisher` sends a notification that temporal processing has completed, i.e., the end of a historical playback data stream has been reached.
-    pub processing_complete_callback: Mutex<Option<Arc<dyn Fn(&str) + Send + Sync + 'a>>>,
+    pub processing_complete_callback: Callback<dyn Fn(&str) + Send + Sync>,
 
     /// Called when the `DataPublisher` sends a notification that requires receipt.
-    pub notification_received_callback: Mutex<Option<Arc<dyn Fn(&str) + Send + Sync + 'a>>>,
+    pub notification_received_callback: Callback<dyn Fn(&str) + Send + Sync>,
+
+    /// Called with the index, 0 through 15, and payload of a user-defined response sent by the `DataPublisher`.
+    pub user_response_callback: Callback<UserResponseHandler>,
 
     /// Determines whether payload data is compressed, defaults to TSSC.
     pub compress_payload_data: bool,
@@ -142,8 +307,20 @@ pub struct DataSubscriber<'a> {
     /// Determines whether the signal index cache is compressed, defaults to GZip.
     pub compress_signal_index_cache: bool,
 
+    /// Determines whether internal measurements are exchanged during metadata synchronization.
+    pub receive_internal_metadata: bool,
+
+    /// Determines whether external measurements are exchanged during metadata synchronization.
+    pub receive_external_metadata: bool,
+
     /// Defines the STTP protocol version used by this library.
-    pub version: String,
+    pub version: u8,
+
+    /// Determines whether the command channel is secured with TLS, defaults to `SecurityMode::Off`.
+    pub security_mode: SecurityMode,
+
+    /// Defines the TLS settings applied when the security mode is `SecurityMode::TLS`.
+    pub tls_config: TlsConfig,
 
     /// Defines the STTP library API title as identification information of `DataSubscriber` to a `DataPublisher`.
     pub sttp_source_info: String,
@@ -156,45 +333,50 @@ pub struct DataSubscriber<'a> {
 
     // /// Defines the metadata cache associated with this `DataSubscriber`.
     //pub metadata_cache: MetadataCache,
+    measurement_registry: Mutex<HashMap<Uuid, MeasurementMetadata>>,
 
     //
     /// Defines the socket timeout in seconds for the `DataSubscriber` connection.
     pub socket_timeout: f64,
 
     // Measurement parsing
-    metadata_requested: DateTime<Utc>,
-    signal_index_cache: [SignalIndexCache; 2],
-    signal_index_cache_mutex: Mutex<()>,
-    cache_index: usize,
-    time_index: usize,
-    base_time_offsets: [i64; 2],
-    key_ivs: Option<Vec<Vec<u8>>>,
-    last_missing_cache_warning: f64,
+    metadata_requested: Mutex<DateTime<Utc>>,
+    signal_index_cache: Mutex<[Arc<SignalIndexCache>; 2]>,
+    cache_index: AtomicUsize,
+    time_index: AtomicUsize,
+    base_time_offsets: Mutex<[i64; 2]>,
+    key_ivs: Mutex<Option<Arc<KeyIVs>>>,
+    last_missing_cache_warning: Mutex<f64>,
     tssc_reset_requested: AtomicBool,
-    tssc_last_oos_report: DateTime<Utc>,
-    tssc_last_oos_report_mutex: Mutex<()>,
-    //buffer_block_expected_sequence_number: u32,
-    //buffer_block_cache: Vec<BufferBlock>,
+    tssc_last_oos_report: Mutex<DateTime<Utc>>,
+    buffer_block_expected_sequence_number: AtomicU32,
+    buffer_block_cache: Mutex<BTreeMap<u32, BufferBlock>>,
 }
 
-impl<'a> DataSubscriber<'a> {
+impl DataSubscriber {
     /// Creates a new `DataSubscriber` instance.
     pub fn new() -> Self {
         DataSubscriber {
-            subscription: SubscriptionInfo::default(),
-            subscriber_id: Uuid::nil(),
+            subscription: Mutex::new(SubscriptionInfo::default()),
+            subscriber_id: Mutex::new(Uuid::nil()),
             encoding: OperationalEncoding::UTF8,
+            operational_modes: Mutex::new(OperationalModes::NoFlags),
             connector: Arc::new(Mutex::new(SubscriberConnector::new())),
             connected: AtomicBool::new(false),
             validated: AtomicBool::new(false),
             listening: AtomicBool::new(false),
+            listening_thread: Mutex::new(None),
             subscribed: AtomicBool::new(false),
             command_channel_socket: Mutex::new(None),
-            command_channel_response_thread: None,
+            command_channel_response_thread: Mutex::new(None),
             data_channel_socket: Mutex::new(None),
-            data_channel_response_thread: None,
-            connection_id: "".to_string(),
+            data_channel_response_thread: Mutex::new(None),
+            data_channel_active: AtomicBool::new(false),
+            connection_id: Mutex::new(String::new()),
             connect_action_mutex: Mutex::new(()),
+            connection_generation: AtomicU64::new(0),
+            command_result_waiters: Mutex::new(HashMap::new()),
+            user_response_waiters: Mutex::new(UserResponseWaiters::default()),
             connection_termination_thread_mutex: Mutex::new(None),
             disconnect_thread_mutex: Mutex::new(None),
             disconnecting: AtomicBool::new(false),
@@ -209,45 +391,62 @@ impl<'a> DataSubscriber<'a> {
             connection_terminated_callback: Mutex::new(None),
             auto_reconnect_callback: Mutex::new(None),
             metadata_received_callback: Mutex::new(None),
+            metadata_parsed_callback: Mutex::new(None),
             subscription_updated_callback: Mutex::new(None),
             data_start_time_callback: Mutex::new(None),
             configuration_changed_callback: Mutex::new(None),
             new_measurements_callback: Mutex::new(None),
-            //new_buffer_blocks_callback:Mutex::new(None),
+            new_buffer_blocks_callback: Mutex::new(None),
             processing_complete_callback: Mutex::new(None),
             notification_received_callback: Mutex::new(None),
+            user_response_callback: Mutex::new(None),
             compress_payload_data: Defaults::COMPRESS_PAYLOAD_DATA,
             compress_metadata: Defaults::COMPRESS_METADATA,
             compress_signal_index_cache: Defaults::COMPRESS_SIGNAL_INDEX_CACHE,
-            version: Defaults::VERSION.to_string(),
+            receive_internal_metadata: Defaults::RECEIVE_INTERNAL_METADATA,
+            receive_external_metadata: Defaults::RECEIVE_EXTERNAL_METADATA,
+            version: Defaults::VERSION,
+            security_mode: SecurityMode::Off,
+            tls_config: TlsConfig::default(),
             sttp_source_info: Version::STTP_SOURCE.to_string(),
             sttp_version_info: Version::STTP_VERSION.to_string(),
             sttp_updated_on_info: Version::STTP_UPDATED_ON.to_string(),
             //metadata_cache: MetadataCache::new(),
+            measurement_registry: Mutex::new(HashMap::new()),
             socket_timeout: Defaults::SOCKET_TIMEOUT,
-            metadata_requested: DateTime::default(),
-            signal_index_cache: [SignalIndexCache::new(), SignalIndexCache::new()],
-            signal_index_cache_mutex: Mutex::new(()),
-            cache_index: 0,
-            time_index: 0,
-            base_time_offsets: [0, 0],
-            key_ivs: None,
-            last_missing_cache_warning: 0.0,
+            metadata_requested: Mutex::new(DateTime::default()),
+            signal_index_cache: Mutex::new([
+                Arc::new(SignalIndexCache::new()),
+                Arc::new(SignalIndexCache::new()),
+            ]),
+            cache_index: AtomicUsize::new(0),
+            time_index: AtomicUsize::new(0),
+            base_time_offsets: Mutex::new([0, 0]),
+            key_ivs: Mutex::new(None),
+            last_missing_cache_warning: Mutex::new(0.0),
             tssc_reset_requested: AtomicBool::new(false),
-            tssc_last_oos_report: DateTime::default(),
-            tssc_last_oos_report_mutex: Mutex::new(()),
-            //buffer_block_expected_sequence_number: 0,
-            //buffer_block_cache: Vec::new(),
+            tssc_last_oos_report: Mutex::new(DateTime::default()),
+            buffer_block_expected_sequence_number: AtomicU32::new(0),
+            buffer_block_cache: Mutex::new(BTreeMap::new()),
         }
     }
 
     /// Cleanly shuts down a `DataSubscriber` that is no longer being used, e.g., during a normal application exit.
-    pub fn dispose(&self) {
+    pub fn dispose(self: &Arc<Self>) {
         self.disposing.store(true, Ordering::SeqCst);
-        self.connector.lock().unwrap().dispose();
 
-        // TODO: Implement
-        //self.disconnect(true, false);
+        // Wait on any running reconnect to complete, connector lock is released first since
+        // the reconnect thread accesses the connector
+        let reconnect_thread = self.connector.lock().unwrap().dispose();
+
+        if let Some(thread) = reconnect_thread {
+            if thread.thread().id() != thread::current().id() && thread.join().is_err() {
+                self.dispatch_error_message("failed to join reconnect thread");
+            }
+        }
+
+        // Disposal is a user request, so this is not an auto-reconnect, and listening stops
+        self._disconnect(true, false, true, None);
     }
 
     /// Determines if a `DataSubscriber` is currently connected to a `DataPublisher`.
@@ -256,6 +455,9 @@ impl<'a> DataSubscriber<'a> {
     }
 
     /// Determines if a `DataSubscriber` connection has been validated as an STTP connection.
+    ///
+    /// Publishers only reply to the operational modes sent on connect when they are rejected, which
+    /// disconnects, so the connection is validated once any other response has been received.
     pub fn is_validated(&self) -> bool {
         self.validated.load(Ordering::SeqCst)
     }
@@ -272,8 +474,172 @@ impl<'a> DataSubscriber<'a> {
     }
 
     /// Returns the IP address and DNS host name, if resolvable, of current STTP connection.
-    pub fn get_connection_id(&self) -> &str {
-        &self.connection_id
+    pub fn get_connection_id(&self) -> String {
+        self.connection_id.lock().unwrap().clone()
+    }
+
+    /// Gets the operational modes requested from the `DataPublisher` for the current connection.
+    pub fn operational_modes(&self) -> OperationalModes {
+        *self.operational_modes.lock().unwrap()
+    }
+
+    /// Gets a copy of the subscription related settings of the `DataSubscriber`.
+    pub fn subscription(&self) -> SubscriptionInfo {
+        self.subscription.lock().unwrap().clone()
+    }
+
+    /// Defines the subscription related settings used by the next call to `subscribe`.
+    pub fn set_subscription(&self, subscription: SubscriptionInfo) {
+        *self.subscription.lock().unwrap() = subscription;
+    }
+
+    /// Gets the total number of bytes received via the command channel since last connection.
+    pub fn total_command_channel_bytes_received(&self) -> u64 {
+        self.total_command_channel_bytes_received
+            .load(Ordering::SeqCst)
+    }
+
+    /// Gets the total number of bytes received via the data channel since last connection.
+    pub fn total_data_channel_bytes_received(&self) -> u64 {
+        self.total_data_channel_bytes_received
+            .load(Ordering::SeqCst)
+    }
+
+    /// Gets the total number of measurements received since last subscription.
+    pub fn total_measurements_received(&self) -> u64 {
+        self.total_measurements_received.load(Ordering::SeqCst)
+    }
+
+    /// Registers interest in the outcome of the next response to the specified `ServerCommand`.
+    ///
+    /// The returned receiver is sent a `CommandResult` when the `DataPublisher` replies to the command
+    /// with a `ServerResponse::Succeeded` or `ServerResponse::Failed` response code. Register before the
+    /// command is sent so that a quick response is not missed, then wait with `recv_timeout`, e.g.:
+    ///
+    /// ```no_run
+    /// # use sttp::transport::{DataSubscriber, ServerCommand};
+    /// # use std::time::Duration;
+    /// # use std::sync::Arc;
+    /// # let subscriber = Arc::new(DataSubscriber::new());
+    /// let receiver = subscriber.expect_command_result(ServerCommand::Subscribe);
+    /// subscriber.subscribe().unwrap();
+    ///
+    /// match receiver.recv_timeout(Duration::from_secs(5)) {
+    ///     Ok(result) if result.succeeded => println!("subscribed"),
+    ///     Ok(result) => println!("subscribe rejected: {}", result.message),
+    ///     Err(_) => println!("no response to subscribe"),
+    /// }
+    /// ```
+    ///
+    /// The receiver is disconnected without a result if the connection is terminated first.
+    pub fn expect_command_result(&self, command: ServerCommand) -> Receiver<CommandResult> {
+        let (sender, receiver) = mpsc::channel();
+
+        self.command_result_waiters
+            .lock()
+            .unwrap()
+            .entry(command)
+            .or_default()
+            .push(sender);
+
+        receiver
+    }
+
+    /// Sends a user-defined command, `ServerCommand::UserCommand00` through `UserCommand15` as selected
+    /// by `index`, with the specified payload to the `DataPublisher`.
+    ///
+    /// Any reply from the `DataPublisher` with the matching `ServerResponse::UserResponse00` through
+    /// `UserResponse15` code is delivered to the `user_response_callback`.
+    pub fn send_user_command(
+        self: &Arc<Self>,
+        index: u8,
+        data: &[u8],
+    ) -> Result<(), Box<dyn Error>> {
+        let command = self.user_command(index)?;
+
+        self.send_server_command_with_payload(command, data);
+
+        Ok(())
+    }
+
+    fn user_command(&self, index: u8) -> Result<ServerCommand, Box<dyn Error>> {
+        let command = match ServerCommand::user_command(index) {
+            Some(command) => command,
+            None => return Err(format!("user command index {} is out of range 0-15", index).into()),
+        };
+
+        if !self.connected.load(Ordering::SeqCst) {
+            return Err("subscriber is not connected; cannot send user command".into());
+        }
+
+        Ok(command)
+    }
+
+    /// Registers interest in the payload of the next user-defined response with the specified `index`.
+    ///
+    /// Like `expect_command_result`, register before the user command is sent so that a quick response
+    /// is not missed. The receiver is disconnected without a payload if the connection is terminated first.
+    pub fn expect_user_response(&self, index: u8) -> Receiver<Vec<u8>> {
+        let (sender, receiver) = mpsc::channel();
+
+        self.user_response_waiters
+            .lock()
+            .unwrap()
+            .add(index, sender);
+
+        receiver
+    }
+
+    /// Sends a user-defined command with the specified `index` and payload, then waits up to `timeout`
+    /// for the `DataPublisher` to reply with the user-defined response of the same index.
+    ///
+    /// Responses are correlated by index, so only one request per index should be outstanding. When a
+    /// request times out, the next response with its index is taken as its late reply and discarded.
+    pub fn request_user_response(
+        self: &Arc<Self>,
+        index: u8,
+        data: &[u8],
+        timeout: Duration,
+    ) -> Result<Vec<u8>, Box<dyn Error>> {
+        // Command is validated before registering so that a rejected request leaves no waiter
+        // behind, other callers may be awaiting a response with the same index
+        let command = self.user_command(index)?;
+        let (sender, receiver) = mpsc::channel();
+        let id = self
+            .user_response_waiters
+            .lock()
+            .unwrap()
+            .add(index, sender);
+
+        self.send_server_command_with_payload(command, data);
+
+        match receiver.recv_timeout(timeout) {
+            Ok(response) => Ok(response),
+            Err(RecvTimeoutError::Timeout) => {
+                // Response may have been delivered after the timeout elapsed
+                if !self
+                    .user_response_waiters
+                    .lock()
+                    .unwrap()
+                    .abandon(index, id)
+                {
+                    if let Ok(response) = receiver.try_recv() {
+                        return Ok(response);
+                    }
+                }
+
+                Err(format!(
+                    "timed out after {:?} waiting for response to user command {}",
+                    timeout, index
+                )
+                .into())
+            }
+            Err(RecvTimeoutError::Disconnected) => Err(format!(
+                "connection terminated while waiting for response to user command {}",
+                index
+            )
+            .into()),
+        }
     }
 
     /// Encodes an STTP string according to the defined operational modes.
@@ -287,36 +653,65 @@ impl<'a> DataSubscriber<'a> {
     }
 
     /// Decodes an STTP string according to the defined operational modes.
-    pub fn decode_string(&self, data: Vec<u8>) -> String {
+    pub fn decode_string(&self, data: &[u8]) -> String {
         // Latest version of STTP only encodes to UTF8
         if self.encoding != OperationalEncoding::UTF8 {
             panic!("Rust implementation of STTP only supports UTF8 string encoding")
         }
 
-        String::from_utf8(data).unwrap()
+        String::from_utf8_lossy(data).into_owned()
+    }
+
+    /// Gets the `MeasurementMetadata` for the specified signal ID from the local
+    /// registry. If the metadata does not exist, a new record is created and returned.
+    pub fn lookup_metadata(&self, signal_id: Uuid, source: &str, id: u64) -> MeasurementMetadata {
+        let mut registry = self.measurement_registry.lock().unwrap();
+
+        let metadata = registry
+            .entry(signal_id)
+            .or_insert_with(|| MeasurementMetadata::new(signal_id, source, id));
+
+        // Measurement key may only be known once a signal index cache is received
+        if metadata.source.is_empty() && !source.is_empty() {
+            metadata.source = source.to_string();
+            metadata.id = id;
+        }
+
+        metadata.clone()
+    }
+
+    /// Gets the `MeasurementMetadata` associated with a measurement from the local
+    /// registry. If the metadata does not exist, a new record is created and returned.
+    pub fn metadata(&self, measurement: &dyn Measurement) -> MeasurementMetadata {
+        self.lookup_metadata(measurement.signal_id(), "", 0)
     }
 
-    // TODO: Implement
-    // /// Gets the `MeasurementMetadata` for the specified signalID from the local
-    // /// registry. If the metadata does not exist, a new record is created and returned.
-    // pub fn lookup_metadata(&self, id: &str) -> &MeasurementMetadata {
+    /// Gets the value of a `Measurement` with any linear adjustments applied from the
+    /// measurement's adder and multiplier metadata, if found.
+    pub fn adjusted_value(&self, measurement: &dyn Measurement) -> f64 {
+        let registry = self.measurement_registry.lock().unwrap();
 
-    // /// Gets the `MeasurementMetadata` associated with a measurement from the local
-    // /// registry. If the metadata does not exist, a new record is created and returned.
-    // pub fn metadata(&self, measurement: Measurement) &MeasurementMetadata {
+        match registry.get(&measurement.signal_id()) {
+            Some(metadata) => metadata.adjusted_value(measurement.value()),
+            None => measurement.value(),
+        }
+    }
 
-    // /// AdjustedValue gets the Value of a Measurement with any linear adjustments applied from the
-    // /// measurement's Adder and Multiplier metadata, if found.
-    // pub fn AdjustedValue(&self, measurement: Measurement) float64 {
+    /// Loads the measurement records, e.g., from the `MeasurementDetail` and `ActiveMeasurements`
+    /// tables, of the specified `DataSet` into the local metadata registry. Returns the number of
+    /// records loaded. Metadata received from the publisher is loaded automatically.
+    pub fn load_metadata(&self, data_set: &DataSet) -> usize {
+        load_measurement_metadata(&mut self.measurement_registry.lock().unwrap(), data_set)
+    }
 
     /// Requests the the `DataSubscriber` initiate a connection to the `DataPublisher`.
-    pub fn connect(&mut self, hostname: &str, port: u16) -> Result<(), Box<dyn Error>> {
+    pub fn connect(self: &Arc<Self>, hostname: &str, port: u16) -> Result<(), Box<dyn Error>> {
         //  User requests to connection are not an auto-reconnect attempt
         self._connect(hostname, port, false)
     }
 
-    fn _connect(
-        &mut self,
+    pub(crate) fn _connect(
+        self: &Arc<Self>,
         hostname: &str,
         port: u16,
         auto_reconnecting: bool,
@@ -331,6 +726,8 @@ impl<'a> DataSubscriber<'a> {
             );
         }
 
+        self.validate_version()?;
+
         // Make sure any pending disconnect has completed to make sure socket is closed
         let mut disconnect_thread_guard = match self.disconnect_thread_mutex.lock() {
             Ok(guard) => guard,
@@ -340,7 +737,10 @@ impl<'a> DataSubscriber<'a> {
         };
 
         if let Some(discconnect_thread) = disconnect_thread_guard.take() {
-            if discconnect_thread.join().is_err() {
+            // Connection may be initiated from a connection terminated callback on disconnect thread
+            if discconnect_thread.thread().id() != thread::current().id()
+                && discconnect_thread.join().is_err()
+            {
                 return Err("failed to join disconnect thread".into());
             }
         }
@@ -349,9 +749,12 @@ impl<'a> DataSubscriber<'a> {
 
         // Let any pending connect or disconnect operation complete before new connect,
         // this prevents destruction disconnect before connection is completed
-        if self.connect_action_mutex.lock().is_err() {
-            return Err("failed to lock connect action mutex".into());
-        }
+        let _connect_action_guard = match self.connect_action_mutex.lock() {
+            Ok(guard) => guard,
+            Err(_) => {
+                return Err("failed to lock connect action mutex".into());
+            }
+        };
 
         // Initialize connection state
         self.setup_connection();
@@ -360,6 +763,8 @@ impl<'a> DataSubscriber<'a> {
             Ok(mut guard) => {
                 if !auto_reconnecting {
                     guard.reset_connection();
+                    guard.hostname = hostname.to_string();
+                    guard.port = port;
                 }
                 guard.connection_refused.store(false, Ordering::SeqCst);
             }
@@ -368,20 +773,194 @@ impl<'a> DataSubscriber<'a> {
             }
         }
 
-        // TODO: Add TLS implementation options
-        match TcpStream::connect(hostname.to_owned() + ":" + port.to_string().as_str()) {
-            Ok(stream) => {
-                self.establish_connection(stream, false);
-            }
+        let stream = match TcpStream::connect(hostname.to_owned() + ":" + port.to_string().as_str())
+        {
+            Ok(stream) => stream,
             Err(e) => {
                 return Err(format!("failed to connect: {}", e).into());
             }
+        };
+
+        let command_channel = match self.security_mode {
+            SecurityMode::Off => CommandChannel::Tcp(stream),
+            SecurityMode::TLS => {
+                CommandChannel::Tls(Arc::new(tls::connect(stream, hostname, &self.tls_config)?))
+            }
+        };
+
+        self.establish_connection(command_channel, false)
+    }
+
+    /// Requests that the `DataSubscriber` listen for a connection from a `DataPublisher`, i.e., a
+    /// reverse connection that the publisher initiates, e.g., from behind a firewall. An empty
+    /// `address` listens on all IPv4 interfaces.
+    ///
+    /// Each accepted connection replaces any active connection so a publisher can repeatedly
+    /// reconnect. Listening continues until `disconnect` is called.
+    pub fn listen(self: &Arc<Self>, address: &str, port: u16) -> Result<(), Box<dyn Error>> {
+        if self.connected.load(Ordering::SeqCst) {
+            return Err("subscriber is already connected; disconnect first".into());
+        }
+
+        if self.listening.load(Ordering::SeqCst) {
+            return Err("subscriber is already listening for connections".into());
+        }
+
+        self.validate_version()?;
+
+        if self.security_mode == SecurityMode::TLS {
+            return Err("TLS is not supported for listening connections".into());
+        }
+
+        // Make sure any prior listening thread has released its socket
+        let listening_thread = self.listening_thread.lock().unwrap().take();
+
+        if let Some(listening_thread) = listening_thread {
+            if listening_thread.join().is_err() {
+                return Err("failed to join listening thread".into());
+            }
+        }
+
+        let address = if address.is_empty() {
+            "0.0.0.0"
+        } else {
+            address
+        };
+
+        let listener = TcpListener::bind((address, port))
+            .map_err(|e| format!("failed to listen on {}:{}: {}", address, port, e))?;
+
+        // Accepts are polled so that the listening thread can observe when listening stops
+        listener.set_nonblocking(true)?;
+
+        let local_address = listener.local_addr()?;
+
+        self.listening.store(true, Ordering::SeqCst);
+
+        let ds = Arc::clone(self);
+
+        *self.listening_thread.lock().unwrap() = Some(thread::spawn(move || {
+            ds.run_listening_thread(listener);
+        }));
+
+        self.dispatch_status_message(&format!(
+            "Listening for publisher connections on {} ...",
+            local_address
+        ));
+
+        Ok(())
+    }
+
+    fn run_listening_thread(self: &Arc<Self>, listener: TcpListener) {
+        while self.listening.load(Ordering::SeqCst) {
+            let stream = match listener.accept() {
+                Ok((stream, _)) => stream,
+                Err(e) => {
+                    if e.kind() != ErrorKind::WouldBlock {
+                        self.dispatch_error_message(&format!(
+                            "error accepting publisher connection: {}",
+                            e
+                        ));
+                    }
+
+                    thread::sleep(LISTEN_POLL_INTERVAL);
+                    continue;
+                }
+            };
+
+            if let Err(e) = self.accept_connection(stream) {
+                self.dispatch_error_message(&format!(
+                    "failed to accept publisher connection: {}",
+                    e
+                ));
+            }
+        }
+    }
+
+    fn accept_connection(self: &Arc<Self>, stream: TcpStream) -> Result<(), Box<dyn Error>> {
+        // Accepted sockets can inherit the non-blocking mode of the listener on some platforms
+        stream.set_nonblocking(false)?;
+
+        let _connect_action_guard = match self.connect_action_mutex.lock() {
+            Ok(guard) => guard,
+            Err(_) => {
+                return Err("failed to lock connect action mutex".into());
+            }
+        };
+
+        // Listening may have stopped while waiting on connect action lock
+        if !self.listening.load(Ordering::SeqCst) {
+            return Ok(());
+        }
+
+        // A reconnecting publisher replaces the active connection, which may not have been
+        // detected as closed yet, e.g., when the publisher restarted without closing its socket
+        if self.connected.load(Ordering::SeqCst) {
+            self.dispatch_status_message(&format!(
+                "Publisher reconnected, replacing connection from \"{}\"",
+                self.get_connection_id()
+            ));
+        }
+
+        // Prior connection may also be terminating, its pending disconnect will not close the
+        // replacement connection, so it is closed here
+        if self.close_connection() {
+            self.dispatch_connection_terminated_callback();
+        }
+
+        self.setup_connection();
+        self.establish_connection(CommandChannel::Tcp(stream), true)
+    }
+
+    // Closes the sockets of the active connection and waits for its threads to complete.
+    // Returns true if there was an active connection to close.
+    fn close_connection(&self) -> bool {
+        self.connected.store(false, Ordering::SeqCst);
+        self.validated.store(false, Ordering::SeqCst);
+        self.subscribed.store(false, Ordering::SeqCst);
+
+        self.close_data_channel();
+
+        let command_channel = self.command_channel_socket.lock().unwrap().take();
+        let was_connected = command_channel.is_some();
+
+        if let Some(command_channel) = command_channel {
+            // Socket may already be closed by peer
+            let _ = command_channel.shutdown();
+        }
+
+        let command_channel_response_thread =
+            self.command_channel_response_thread.lock().unwrap().take();
+
+        if let Some(thread) = command_channel_response_thread {
+            if thread.thread().id() != thread::current().id() && thread.join().is_err() {
+                self.dispatch_error_message("failed to join command channel response thread");
+            }
+        }
+
+        // Release any callers awaiting command results, no responses will arrive now
+        self.command_result_waiters.lock().unwrap().clear();
+        self.user_response_waiters.lock().unwrap().clear();
+
+        was_connected
+    }
+
+    pub(crate) fn validate_version(&self) -> Result<(), Box<dyn Error>> {
+        if !(MIN_SUPPORTED_VERSION..=MAX_SUPPORTED_VERSION).contains(&self.version) {
+            return Err(format!(
+                "STTP protocol version {} is not supported; version must be between {} and {}",
+                self.version, MIN_SUPPORTED_VERSION, MAX_SUPPORTED_VERSION
+            )
+            .into());
         }
 
         Ok(())
     }
 
-    fn setup_connection(&mut self) {
+    fn setup_connection(&self) {
+        // Identifies the connection so that termination of a prior connection cannot close it
+        self.connection_generation.fetch_add(1, Ordering::SeqCst);
+
         self.disconnected.store(false, Ordering::SeqCst);
         self.subscribed.store(false, Ordering::SeqCst);
 
@@ -391,170 +970,212 @@ impl<'a> DataSubscriber<'a> {
             .store(0, Ordering::SeqCst);
         self.total_measurements_received.store(0, Ordering::SeqCst);
 
-        self.key_ivs = None;
+        *self.key_ivs.lock().unwrap() = None;
 
-        // TODO: Implement
-        //self.buffer_block_expected_sequence_number = 0;
-        //self.measurement_registry = Dict(Uuid, MeasurementMetadata);
+        *self.base_time_offsets.lock().unwrap() = [0, 0];
+        self.time_index.store(0, Ordering::SeqCst);
+
+        *self.signal_index_cache.lock().unwrap() = [
+            Arc::new(SignalIndexCache::new()),
+            Arc::new(SignalIndexCache::new()),
+        ];
+        self.cache_index.store(0, Ordering::SeqCst);
+
+        self.measurement_registry.lock().unwrap().clear();
+
+        self.buffer_block_expected_sequence_number
+            .store(0, Ordering::SeqCst);
+        self.buffer_block_cache.lock().unwrap().clear();
     }
 
-    fn establish_connection(&mut self, stream: TcpStream, listening: bool) {
+    fn establish_connection(
+        self: &Arc<Self>,
+        command_channel: CommandChannel,
+        listening: bool,
+    ) -> Result<(), Box<dyn Error>> {
         let mut addr_name = "<unknown>".to_string();
 
         if listening {
-            // TODO: Implement / Add DNS resolution
-        } else {
-            match self.connector.lock() {
-                Ok(guard) => {
-                    addr_name = guard.hostname.to_owned() + ":" + guard.port.to_string().as_str();
-                }
-                Err(_) => {}
+            // Identify publisher by its reverse DNS name, when available, and its address
+            if let Ok(peer) = command_channel.peer_addr() {
+                addr_name = match dns_lookup::lookup_addr(&peer.ip()) {
+                    Ok(hostname) if hostname != peer.ip().to_string() => {
+                        format!("{} ({})", hostname, peer)
+                    }
+                    _ => peer.to_string(),
+                };
             }
+        } else if let Ok(guard) = self.connector.lock() {
+            addr_name = guard.hostname.to_owned() + ":" + guard.port.to_string().as_str();
         }
 
-        self.connection_id = addr_name.to_string();
+        *self.connection_id.lock().unwrap() = addr_name;
 
         if listening {
-            self.dispatch_status_message(
-                &("Processing connection attempt from \"".to_string()
-                    + &self.connection_id
-                    + "\" ..."),
-            );
+            self.dispatch_status_message(&format!(
+                "Processing connection attempt from \"{}\" ...",
+                self.get_connection_id()
+            ));
         }
 
-        self.connected.store(true, Ordering::SeqCst);
-        self.last_missing_cache_warning = 0.0;
+        let read_stream = match command_channel.reader() {
+            Ok(read_stream) => read_stream,
+            Err(e) => {
+                return Err(format!("failed to clone command channel socket: {}", e).into());
+            }
+        };
 
-        let read_stream = stream
-            .try_clone()
-            .expect("failed to clone command channel socket");
+        *self.command_channel_socket.lock().unwrap() = Some(command_channel);
+        *self.last_missing_cache_warning.lock().unwrap() = 0.0;
+        self.connected.store(true, Ordering::SeqCst);
 
-        self.command_channel_socket = Mutex::new(Some(stream));
+        let ds = Arc::clone(self);
 
-        self.command_channel_response_thread = Some(thread::spawn(move || {
-            Self::run_command_channel_response_thread(read_stream);
+        *self.command_channel_response_thread.lock().unwrap() = Some(thread::spawn(move || {
+            ds.run_command_channel_response_thread(read_stream);
         }));
 
         self.send_operational_modes();
 
         // Notify consumers of the connection
-        let callback_mutex = self.connection_established_callback.lock();
+        let callback = self.connection_established_callback.lock().unwrap().clone();
 
-        if callback_mutex.is_ok() {
-            let callback_guard = callback_mutex.unwrap();
-            if callback_guard.is_some() {
-                let callback = callback_guard.as_ref().unwrap();
-                callback();
-            }
+        if let Some(callback) = callback {
+            callback();
         }
+
+        Ok(())
     }
 
-    ///
-    pub fn subscribe(&mut self) -> Result<(), Box<dyn Error>> {
+    /// Notifies the `DataPublisher` that a `DataSubscriber` would like to start receiving streaming data.
+    pub fn subscribe(self: &Arc<Self>) -> Result<(), Box<dyn Error>> {
         if !self.connected.load(Ordering::SeqCst) {
             return Err("subscriber is not connected; cannot subscribe".into());
         }
 
-        if !self.validated.load(Ordering::SeqCst) {
-            return Err("subscriber is not validated; cannot subscribe".into());
-        }
-
         if self.subscribed.load(Ordering::SeqCst) {
             self.unsubscribe();
         }
 
         self.total_measurements_received.store(0, Ordering::SeqCst);
 
+        let subscription = self.subscription();
         let mut parameter_builder = String::new();
 
         parameter_builder.push_str("throttled=");
-        parameter_builder.push_str(&format!("{}", self.subscription.throttled));
+        parameter_builder.push_str(&format!("{}", subscription.throttled));
         parameter_builder.push_str(";publishInterval=");
-        parameter_builder.push_str(&format!("{:.6}", self.subscription.publish_interval));
+        parameter_builder.push_str(&format!("{:.6}", subscription.publish_interval));
         parameter_builder.push_str(";includeTime=");
-        parameter_builder.push_str(&format!("{}", self.subscription.include_time));
+        parameter_builder.push_str(&format!("{}", subscription.include_time));
         parameter_builder.push_str(";enableTimeReasonabilityCheck=");
-        parameter_builder.push_str(&format!(
-            "{}",
-            self.subscription.enable_time_reasonability_check
-        ));
+        parameter_builder.push_str(&format!("{}", subscription.enable_time_reasonability_check));
         parameter_builder.push_str(";lagTime=");
-        parameter_builder.push_str(&format!("{:.6}", self.subscription.lag_time));
+        parameter_builder.push_str(&format!("{:.6}", subscription.lag_time));
         parameter_builder.push_str(";leadTime=");
-        parameter_builder.push_str(&format!("{:.6}", self.subscription.lead_time));
+        parameter_builder.push_str(&format!("{:.6}", subscription.lead_time));
         parameter_builder.push_str(";useLocalClockAsRealTime=");
-        parameter_builder.push_str(&format!(
-            "{}",
-            self.subscription.use_local_clock_as_real_time
-        ));
+        parameter_builder.push_str(&format!("{}", subscription.use_local_clock_as_real_time));
         parameter_builder.push_str(";processingInterval=");
-        parameter_builder.push_str(&format!("{}", self.subscription.processing_interval));
+        parameter_builder.push_str(&format!("{}", subscription.processing_interval));
         parameter_builder.push_str(";useMillisecondResolution=");
-        parameter_builder.push_str(&format!("{}", self.subscription.use_millisecond_resolution));
-        parameter_builder.push_str(";requestNaNValueFilter");
-        parameter_builder.push_str(&format!("{}", self.subscription.request_nan_value_filter));
+        parameter_builder.push_str(&format!("{}", subscription.use_millisecond_resolution));
+        parameter_builder.push_str(";requestNaNValueFilter=");
+        parameter_builder.push_str(&format!("{}", subscription.request_nan_value_filter));
         parameter_builder.push_str(";assemblyInfo={source=");
         parameter_builder.push_str(&self.sttp_source_info);
         parameter_builder.push_str(";version=");
         parameter_builder.push_str(&self.sttp_version_info);
         parameter_builder.push_str(";updatedOn=");
         parameter_builder.push_str(&self.sttp_updated_on_info);
-        parameter_builder.push_str("}");
+        parameter_builder.push('}');
 
-        if self.subscription.filter_expression.len() > 0 {
+        if !subscription.filter_expression.is_empty() {
             parameter_builder.push_str(";filterExpression={");
-            parameter_builder.push_str(&self.subscription.filter_expression);
-            parameter_builder.push_str("}");
+            parameter_builder.push_str(&subscription.filter_expression);
+            parameter_builder.push('}');
         }
 
-        // if self.subscription.udp_data_channel {
-        // TODO: Implement
-        // }
+        // Make sure any data channel from a prior subscription is closed before binding a new one
+        self.close_data_channel();
+
+        if subscription.udp_data_channel {
+            let interface = if subscription.data_channel_interface.is_empty() {
+                "0.0.0.0"
+            } else {
+                subscription.data_channel_interface.as_str()
+            };
+
+            let socket = UdpSocket::bind((interface, subscription.data_channel_local_port))
+                .map_err(|e| {
+                    format!(
+                        "failed to open UDP socket for port {}: {}",
+                        subscription.data_channel_local_port, e
+                    )
+                })?;
+
+            // Requested port may be zero, so advertise port actually assigned to the socket
+            let local_port = socket.local_addr()?.port();
+            let read_socket = socket.try_clone()?;
+
+            // Reads time out periodically so the receive thread can observe data channel shutdown
+            read_socket.set_read_timeout(Some(DATA_CHANNEL_READ_TIMEOUT))?;
+
+            *self.data_channel_socket.lock().unwrap() = Some(socket);
+            self.data_channel_active.store(true, Ordering::SeqCst);
+
+            let ds = Arc::clone(self);
+
+            *self.data_channel_response_thread.lock().unwrap() = Some(thread::spawn(move || {
+                ds.run_data_channel_response_thread(read_socket);
+            }));
+
+            parameter_builder.push_str(";dataChannel={localport=");
+            parameter_builder.push_str(&local_port.to_string());
+            parameter_builder.push_str(";interface=");
+            parameter_builder.push_str(interface);
+            parameter_builder.push('}');
+        }
 
-        if self.subscription.start_time.len() > 0 {
+        if !subscription.start_time.is_empty() {
             parameter_builder.push_str(";startTimeConstraint=");
-            parameter_builder.push_str(&self.subscription.start_time);
+            parameter_builder.push_str(&subscription.start_time);
         }
 
-        if self.subscription.stop_time.len() > 0 {
+        if !subscription.stop_time.is_empty() {
             parameter_builder.push_str(";stopTimeConstraint=");
-            parameter_builder.push_str(&self.subscription.stop_time);
+            parameter_builder.push_str(&subscription.stop_time);
         }
 
-        if self.subscription.constraint_parameters.len() > 0 {
+        if !subscription.constraint_parameters.is_empty() {
             parameter_builder.push_str(";timeConstraintParameters=");
-            parameter_builder.push_str(&self.subscription.constraint_parameters);
+            parameter_builder.push_str(&subscription.constraint_parameters);
         }
 
-        if self.subscription.extra_connection_string_parameters.len() > 0 {
+        if !subscription.extra_connection_string_parameters.is_empty() {
             parameter_builder.push(';');
-            parameter_builder.push_str(&self.subscription.extra_connection_string_parameters);
+            parameter_builder.push_str(&subscription.extra_connection_string_parameters);
         }
 
-        let parameter_string = parameter_builder.to_string();
-        let length = parameter_string.len();
-        let mut buffer = vec![0u8; 5 + length];
+        let parameter_string = self.encode_string(&parameter_builder);
+        let length = parameter_string.len() as u32;
+        let mut buffer = vec![0u8; 5 + parameter_string.len()];
 
         buffer[0] = DataPacketFlags::COMPACT.bits();
         buffer[1..5].copy_from_slice(&length.to_be_bytes());
-        buffer[5..].copy_from_slice(parameter_string.as_bytes());
+        buffer[5..].copy_from_slice(&parameter_string);
 
         self.send_server_command_with_payload(ServerCommand::Subscribe, buffer.as_slice());
 
         // Reset TSSC decompressor on successful (re)subscription
-        let guard = self.tssc_last_oos_report_mutex.try_lock();
-
-        if guard.is_ok() {
-            self.tssc_last_oos_report = Utc::now();
-        }
-
+        *self.tssc_last_oos_report.lock().unwrap() = Utc::now();
         self.tssc_reset_requested.store(true, Ordering::SeqCst);
 
         Ok(())
     }
 
     /// Notifies the `DataPublisher` that a `DataSubscriber` would like to stop receiving streaming data.
-    pub fn unsubscribe(&mut self) {
+    pub fn unsubscribe(self: &Arc<Self>) {
         if !self.connected.load(Ordering::SeqCst) || !self.subscribed.load(Ordering::SeqCst) {
             return;
         }
@@ -562,40 +1183,53 @@ impl<'a> DataSubscriber<'a> {
         self.send_server_command(ServerCommand::Unsubscribe);
 
         self.disconnecting.store(true, Ordering::SeqCst);
+        self.close_data_channel();
+        self.disconnecting.store(false, Ordering::SeqCst);
+    }
 
-        let result = self.data_channel_socket.lock();
-
-        if result.is_ok() {
-            let mut guard = result.unwrap();
+    // Stops the UDP data channel receive thread, if any, and releases its socket
+    fn close_data_channel(&self) {
+        self.data_channel_active.store(false, Ordering::SeqCst);
 
-            if guard.is_some() {
-                let socket = guard.as_mut().unwrap();
-                let result = socket.shutdown(Shutdown::Both);
+        let data_channel_response_thread = self.data_channel_response_thread.lock().unwrap().take();
 
-                if result.is_err() {
-                    self.dispatch_error_message(&format!(
-                        "exception while disconnecting data subscriber UDP data channel: {}",
-                        result.err().unwrap()
-                    ));
-                }
+        if let Some(thread) = data_channel_response_thread {
+            // Thread cannot join itself, e.g., when unsubscribing from a measurement callback
+            if thread.thread().id() != thread::current().id() && thread.join().is_err() {
+                self.dispatch_error_message("failed to join data channel response thread");
             }
         }
 
-        if self.data_channel_response_thread.is_some() {
-            let thread = self.data_channel_response_thread.take().unwrap();
-            let result = thread.join();
+        *self.data_channel_socket.lock().unwrap() = None;
+    }
 
-            if result.is_err() {
-                self.dispatch_error_message("failed to join disconnect thread");
-            }
+    /// Requests that the `DataPublisher` send a new set of cipher keys for encrypting data
+    /// packets sent over a UDP data channel.
+    pub fn rotate_cipher_keys(self: &Arc<Self>) {
+        self.send_server_command(ServerCommand::RotateCipherKeys);
+    }
+
+    /// Requests metadata from the `DataPublisher`. When `metadata_filters` is not empty, it is sent
+    /// as a set of semicolon separated filter expressions used to limit the returned metadata tables.
+    pub fn request_metadata(self: &Arc<Self>, metadata_filters: &str) {
+        if metadata_filters.is_empty() {
+            self.send_server_command(ServerCommand::MetadataRefresh);
+            return;
         }
 
-        self.disconnecting.store(false, Ordering::SeqCst);
+        let filters = self.encode_string(metadata_filters);
+        let mut buffer = Vec::with_capacity(4 + filters.len());
+
+        buffer.extend_from_slice(&(filters.len() as u32).to_be_bytes());
+        buffer.extend_from_slice(&filters);
+
+        self.send_server_command_with_payload(ServerCommand::MetadataRefresh, &buffer);
     }
 
     /// Initiates a `DataSubscriber` disconnect sequence.
-    pub fn disconnect(&self) {
-        if self.disconnecting.load(Ordering::SeqCst) {
+    pub fn disconnect(self: &Arc<Self>) {
+        // A pending disconnect, e.g., from a connection termination, does not stop listening
+        if self.disconnecting.load(Ordering::SeqCst) && !self.listening.load(Ordering::SeqCst) {
             return;
         }
 
@@ -603,37 +1237,56 @@ impl<'a> DataSubscriber<'a> {
         // issues where user may call disconnect method from a dispatched event thread. Also,
         // user requests to disconnect are not an auto-reconnect attempt and should initiate
         // shutdown of listening socket as well.
-        self._disconnect(false, false, true);
+        self._disconnect(false, false, true, None);
     }
 
-    fn _disconnect(&self, join_thread: bool, auto_reconnecting: bool, include_listener: bool) {
+    fn _disconnect(
+        self: &Arc<Self>,
+        join_thread: bool,
+        auto_reconnecting: bool,
+        include_listener: bool,
+        connection_generation: Option<u64>,
+    ) {
+        // Termination of a connection that has since been replaced must not disturb the new
+        // connection, so the connection is checked while no new connection can be established
+        let connect_action_guard = match connection_generation {
+            Some(generation) => {
+                let guard = self.connect_action_mutex.lock().unwrap();
+
+                if self.connection_generation.load(Ordering::SeqCst) != generation {
+                    return;
+                }
+
+                Some(guard)
+            }
+            None => None,
+        };
+
         // Check if disconnect thread is running or subscriber has already disconnected
         if self.disconnecting.load(Ordering::SeqCst) {
+            // Stopping the listener follows the pending disconnect so that any connection
+            // accepted in the meantime is also closed and the listening thread is joined
+            if include_listener && self.listening.swap(false, Ordering::SeqCst) {
+                self.disconnect_after_pending(join_thread);
+                return;
+            }
+
             if !auto_reconnecting
                 && !self.listening.load(Ordering::SeqCst)
                 && !self.disconnected.load(Ordering::SeqCst)
             {
-                match self.connector.lock() {
-                    Ok(guard) => {
-                        guard.cancel();
-                    }
-                    Err(_) => {}
+                if let Ok(guard) = self.connector.lock() {
+                    guard.cancel();
                 }
             }
 
             if join_thread && !self.disconnected.load(Ordering::SeqCst) {
-                let result = self.disconnect_thread_mutex.lock();
-
-                if result.is_ok() {
-                    let mut guard = result.unwrap();
-
-                    if guard.is_some() {
-                        let thread = guard.take().unwrap();
-                        let result = thread.join();
+                let disconnect_thread = self.disconnect_thread_mutex.lock().unwrap().take();
 
-                        if result.is_err() {
-                            self.dispatch_error_message("failed to join disconnect thread");
-                        }
+                if let Some(thread) = disconnect_thread {
+                    // Disconnect thread cannot join itself, e.g., when disposing from a callback
+                    if thread.thread().id() != thread::current().id() && thread.join().is_err() {
+                        self.dispatch_error_message("failed to join disconnect thread");
                     }
                 }
             }
@@ -652,152 +1305,2484 @@ impl<'a> DataSubscriber<'a> {
 
         self.subscribed.store(false, Ordering::SeqCst);
 
-        // TODO: Implement
-        // let disconnect_thread = thread::spawn(move || {
-        //     self.run_disconnect_thread(&auto_reconnecting, &include_listener)
-        // });
+        // Release any callers awaiting command results, no responses will arrive now
+        self.command_result_waiters.lock().unwrap().clear();
+        self.user_response_waiters.lock().unwrap().clear();
+
+        drop(connect_action_guard);
+
+        let ds = Arc::clone(self);
+
+        if join_thread {
+            let disconnect_thread = thread::spawn(move || {
+                ds.run_disconnect_thread(
+                    auto_reconnecting,
+                    include_listener,
+                    connection_generation,
+                );
+            });
+
+            if disconnect_thread.join().is_err() {
+                self.dispatch_error_message("failed to join disconnect thread");
+            }
+        } else {
+            // Disconnect thread is stored before it can be observed as pending by other callers
+            let mut disconnect_thread_guard = self.disconnect_thread_mutex.lock().unwrap();
+
+            *disconnect_thread_guard = Some(thread::spawn(move || {
+                ds.run_disconnect_thread(
+                    auto_reconnecting,
+                    include_listener,
+                    connection_generation,
+                );
+            }));
+        }
+    }
+
+    fn disconnect_after_pending(self: &Arc<Self>, join_thread: bool) {
+        if let Ok(guard) = self.connector.lock() {
+            guard.cancel();
+        }
+
+        let mut disconnect_thread_guard = self.disconnect_thread_mutex.lock().unwrap();
+        let pending_thread = disconnect_thread_guard.take();
+        let caller_thread_id = thread::current().id();
+        let ds = Arc::clone(self);
+
+        let disconnect_thread = thread::spawn(move || {
+            // Pending disconnect thread may be the caller, e.g., when disposing from a callback
+            if let Some(thread) = pending_thread {
+                if thread.thread().id() != caller_thread_id && thread.join().is_err() {
+                    ds.dispatch_error_message("failed to join disconnect thread");
+                }
+            }
+
+            ds._disconnect(true, false, true, None);
+        });
+
+        if join_thread {
+            drop(disconnect_thread_guard);
+
+            if disconnect_thread.join().is_err() {
+                self.dispatch_error_message("failed to join disconnect thread");
+            }
+        } else {
+            *disconnect_thread_guard = Some(disconnect_thread);
+        }
+    }
+
+    fn run_disconnect_thread(
+        self: &Arc<Self>,
+        auto_reconnecting: bool,
+        include_listener: bool,
+        connection_generation: Option<u64>,
+    ) {
+        // Let any pending connection termination complete before disconnect, user requested
+        // disconnects also cancel any pending connection attempts
+        if !auto_reconnecting {
+            if let Ok(guard) = self.connector.lock() {
+                guard.cancel();
+            }
+
+            let connection_termination_thread = self
+                .connection_termination_thread_mutex
+                .lock()
+                .unwrap()
+                .take();
+
+            if let Some(thread) = connection_termination_thread {
+                if thread.thread().id() != thread::current().id() && thread.join().is_err() {
+                    self.dispatch_error_message("failed to join connection termination thread");
+                }
+            }
+        }
+
+        // Let any pending connect operation complete before disconnect - prevents destruction
+        // disconnect before connection is completed
+        let connect_action_guard = self.connect_action_mutex.lock().unwrap();
+
+        // A listening subscriber may have accepted a new connection, which closed the terminated
+        // connection, while waiting on the connect action lock
+        if let Some(generation) = connection_generation {
+            if self.connection_generation.load(Ordering::SeqCst) != generation {
+                self.disconnecting.store(false, Ordering::SeqCst);
+                return;
+            }
+        }
+
+        // Close sockets so that reader threads can shutdown gracefully, then wait for them
+        let was_connected = self.close_connection();
+
+        drop(connect_action_guard);
+
+        // Listening thread may be waiting on connect action lock, so it is joined after release
+        if include_listener {
+            let listening_thread = self.listening_thread.lock().unwrap().take();
+
+            if let Some(thread) = listening_thread {
+                if thread.thread().id() != thread::current().id() && thread.join().is_err() {
+                    self.dispatch_error_message("failed to join listening thread");
+                }
+            }
+        }
+
+        // Disconnect complete
+        self.disconnected.store(true, Ordering::SeqCst);
+        self.disconnecting.store(false, Ordering::SeqCst);
+
+        // Notify consumers of disconnect, once per established connection
+        if was_connected {
+            self.dispatch_connection_terminated_callback();
+        }
+
+        // Listening subscribers wait for the publisher to reconnect instead
+        if auto_reconnecting
+            && !self.listening.load(Ordering::SeqCst)
+            && !self.disposing.load(Ordering::SeqCst)
+            && self.connector.lock().unwrap().auto_reconnect
+        {
+            SubscriberConnector::auto_reconnect(self);
+        }
+    }
+
+    fn dispatch_connection_terminated_callback(&self) {
+        let callback = self.connection_terminated_callback.lock().unwrap().clone();
+
+        if let Some(callback) = callback {
+            callback();
+        }
+    }
+
+    fn run_command_channel_response_thread(self: &Arc<Self>, stream: Box<dyn Read + Send>) {
+        let mut reader = BufReader::new(stream);
+        let mut header = [0u8; PAYLOAD_HEADER_SIZE];
+        let mut buffer = vec![0u8; MAX_PACKET_SIZE];
+
+        while self.connected.load(Ordering::SeqCst) {
+            // Read payload header, i.e., the big-endian size of the following response frame
+            if let Err(e) = reader.read_exact(&mut header) {
+                self.handle_command_channel_read_error(e);
+                return;
+            }
 
-        // self.disconnect_thread_mutex = Mutex::new(Some(disconnect_thread));
+            self.total_command_channel_bytes_received
+                .fetch_add(PAYLOAD_HEADER_SIZE as u64, Ordering::SeqCst);
+
+            let packet_size = u32::from_be_bytes(header) as usize;
+
+            if packet_size > MAX_RESPONSE_SIZE {
+                // Skip over the oversized frame so that framing remains intact for the next response
+                self.dispatch_error_message(&format!(
+                    "received command channel response of {} bytes which exceeds the maximum allowed size of {} bytes; response ignored",
+                    packet_size, MAX_RESPONSE_SIZE
+                ));
+
+                match io::copy(
+                    &mut reader.by_ref().take(packet_size as u64),
+                    &mut io::sink(),
+                ) {
+                    Ok(skipped) if skipped == packet_size as u64 => {
+                        self.total_command_channel_bytes_received
+                            .fetch_add(skipped, Ordering::SeqCst);
+                        continue;
+                    }
+                    Ok(_) => {
+                        self.handle_command_channel_read_error(ErrorKind::UnexpectedEof.into());
+                        return;
+                    }
+                    Err(e) => {
+                        self.handle_command_channel_read_error(e);
+                        return;
+                    }
+                }
+            }
 
-        // let result = self.disconnect_thread_mutex.lock();
+            if packet_size > buffer.len() {
+                buffer.resize(packet_size, 0);
+            }
 
-        // if result.is_ok() {
-        //     let mut guard = result.unwrap();
+            // Read response frame, read_exact handles any partial reads from the socket
+            if let Err(e) = reader.read_exact(&mut buffer[..packet_size]) {
+                self.handle_command_channel_read_error(e);
+                return;
+            }
 
-        //     if guard.is_some() {
-        //         let thread = guard.take().unwrap();
-        //         let result = thread.join();
+            self.total_command_channel_bytes_received
+                .fetch_add(packet_size as u64, Ordering::SeqCst);
 
-        //         if result.is_err() {
-        //             self.dispatch_error_message("failed to join disconnect thread");
-        //         }
-        //     }
-        // }
+            self.process_server_response(&buffer[..packet_size]);
+        }
     }
 
-    fn run_disconnect_thread(&self, auto_reconnecting: &bool, include_listener: &bool) {}
+    fn run_data_channel_response_thread(self: &Arc<Self>, socket: UdpSocket) {
+        let mut buffer = vec![0u8; MAX_DATAGRAM_SIZE];
 
-    fn run_command_channel_response_thread(mut stream: TcpStream) {
-        // TODO: Implement properly
-        loop
-        /* self.connected.load(Ordering::Relaxed) */
+        while self.connected.load(Ordering::SeqCst)
+            && self.data_channel_active.load(Ordering::SeqCst)
         {
-            // Read data from the server
-            let mut buffer = [0; 512];
-            match stream.read(&mut buffer) {
-                Ok(bytes_read) => {
-                    let response = from_utf8(&buffer[0..bytes_read]).unwrap();
-                    println!("Received response: {}", response);
+            // Each datagram holds one complete response frame without a payload header
+            let length = match socket.recv(&mut buffer) {
+                Ok(length) => length,
+                Err(e) if matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => {
+                    continue;
                 }
                 Err(e) => {
-                    println!("Failed to receive data: {}", e);
+                    if self.data_channel_active.load(Ordering::SeqCst)
+                        && !self.disconnecting.load(Ordering::SeqCst)
+                    {
+                        self.dispatch_error_message(&format!(
+                            "error reading data from UDP data channel: {}",
+                            e
+                        ));
+                    }
+
+                    return;
                 }
+            };
+
+            self.total_data_channel_bytes_received
+                .fetch_add(length as u64, Ordering::SeqCst);
+
+            self.process_server_response(&buffer[..length]);
+        }
+    }
+
+    fn handle_command_channel_read_error(self: &Arc<Self>, error: io::Error) {
+        // Read errors are expected when the socket is closed during a disconnect
+        if self.disconnecting.load(Ordering::SeqCst) || !self.connected.load(Ordering::SeqCst) {
+            return;
+        }
+
+        if error.kind() != ErrorKind::UnexpectedEof {
+            self.dispatch_error_message(&format!(
+                "error reading data from command channel: {}",
+                error
+            ));
+        }
+
+        self.dispatch_connection_terminated();
+    }
+
+    fn process_server_response(self: &Arc<Self>, buffer: &[u8]) {
+        if buffer.len() < RESPONSE_HEADER_SIZE {
+            self.dispatch_error_message(&format!(
+                "received command channel response of {} bytes which is smaller than the {} byte response header; response ignored",
+                buffer.len(),
+                RESPONSE_HEADER_SIZE
+            ));
+            return;
+        }
+
+        let response_code = buffer[0];
+        let command_code = buffer[1];
+        let data = &buffer[RESPONSE_HEADER_SIZE..];
+
+        let response = match ServerResponse::try_from(response_code) {
+            Ok(response) => response,
+            Err(_) => {
+                self.dispatch_error_message(&format!(
+                    "encountered unexpected server response code: 0x{:02X}",
+                    response_code
+                ));
+                return;
+            }
+        };
+
+        // Publisher processes commands in order, so any response other than a reply to the
+        // operational modes shows that the operational modes were accepted
+        if command_code != ServerCommand::DefineOperationalModes as u8 {
+            self.confirm_operational_modes();
+        }
+
+        if let Some(index) = response.user_response_index() {
+            self.handle_user_response(index, data);
+            return;
+        }
+
+        match response {
+            ServerResponse::Succeeded => self.handle_succeeded(command_code, data),
+            ServerResponse::Failed => self.handle_failed(command_code, data),
+            ServerResponse::DataPacket => self.handle_data_packet(data),
+            ServerResponse::UpdateSignalIndexCache => self.handle_update_signal_index_cache(data),
+            ServerResponse::UpdateCipherKeys => self.handle_update_cipher_keys(data),
+            ServerResponse::BufferBlock => self.handle_buffer_block(data),
+            ServerResponse::Notify => self.handle_notification(data),
+            ServerResponse::UpdateBaseTimes => self.handle_update_base_times(data),
+            ServerResponse::DataStartTime => self.handle_data_start_time(data),
+            ServerResponse::ProcessingComplete => self.handle_processing_complete(data),
+            ServerResponse::ConfigurationChanged => self.handle_configuration_changed(),
+            ServerResponse::NoOP => {
+                // NoOP is a keep-alive ping, nothing to do
             }
+            _ => self.dispatch_status_message(&format!(
+                "received unhandled server response: {:?}",
+                response
+            )),
         }
     }
 
-    fn send_operational_modes(&self) {}
+    fn handle_succeeded(self: &Arc<Self>, command_code: u8, data: &[u8]) {
+        let command = match ServerCommand::try_from(command_code) {
+            Ok(command) => command,
+            Err(_) => {
+                // If we don't know what the message is, we can't interpret the data sent with
+                // the packet. Deliver an error message to the user via the error message callback.
+                self.dispatch_error_message(&format!(
+                    "received success code in response to unknown server command: 0x{:02X}",
+                    command_code
+                ));
+                return;
+            }
+        };
+
+        match command {
+            ServerCommand::MetadataRefresh => {
+                // Metadata responses carry a serialized data set instead of a message
+                self.handle_metadata_refresh(data);
+                self.complete_command(command, true, String::new());
+                return;
+            }
+            ServerCommand::Subscribe => self.subscribed.store(true, Ordering::SeqCst),
+            ServerCommand::Unsubscribe => self.subscribed.store(false, Ordering::SeqCst),
+            ServerCommand::DefineOperationalModes => self.validated.store(true, Ordering::SeqCst),
+            _ => {}
+        }
 
-    fn send_server_command(&mut self, command: ServerCommand) {
-        self.send_server_command_with_payload(command, &[]);
+        // Each of these responses come with a message that will
+        // be delivered to the user via the status message callback.
+        let message = self.decode_string(data);
+        let mut status = format!(
+            "received success code in response to server command: {:?}",
+            command
+        );
+
+        if !message.is_empty() {
+            status.push('\n');
+            status.push_str(&message);
+        }
+
+        self.dispatch_status_message(&status);
+        self.complete_command(command, true, message);
+    }
+
+    fn handle_failed(self: &Arc<Self>, command_code: u8, data: &[u8]) {
+        let message = self.decode_string(data);
+
+        let command = match ServerCommand::try_from(command_code) {
+            Ok(command) => command,
+            Err(_) => {
+                let mut error = format!(
+                    "received failure code in response to unknown server command: 0x{:02X}",
+                    command_code
+                );
+
+                if !message.is_empty() {
+                    error.push('\n');
+                    error.push_str(&message);
+                }
+
+                self.dispatch_error_message(&error);
+                return;
+            }
+        };
+
+        match command {
+            ServerCommand::Connect => {
+                if let Ok(guard) = self.connector.lock() {
+                    guard.connection_refused.store(true, Ordering::SeqCst);
+                }
+            }
+            ServerCommand::Subscribe => self.subscribed.store(false, Ordering::SeqCst),
+            ServerCommand::DefineOperationalModes => self.validated.store(false, Ordering::SeqCst),
+            _ => {}
+        }
+
+        // A refused connection is only reported with the publisher's message
+        let mut error = if command == ServerCommand::Connect {
+            String::new()
+        } else if command == ServerCommand::DefineOperationalModes {
+            format!(
+                "publisher rejected operational modes for STTP protocol version {}; disconnecting",
+                operational_modes_version(self.operational_modes())
+            )
+        } else {
+            format!(
+                "received failure code in response to server command: {:?}",
+                command
+            )
+        };
+
+        if !message.is_empty() {
+            if !error.is_empty() {
+                error.push('\n');
+            }
+
+            error.push_str(&message);
+        }
+
+        if !error.is_empty() {
+            self.dispatch_error_message(&error);
+        }
+
+        self.complete_command(command, false, message);
+
+        // Session cannot continue with operational modes the publisher did not accept
+        if command == ServerCommand::DefineOperationalModes {
+            self.disconnect();
+        }
     }
 
-    fn send_server_command_with_message(&mut self, command: ServerCommand, message: &str) {
-        self.send_server_command_with_payload(command, message.as_bytes());
+    fn complete_command(&self, command: ServerCommand, succeeded: bool, message: String) {
+        let waiters = self
+            .command_result_waiters
+            .lock()
+            .unwrap()
+            .remove(&command)
+            .unwrap_or_default();
+
+        for waiter in waiters {
+            // Waiter may have given up on the result, so any send failure is ignored
+            let _ = waiter.send(CommandResult {
+                command,
+                succeeded,
+                message: message.clone(),
+            });
+        }
     }
 
-    fn send_server_command_with_payload(&mut self, command: ServerCommand, data: &[u8]) {
-        if !self.connected.load(Ordering::SeqCst) {
+    fn confirm_operational_modes(&self) {
+        if !self.connected.load(Ordering::SeqCst) || self.validated.swap(true, Ordering::SeqCst) {
             return;
         }
 
-        let packet_size = data.len() + 1;
-        let command_buffer_size = 5 + packet_size + PAYLOAD_HEADER_SIZE;
-        let mut buffer = vec![0u8; command_buffer_size];
+        self.complete_command(ServerCommand::DefineOperationalModes, true, String::new());
+    }
 
-        // Insert packet size
-        buffer[..4].copy_from_slice(&packet_size.to_be_bytes());
+    fn handle_metadata_refresh(&self, data: &[u8]) {
+        let metadata_requested = *self.metadata_requested.lock().unwrap();
+        let elapsed = (Utc::now() - metadata_requested).num_milliseconds() as f64 / 1000.0;
 
-        // Insert command code
-        buffer[4] = command as u8;
+        self.dispatch_status_message(&format!(
+            "Received {} bytes of metadata in {:.3} seconds",
+            data.len(),
+            elapsed
+        ));
 
-        if data.len() > 0 {
-            buffer[5..].copy_from_slice(data);
+        let data = match self.decompress(self.compress_metadata, data) {
+            Ok(data) => data,
+            Err(e) => {
+                self.dispatch_error_message(&format!("failed to decompress metadata: {}", e));
+                return;
+            }
+        };
+
+        match DataSet::from_xml(&data) {
+            Ok(data_set) => {
+                self.load_metadata(&data_set);
+
+                let callback = self.metadata_parsed_callback.lock().unwrap().clone();
+
+                if let Some(callback) = callback {
+                    callback(&data_set);
+                }
+            }
+            Err(e) => {
+                self.dispatch_error_message(&format!("failed to load measurement metadata: {}", e))
+            }
         }
 
-        if command == ServerCommand::MetadataRefresh {
-            // Track start time of metadata request to calculate round-trip receive time
-            self.metadata_requested = Utc::now();
+        let callback = self.metadata_received_callback.lock().unwrap().clone();
+
+        if let Some(callback) = callback {
+            callback(&data);
+        }
+    }
+
+    // Decompresses a GZip payload when compression is enabled for the payload type. Payloads without a
+    // GZip header are passed through since the publisher may not have honored the requested mode.
+    fn decompress<'a>(
+        &self,
+        compressed: bool,
+        data: &'a [u8],
+    ) -> Result<Cow<'a, [u8]>, Box<dyn Error>> {
+        if compressed && compression::is_gzip(data) {
+            Ok(Cow::Owned(compression::gzip_decompress(data)?))
+        } else {
+            Ok(Cow::Borrowed(data))
+        }
+    }
+
+    fn handle_update_signal_index_cache(self: &Arc<Self>, data: &[u8]) {
+        if data.is_empty() {
+            return;
+        }
+
+        // STTP version 2 or greater defines which of the two cache slots is being updated
+        let (cache_index, data) = if self.version > 1 {
+            (if data[0] > 0 { 1 } else { 0 }, &data[1..])
+        } else {
+            (0, data)
+        };
+
+        let data = match self.decompress(self.compress_signal_index_cache, data) {
+            Ok(data) => data,
+            Err(e) => {
+                self.dispatch_error_message(&format!(
+                    "failed to decompress signal index cache: {}",
+                    e
+                ));
+                return;
+            }
+        };
+
+        let mut signal_index_cache = SignalIndexCache::new();
+
+        match signal_index_cache.decode(&data) {
+            Ok(subscriber_id) => {
+                *self.subscriber_id.lock().unwrap() = subscriber_id;
+            }
+            Err(e) => {
+                self.dispatch_error_message(&format!("failed to parse signal index cache: {}", e));
+                return;
+            }
+        }
+
+        // Register metadata for the measurements defined in the cache
+        for (signal_id, source, id) in signal_index_cache.records() {
+            self.lookup_metadata(signal_id, source, id);
+        }
+
+        let signal_index_cache = Arc::new(signal_index_cache);
+
+        self.signal_index_cache.lock().unwrap()[cache_index] = Arc::clone(&signal_index_cache);
+        self.cache_index.store(cache_index, Ordering::SeqCst);
+
+        // Publisher keeps encoding with the prior cache until the update is confirmed
+        if self.version > 1 {
+            self.send_server_command(ServerCommand::ConfirmUpdateSignalIndexCache);
+        }
+
+        let callback = self.subscription_updated_callback.lock().unwrap().clone();
+
+        if let Some(callback) = callback {
+            callback(&signal_index_cache);
+        }
+    }
+
+    fn handle_buffer_block(self: &Arc<Self>, data: &[u8]) {
+        // Buffer block format: sequence number, active cache index byte for STTP version 2 or
+        // greater, signal index, then the buffer data
+        let header_size = if self.version > 1 { 9 } else { 8 };
+
+        if data.len() < header_size {
+            self.dispatch_error_message("received buffer block that is too small to parse");
+            return;
         }
 
-        let result = self.command_channel_socket.lock();
+        let sequence_number = u32::from_be_bytes(data[..4].try_into().unwrap());
+        let expected_sequence_number = self
+            .buffer_block_expected_sequence_number
+            .load(Ordering::SeqCst);
+
+        // Blocks too far ahead of the expected sequence are not cached, nor confirmed, so that a
+        // lost or bogus sequence number cannot grow the cache for the rest of the session
+        let distance = sequence_number.wrapping_sub(expected_sequence_number);
 
-        if result.is_err() {
+        if (distance as i32) > 0 && distance > MAX_BUFFER_BLOCK_REORDER_WINDOW {
             self.dispatch_error_message(&format!(
-                "failed to send server command - disconnecting: {}",
-                result.err().unwrap()
+                "buffer block sequence number {} is too far ahead of expected sequence number {}; block ignored",
+                sequence_number, expected_sequence_number
             ));
-            self.dispatch_connection_terminated();
+            return;
+        }
+
+        // Confirmations are also sent for buffer blocks already received since a retransmission
+        // means the publisher did not receive the prior confirmation
+        self.send_server_command_with_payload(ServerCommand::ConfirmBufferBlock, &data[..4]);
+
+        let cache_index = if self.version > 1 {
+            (data[4] & 1) as usize
+        } else {
+            self.cache_index.load(Ordering::SeqCst)
+        };
+
+        let signal_index =
+            i32::from_be_bytes(data[header_size - 4..header_size].try_into().unwrap());
+        let signal_id =
+            self.signal_index_cache.lock().unwrap()[cache_index].signal_id(signal_index);
 
+        let buffer_block = BufferBlock {
+            signal_id,
+            buffer: data[header_size..].to_vec(),
+        };
+
+        let mut buffer_block_cache = self.buffer_block_cache.lock().unwrap();
+        let mut expected_sequence_number = self
+            .buffer_block_expected_sequence_number
+            .load(Ordering::SeqCst);
+
+        // Sequence numbers compare with wrapping so that a long running session can roll over
+        if (sequence_number.wrapping_sub(expected_sequence_number) as i32) < 0 {
+            return;
+        }
+
+        // Cache out-of-sequence buffer blocks until missing blocks have been received
+        if sequence_number != expected_sequence_number {
+            buffer_block_cache
+                .entry(sequence_number)
+                .or_insert(buffer_block);
             return;
         }
 
-        let guard = result.unwrap();
+        let mut buffer_blocks = vec![buffer_block];
+        expected_sequence_number = expected_sequence_number.wrapping_add(1);
+
+        while let Some(cached) = buffer_block_cache.remove(&expected_sequence_number) {
+            buffer_blocks.push(cached);
+            expected_sequence_number = expected_sequence_number.wrapping_add(1);
+        }
+
+        self.buffer_block_expected_sequence_number
+            .store(expected_sequence_number, Ordering::SeqCst);
+
+        drop(buffer_block_cache);
+
+        let callback = self.new_buffer_blocks_callback.lock().unwrap().clone();
+
+        if let Some(callback) = callback {
+            callback(buffer_blocks.iter().collect());
+        }
+    }
 
-        if guard.is_none() {
+    fn handle_update_base_times(self: &Arc<Self>, data: &[u8]) {
+        // Base times format: active time index followed by the two base time offsets
+        if data.len() < 20 {
+            self.dispatch_error_message("received base time update that is too small to parse");
             return;
         }
 
-        let mut socket = guard.as_ref().unwrap();
-        let result = socket.write_all(&buffer);
+        let time_index = (u32::from_be_bytes(data[..4].try_into().unwrap()) & 1) as usize;
 
-        if result.is_err() {
-            // Write error, connection may have been closed by peer; terminate connection
+        let base_time_offsets = [
+            i64::from_be_bytes(data[4..12].try_into().unwrap()),
+            i64::from_be_bytes(data[12..20].try_into().unwrap()),
+        ];
+
+        // Offsets are timestamps, which are never negative
+        if base_time_offsets.iter().any(|offset| *offset < 0) {
             self.dispatch_error_message(&format!(
-                "failed to send server command - disconnecting: {}",
-                result.err().unwrap()
+                "received base time update with invalid offsets: {}, {}",
+                base_time_offsets[0], base_time_offsets[1]
             ));
-            self.dispatch_connection_terminated()
+            return;
+        }
+
+        *self.base_time_offsets.lock().unwrap() = base_time_offsets;
+        self.time_index.store(time_index, Ordering::SeqCst);
+
+        // Publisher keeps encoding with the prior offsets until the update is confirmed
+        if self.version > 1 {
+            self.send_server_command(ServerCommand::ConfirmUpdateBaseTimes);
         }
+
+        self.dispatch_status_message(&format!(
+            "Received new base time offset from publisher: {}",
+            Ticks::new(base_time_offsets[time_index ^ 1] as u64)
+        ));
     }
 
-    fn dispatch_status_message(&self, message: &str) {
-        let result = self.status_message_callback.lock();
-        if result.is_ok() {
-            let guard = result.unwrap();
-            if guard.is_some() {
-                guard.as_ref().unwrap()(message);
-            }
+    fn handle_data_start_time(&self, data: &[u8]) {
+        if data.len() < 8 {
+            self.dispatch_error_message("received data start time that is too small to parse");
+            return;
+        }
+
+        let start_time = Ticks::new(u64::from_be_bytes(data[..8].try_into().unwrap()));
+        let callback = self.data_start_time_callback.lock().unwrap().clone();
+
+        if let Some(callback) = callback {
+            callback(start_time);
         }
     }
 
-    fn dispatch_error_message(&self, message: &str) {
-        let result = self.error_message_callback.lock();
-        if result.is_ok() {
-            let guard = result.unwrap();
-            if guard.is_some() {
-                guard.as_ref().unwrap()(message);
-            }
+    fn handle_processing_complete(&self, data: &[u8]) {
+        let message = self.decode_string(data);
+        let callback = self.processing_complete_callback.lock().unwrap().clone();
+
+        if let Some(callback) = callback {
+            callback(&message);
         }
     }
 
-    fn dispatch_connection_terminated(&self) {
-        let result = self.connection_termination_thread_mutex.lock();
+    fn handle_configuration_changed(&self) {
+        self.dispatch_status_message(
+            "Received notification from publisher that configuration has changed",
+        );
 
-        if result.is_ok() {
-            let guard = result.unwrap();
-            if guard.is_some() {
-                return;
-            }
+        let callback = self.configuration_changed_callback.lock().unwrap().clone();
+
+        if let Some(callback) = callback {
+            callback();
+        }
+    }
+
+    fn handle_user_response(&self, index: u8, data: &[u8]) {
+        let waiters = self.user_response_waiters.lock().unwrap().take(index);
+
+        for waiter in waiters {
+            // Waiter may have timed out, so any send failure is ignored
+            let _ = waiter.send(data.to_vec());
+        }
+
+        let callback = self.user_response_callback.lock().unwrap().clone();
+
+        if let Some(callback) = callback {
+            callback(index, data);
+        }
+    }
 
-            // TODO: Implement
-            // self.connection_termination_thread_mutex
-            //     .lock()
-            //     .unwrap()
-            //     .replace(thread::spawn(move || {
-            //         //self._disconnect(false, true);
-            //         self.connection_termination_thread_mutex = Mutex::new(None);
-            //     }));
+    fn handle_notification(self: &Arc<Self>, data: &[u8]) {
+        // Notification format: hash of the notification followed by the message
+        if data.len() < 4 {
+            self.dispatch_error_message("received notification that is too small to parse");
+            return;
         }
+
+        let message = self.decode_string(&data[4..]);
+
+        self.dispatch_status_message(&format!("NOTIFICATION: {}", message));
+
+        let callback = self.notification_received_callback.lock().unwrap().clone();
+
+        if let Some(callback) = callback {
+            callback(&message);
+        }
+
+        // Publisher repeats notification until its receipt is confirmed with the hash
+        self.send_server_command_with_payload(ServerCommand::ConfirmNotification, &data[..4]);
+    }
+
+    fn handle_update_cipher_keys(self: &Arc<Self>, data: &[u8]) {
+        // Move past active cipher index, the cipher index of each data packet selects its key
+        let mut offset = 1;
+
+        let mut read_buffer = || -> Result<Vec<u8>, Box<dyn Error>> {
+            if offset + 4 > data.len() {
+                return Err("not enough buffer provided to parse".into());
+            }
+
+            let length = u32::from_be_bytes(data[offset..offset + 4].try_into()?) as usize;
+            offset += 4;
+
+            if offset + length > data.len() {
+                return Err("not enough buffer provided to parse".into());
+            }
+
+            let buffer = data[offset..offset + length].to_vec();
+            offset += length;
+
+            Ok(buffer)
+        };
+
+        let mut parse_key_ivs = || -> Result<KeyIVs, Box<dyn Error>> {
+            let mut key_ivs: KeyIVs = Default::default();
+
+            for key in [EVEN_KEY, ODD_KEY] {
+                key_ivs[key][KEY_INDEX] = read_buffer()?;
+                key_ivs[key][IV_INDEX] = read_buffer()?;
+            }
+
+            Ok(key_ivs)
+        };
+
+        match parse_key_ivs() {
+            Ok(key_ivs) => {
+                *self.key_ivs.lock().unwrap() = Some(Arc::new(key_ivs));
+
+                // Confirm receipt so publisher can safely transition to the new keys
+                self.send_server_command(ServerCommand::ConfirmUpdateCipherKeys);
+                self.dispatch_status_message(
+                    "received updated cipher keys for data packet decryption",
+                );
+            }
+            Err(e) => {
+                self.dispatch_error_message(&format!("failed to parse cipher key update: {}", e))
+            }
+        }
+    }
+
+    fn handle_data_packet(&self, data: &[u8]) {
+        if data.len() < 5 {
+            self.dispatch_error_message("received data packet that is too small to parse");
+            return;
+        }
+
+        let data_packet_flags = DataPacketFlags::from_bits_truncate(data[0]);
+        let compressed = data_packet_flags.contains(DataPacketFlags::COMPRESSED);
+        let compact = data_packet_flags.contains(DataPacketFlags::COMPACT);
+
+        if !compressed && !compact {
+            self.dispatch_error_message(
+                "Rust implementation of STTP only supports compact or compressed payload data",
+            );
+            return;
+        }
+
+        // Get a local reference to the cipher keys, these can change at any time
+        let key_ivs = self.key_ivs.lock().unwrap().clone();
+        let decrypted: Vec<u8>;
+
+        let data = match key_ivs {
+            Some(key_ivs) => {
+                let cipher_index = if data_packet_flags.contains(DataPacketFlags::CIPHER_INDEX) {
+                    ODD_KEY
+                } else {
+                    EVEN_KEY
+                };
+
+                let key_iv = &key_ivs[cipher_index];
+
+                decrypted =
+                    match cipher::aes_decrypt(&key_iv[KEY_INDEX], &key_iv[IV_INDEX], &data[1..]) {
+                        Ok(decrypted) => decrypted,
+                        Err(e) => {
+                            self.dispatch_error_message(&format!(
+                                "failed to decrypt data packet: {}",
+                                e
+                            ));
+                            return;
+                        }
+                    };
+
+                if decrypted.len() < 4 {
+                    self.dispatch_error_message("received data packet that is too small to parse");
+                    return;
+                }
+
+                &decrypted[..]
+            }
+            None => &data[1..],
+        };
+
+        let count = u32::from_be_bytes(data[..4].try_into().unwrap());
+
+        // Publisher flags which signal index cache a packet was encoded with, so packets sent
+        // while an updated cache is being confirmed still map to their original signal IDs
+        let cache_index =
+            if self.version > 1 && data_packet_flags.contains(DataPacketFlags::CACHEINDEX) {
+                1
+            } else {
+                0
+            };
+
+        let signal_index_cache = Arc::clone(&self.signal_index_cache.lock().unwrap()[cache_index]);
+        let data = &data[4..];
+
+        // Runtime IDs cannot be mapped to signal IDs until the signal index cache arrives
+        if signal_index_cache.count() == 0 {
+            self.dispatch_missing_cache_warning();
+            return;
+        }
+
+        if compressed {
+            match self.parse_tssc_measurements(&signal_index_cache, data) {
+                Ok(measurements) => {
+                    self.total_measurements_received
+                        .fetch_add(measurements.len() as u64, Ordering::SeqCst);
+                    self.dispatch_new_measurements(&measurements);
+                }
+                Err(e) => self
+                    .dispatch_error_message(&format!("failed to parse TSSC measurements: {}", e)),
+            }
+
+            return;
+        }
+
+        // Count is read from the packet, so it cannot be trusted beyond what the payload can hold
+        if count as usize > data.len() / FIXED_LENGTH {
+            self.dispatch_error_message(&format!(
+                "failed to parse compact measurements: {} byte payload cannot hold {} measurements",
+                data.len(),
+                count
+            ));
+            return;
+        }
+
+        let subscription = self.subscription();
+        let base_time_offsets = self
+            .base_time_offsets
+            .lock()
+            .unwrap()
+            .map(|offset| offset as u64);
+        let mut measurements = Vec::with_capacity(count as usize);
+        let mut index = 0;
+
+        for _ in 0..count {
+            let mut measurement = CompactMeasurement::new(
+                Arc::clone(&signal_index_cache),
+                subscription.include_time,
+                subscription.use_millisecond_resolution,
+            );
+
+            measurement.set_base_time_offsets(base_time_offsets);
+
+            match measurement.decode(&data[index..]) {
+                Ok(bytes_decoded) => index += bytes_decoded,
+                Err(e) => {
+                    self.dispatch_error_message(&format!(
+                        "failed to parse compact measurements: {}",
+                        e
+                    ));
+                    return;
+                }
+            }
+
+            measurements.push(measurement);
+        }
+
+        self.total_measurements_received
+            .fetch_add(measurements.len() as u64, Ordering::SeqCst);
+        self.dispatch_new_measurements(&measurements);
+    }
+
+    fn dispatch_missing_cache_warning(&self) {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_secs_f64())
+            .unwrap_or_default();
+
+        let mut last_missing_cache_warning = self.last_missing_cache_warning.lock().unwrap();
+
+        if *last_missing_cache_warning + MISSING_CACHE_WARNING_INTERVAL >= now {
+            return;
+        }
+
+        // Data commonly arrives just before the cache at the start of a subscription, so
+        // warning is only raised once the cache has been missing for a warning interval
+        let warn = *last_missing_cache_warning != 0.0;
+
+        *last_missing_cache_warning = now;
+        drop(last_missing_cache_warning);
+
+        if warn {
+            self.dispatch_status_message(
+                "WARNING: Signal index cache has not arrived. No compact measurements can be parsed.",
+            );
+        }
+    }
+
+    fn parse_tssc_measurements(
+        &self,
+        signal_index_cache: &SignalIndexCache,
+        data: &[u8],
+    ) -> Result<Vec<BasicMeasurement>, Box<dyn Error>> {
+        if data.len() < 3 {
+            return Err("not enough buffer provided to parse TSSC header".into());
+        }
+
+        // Get TSSC header version
+        if data[0] != TSSC_VERSION {
+            return Err(format!("TSSC version not recognized - received: {}", data[0]).into());
+        }
+
+        // Get TSSC sequence number
+        let sequence_number = u16::from_be_bytes([data[1], data[2]]);
+        let mut decoder_guard = signal_index_cache.tssc_decoder.lock().unwrap();
+
+        if sequence_number == 0 {
+            if let Some(decoder) = decoder_guard.as_ref() {
+                if decoder.sequence_number > 0 {
+                    self.dispatch_status_message(&format!(
+                        "TSSC algorithm reset before sequence number: {}",
+                        decoder.sequence_number
+                    ));
+                }
+            }
+
+            *decoder_guard = Some(Decoder::new(signal_index_cache.max_signal_index()));
+            self.tssc_reset_requested.store(false, Ordering::SeqCst);
+        }
+
+        let decoder = match decoder_guard.as_mut() {
+            Some(decoder) if decoder.sequence_number == sequence_number => decoder,
+            decoder => {
+                // Ignore packets until the publisher resets the algorithm, reporting
+                // unexpected sequence numbers only when no reset is pending
+                if !self.tssc_reset_requested.load(Ordering::SeqCst) {
+                    let mut last_oos_report = self.tssc_last_oos_report.lock().unwrap();
+                    let elapsed =
+                        (Utc::now() - *last_oos_report).num_milliseconds() as f64 / 1000.0;
+
+                    if elapsed > TSSC_OUT_OF_SEQUENCE_REPORT_INTERVAL {
+                        let expecting = decoder.map_or(0, |decoder| decoder.sequence_number);
+
+                        self.dispatch_error_message(&format!(
+                            "TSSC is out of sequence. Expecting: {}, Received: {}",
+                            expecting, sequence_number
+                        ));
+
+                        *last_oos_report = Utc::now();
+                    }
+                }
+
+                return Ok(Vec::new());
+            }
+        };
+
+        decoder.set_buffer(&data[3..]);
+
+        let mut measurements = Vec::new();
+
+        loop {
+            let (signal_index, timestamp, flags, value) = match decoder.try_get_measurement() {
+                Ok(Some(decoded)) => decoded,
+                Ok(None) => break,
+                Err(e) => {
+                    // Keep measurements decoded so far and stay in sequence with the publisher
+                    self.dispatch_error_message(&format!(
+                        "failed to decode TSSC measurement: {}",
+                        e
+                    ));
+                    break;
+                }
+            };
+
+            let mut measurement = BasicMeasurement::default();
+
+            measurement.set_signal_id(signal_index_cache.signal_id(signal_index));
+            measurement.set_timestamp(timestamp);
+            measurement.set_flags(flags);
+            measurement.set_value(value as f64);
+
+            measurements.push(measurement);
+        }
+
+        decoder.sequence_number = decoder.sequence_number.wrapping_add(1);
+
+        // Do not increment to 0 on roll-over
+        if decoder.sequence_number == 0 {
+            decoder.sequence_number = 1;
+        }
+
+        Ok(measurements)
+    }
+
+    fn dispatch_new_measurements<M: Measurement>(&self, measurements: &[M]) {
+        let callback = self.new_measurements_callback.lock().unwrap().clone();
+
+        if let Some(callback) = callback {
+            let mut measurement_refs: Vec<&dyn Measurement> =
+                Vec::with_capacity(measurements.len());
+
+            for measurement in measurements {
+                measurement_refs.push(measurement);
+            }
+
+            callback(measurement_refs);
+        }
+    }
+
+    #[allow(deprecated)]
+    fn send_operational_modes(self: &Arc<Self>) {
+        let mut operational_modes = OperationalModes::from_bits_retain(
+            self.version as u32 & OperationalModes::VersionMask.bits(),
+        );
+
+        operational_modes |= OperationalModes::from_bits_retain(self.encoding as u32);
+
+        if self.receive_internal_metadata {
+            operational_modes |= OperationalModes::ReceiveInternalMetadata;
+        }
+
+        if self.receive_external_metadata {
+            operational_modes |= OperationalModes::ReceiveExternalMetadata;
+        }
+
+        // Compression is only requested when this build can decompress the payloads, legacy
+        // compression mode bits are included for publishers that still depend on them
+        if cfg!(feature = "gzip") {
+            if self.compress_metadata {
+                operational_modes |= OperationalModes::CompressMetadata
+                    | OperationalModes::from_bits_retain(CompressionModes::GZip.bits());
+            }
+
+            if self.compress_signal_index_cache {
+                operational_modes |= OperationalModes::CompressSignalIndexCache
+                    | OperationalModes::from_bits_retain(CompressionModes::GZip.bits());
+            }
+        }
+
+        if self.compress_payload_data {
+            operational_modes |= OperationalModes::CompressPayloadData
+                | OperationalModes::from_bits_retain(CompressionModes::TSSC.bits());
+        }
+
+        *self.operational_modes.lock().unwrap() = operational_modes;
+
+        // Publishers only reply to operational modes on failure, so the connection is validated
+        // when the first response to any other command is received
+        self.send_server_command_with_payload(
+            ServerCommand::DefineOperationalModes,
+            &operational_modes.bits().to_be_bytes(),
+        );
+    }
+
+    fn send_server_command(self: &Arc<Self>, command: ServerCommand) {
+        self.send_server_command_with_payload(command, &[]);
+    }
+
+    fn send_server_command_with_message(self: &Arc<Self>, command: ServerCommand, message: &str) {
+        self.send_server_command_with_payload(command, &self.encode_string(message));
+    }
+
+    fn send_server_command_with_payload(self: &Arc<Self>, command: ServerCommand, data: &[u8]) {
+        if !self.connected.load(Ordering::SeqCst) {
+            return;
+        }
+
+        let packet_size = data.len() + 1;
+        let command_buffer_size = packet_size + PAYLOAD_HEADER_SIZE;
+        let mut buffer = vec![0u8; command_buffer_size];
+
+        // Insert packet size
+        buffer[..4].copy_from_slice(&(packet_size as u32).to_be_bytes());
+
+        // Insert command code
+        buffer[4] = command as u8;
+
+        if !data.is_empty() {
+            buffer[5..].copy_from_slice(data);
+        }
+
+        if command == ServerCommand::MetadataRefresh {
+            // Track start time of metadata request to calculate round-trip receive time
+            *self.metadata_requested.lock().unwrap() = Utc::now();
+        }
+
+        let result = match self.command_channel_socket.lock() {
+            Ok(guard) => match guard.as_ref() {
+                Some(command_channel) => command_channel.write_all(&buffer),
+                None => return,
+            },
+            Err(e) => {
+                self.dispatch_error_message(&format!(
+                    "failed to send server command - disconnecting: {}",
+                    e
+                ));
+                self.dispatch_connection_terminated();
+
+                return;
+            }
+        };
+
+        if let Err(e) = result {
+            // Write error, connection may have been closed by peer; terminate connection
+            self.dispatch_error_message(&format!(
+                "failed to send server command - disconnecting: {}",
+                e
+            ));
+            self.dispatch_connection_terminated()
+        }
+    }
+
+    fn dispatch_status_message(&self, message: &str) {
+        let callback = self.status_message_callback.lock().unwrap().clone();
+
+        if let Some(callback) = callback {
+            callback(message);
+        }
+    }
+
+    fn dispatch_error_message(&self, message: &str) {
+        let callback = self.error_message_callback.lock().unwrap().clone();
+
+        if let Some(callback) = callback {
+            callback(message);
+        }
+    }
+
+    fn dispatch_connection_terminated(self: &Arc<Self>) {
+        let mut connection_termination_thread =
+            self.connection_termination_thread_mutex.lock().unwrap();
+
+        if connection_termination_thread.is_some() {
+            return;
+        }
+
+        let ds = Arc::clone(self);
+        let connection_generation = self.connection_generation.load(Ordering::SeqCst);
+
+        // Connection termination is handled on a separate thread since it waits on the reader
+        // threads to complete, one of which may be the thread detecting the termination
+        *connection_termination_thread = Some(thread::spawn(move || {
+            ds._disconnect(false, true, false, Some(connection_generation));
+            *ds.connection_termination_thread_mutex.lock().unwrap() = None;
+        }));
+    }
+}
+
+impl Default for DataSubscriber {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transport::tssc::Encoder;
+    use crate::transport::StateFlags;
+    use std::net::TcpListener;
+    use std::time::Duration;
+
+    fn response_frame(response: ServerResponse, command: ServerCommand, data: &[u8]) -> Vec<u8> {
+        let mut frame = Vec::new();
+        frame.extend_from_slice(&((RESPONSE_HEADER_SIZE + data.len()) as u32).to_be_bytes());
+        frame.push(response as u8);
+        frame.push(command as u8);
+        frame.extend_from_slice(&(data.len() as u32).to_be_bytes());
+        frame.extend_from_slice(data);
+        frame
+    }
+
+    // Reads the next command sent by the subscriber, i.e., the command code followed by its data
+    fn read_command(publisher: &mut TcpStream) -> Vec<u8> {
+        let mut header = [0u8; PAYLOAD_HEADER_SIZE];
+        publisher.read_exact(&mut header).unwrap();
+
+        let mut packet = vec![0u8; u32::from_be_bytes(header) as usize];
+        publisher.read_exact(&mut packet).unwrap();
+        packet
+    }
+
+    // Encodes an uncompressed signal index cache with a record for each runtime ID and signal ID
+    fn encoded_signal_index_cache(records: &[(i32, Uuid)]) -> Vec<u8> {
+        let mut cache = Uuid::nil().to_bytes_le().to_vec();
+        cache.extend_from_slice(&(records.len() as u32).to_be_bytes());
+
+        for (signal_index, signal_id) in records {
+            cache.extend_from_slice(&signal_index.to_be_bytes());
+            cache.extend_from_slice(&signal_id.to_bytes_le());
+            cache.extend_from_slice(&3u32.to_be_bytes());
+            cache.extend_from_slice(b"PPA");
+            cache.extend_from_slice(&(*signal_index as u64).to_be_bytes());
+        }
+
+        let mut encoded = (cache.len() as u32 + 4).to_be_bytes().to_vec();
+        encoded.extend_from_slice(&cache);
+        encoded
+    }
+
+    fn load_signal_index_cache(ds: &DataSubscriber, cache_index: usize, records: &[(i32, Uuid)]) {
+        let mut signal_index_cache = SignalIndexCache::new();
+        signal_index_cache
+            .decode(&encoded_signal_index_cache(records))
+            .unwrap();
+
+        ds.signal_index_cache.lock().unwrap()[cache_index] = Arc::new(signal_index_cache);
+    }
+
+    #[test]
+    fn test_command_channel_reassembles_partial_frames() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        let ds = Arc::new(DataSubscriber::new());
+        let (metadata_sender, metadata_receiver) = mpsc::channel();
+        let (status_sender, status_receiver) = mpsc::channel();
+
+        *ds.metadata_received_callback.lock().unwrap() = Some(Arc::new(move |data: &[u8]| {
+            metadata_sender.send(data.to_vec()).unwrap();
+        }));
+
+        *ds.status_message_callback.lock().unwrap() = Some(Arc::new(move |message: &str| {
+            status_sender.send(message.to_string()).unwrap();
+        }));
+
+        ds.connect("127.0.0.1", port).unwrap();
+
+        let (mut publisher, _) = listener.accept().unwrap();
+
+        // Deliver a metadata response split across several writes, followed by a keep-alive
+        let metadata = vec![0xA5u8; 2 * MAX_PACKET_SIZE];
+        let mut frames = response_frame(
+            ServerResponse::Succeeded,
+            ServerCommand::MetadataRefresh,
+            &metadata,
+        );
+        frames.extend(response_frame(
+            ServerResponse::Succeeded,
+            ServerCommand::Subscribe,
+            b"subscribed",
+        ));
+
+        for chunk in frames.chunks(1000) {
+            publisher.write_all(chunk).unwrap();
+            publisher.flush().unwrap();
+        }
+
+        let received = metadata_receiver
+            .recv_timeout(Duration::from_secs(5))
+            .unwrap();
+
+        assert_eq!(received, metadata);
+
+        let mut messages = Vec::new();
+
+        while let Ok(message) = status_receiver.recv_timeout(Duration::from_secs(5)) {
+            if message.contains("Subscribe") {
+                messages.push(message);
+                break;
+            }
+        }
+
+        assert_eq!(messages.len(), 1);
+        assert!(messages[0].ends_with("\nsubscribed"));
+        assert_eq!(
+            ds.total_command_channel_bytes_received(),
+            frames.len() as u64
+        );
+    }
+
+    #[test]
+    fn test_command_results_track_subscription_state() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        let ds = Arc::new(DataSubscriber::new());
+        let validation = ds.expect_command_result(ServerCommand::DefineOperationalModes);
+
+        ds.connect("127.0.0.1", port).unwrap();
+
+        let (mut publisher, _) = listener.accept().unwrap();
+
+        let subscription = ds.expect_command_result(ServerCommand::Subscribe);
+        ds.subscribe().unwrap();
+
+        publisher
+            .write_all(&response_frame(
+                ServerResponse::Failed,
+                ServerCommand::Subscribe,
+                b"filter invalid",
+            ))
+            .unwrap();
+
+        // First response after the operational modes shows that they were accepted
+        let result = validation.recv_timeout(Duration::from_secs(5)).unwrap();
+
+        assert!(result.succeeded);
+        assert!(ds.is_validated());
+
+        let result = subscription.recv_timeout(Duration::from_secs(5)).unwrap();
+
+        assert_eq!(result.command, ServerCommand::Subscribe);
+        assert_eq!(result.into_result(), Err("filter invalid".to_string()));
+        assert!(!ds.is_subscribed());
+
+        let subscription = ds.expect_command_result(ServerCommand::Subscribe);
+        ds.subscribe().unwrap();
+
+        publisher
+            .write_all(&response_frame(
+                ServerResponse::Succeeded,
+                ServerCommand::Subscribe,
+                b"subscribed",
+            ))
+            .unwrap();
+
+        let result = subscription.recv_timeout(Duration::from_secs(5)).unwrap();
+
+        assert!(result.succeeded);
+        assert!(ds.is_subscribed());
+    }
+
+    #[test]
+    fn test_udp_data_channel_feeds_response_parser() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        let ds = Arc::new(DataSubscriber::new());
+
+        ds.set_subscription(SubscriptionInfo {
+            udp_data_channel: true,
+            data_channel_local_port: 0,
+            data_channel_interface: "127.0.0.1".to_string(),
+            ..SubscriptionInfo::default()
+        });
+
+        ds.connect("127.0.0.1", port).unwrap();
+
+        let (mut publisher, _) = listener.accept().unwrap();
+
+        let subscription = ds.expect_command_result(ServerCommand::Subscribe);
+        ds.subscribe().unwrap();
+
+        // Skip operational modes command and extract connection string of subscribe command
+        let connection_string = loop {
+            let packet = read_command(&mut publisher);
+
+            if packet[0] == ServerCommand::Subscribe as u8 {
+                break ds.decode_string(&packet[6..]);
+            }
+        };
+
+        let data_channel = connection_string.split(";dataChannel={").nth(1).unwrap();
+
+        assert!(data_channel.ends_with(";interface=127.0.0.1}"));
+
+        let local_port: u16 = data_channel
+            .trim_start_matches("localport=")
+            .split(';')
+            .next()
+            .unwrap()
+            .parse()
+            .unwrap();
+
+        assert_ne!(local_port, 0);
+
+        // UDP datagrams carry a response frame without the payload header
+        let frame = response_frame(ServerResponse::Succeeded, ServerCommand::Subscribe, b"");
+        let datagram = &frame[PAYLOAD_HEADER_SIZE..];
+
+        UdpSocket::bind("127.0.0.1:0")
+            .unwrap()
+            .send_to(datagram, ("127.0.0.1", local_port))
+            .unwrap();
+
+        let result = subscription.recv_timeout(Duration::from_secs(5)).unwrap();
+
+        assert!(result.succeeded);
+        assert!(ds.is_subscribed());
+        assert_eq!(
+            ds.total_data_channel_bytes_received(),
+            datagram.len() as u64
+        );
+
+        ds.unsubscribe();
+
+        assert!(ds.data_channel_socket.lock().unwrap().is_none());
+    }
+
+    #[test]
+    #[cfg(feature = "aes")]
+    fn test_encrypted_data_packet_uses_cipher_index_key() {
+        let ds = Arc::new(DataSubscriber::new());
+
+        load_signal_index_cache(&ds, 0, &[(7, Uuid::nil())]);
+
+        ds.set_subscription(SubscriptionInfo {
+            include_time: false,
+            ..SubscriptionInfo::default()
+        });
+
+        let values = Arc::new(Mutex::new(Vec::new()));
+        let received = Arc::clone(&values);
+
+        *ds.new_measurements_callback.lock().unwrap() =
+            Some(Arc::new(move |measurements: Vec<&dyn Measurement>| {
+                let mut received = received.lock().unwrap();
+
+                for measurement in measurements {
+                    received.push(measurement.value());
+                }
+            }));
+
+        let key_ivs = [
+            (vec![0x11u8; 16], vec![0x22u8; 16]),
+            (vec![0x33u8; 32], vec![0x44u8; 16]),
+        ];
+        let mut update = vec![0u8];
+
+        for (key, iv) in key_ivs.iter() {
+            update.extend_from_slice(&(key.len() as u32).to_be_bytes());
+            update.extend_from_slice(key);
+            update.extend_from_slice(&(iv.len() as u32).to_be_bytes());
+            update.extend_from_slice(iv);
+        }
+
+        let frame = response_frame(
+            ServerResponse::UpdateCipherKeys,
+            ServerCommand::Subscribe,
+            &update,
+        );
+
+        ds.process_server_response(&frame[PAYLOAD_HEADER_SIZE..]);
+
+        // One compact measurement: state flags, runtime ID and value
+        let mut payload = 1u32.to_be_bytes().to_vec();
+        payload.push(0);
+        payload.extend_from_slice(&7i32.to_be_bytes());
+        payload.extend_from_slice(&1.5f32.to_be_bytes());
+
+        let (key, iv) = &key_ivs[ODD_KEY];
+        let mut packet = vec![(DataPacketFlags::COMPACT | DataPacketFlags::CIPHER_INDEX).bits()];
+        packet.extend_from_slice(&cipher::aes_encrypt(key, iv, &payload).unwrap());
+
+        let frame = response_frame(
+            ServerResponse::DataPacket,
+            ServerCommand::Subscribe,
+            &packet,
+        );
+
+        ds.process_server_response(&frame[PAYLOAD_HEADER_SIZE..]);
+
+        assert_eq!(*values.lock().unwrap(), [1.5]);
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn test_operational_modes_rejection_fails_validation() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        let ds = Arc::new(DataSubscriber::new());
+        let (error_sender, error_receiver) = mpsc::channel();
+
+        *ds.error_message_callback.lock().unwrap() = Some(Arc::new(move |message: &str| {
+            error_sender.send(message.to_string()).unwrap();
+        }));
+
+        let validation = ds.expect_command_result(ServerCommand::DefineOperationalModes);
+
+        ds.connect("127.0.0.1", port).unwrap();
+
+        let (mut publisher, _) = listener.accept().unwrap();
+
+        // Read the operational modes command sent on connect
+        let command = read_command(&mut publisher);
+
+        assert_eq!(command.len(), 5);
+        assert_eq!(command[0], ServerCommand::DefineOperationalModes as u8);
+
+        let requested = OperationalModes::from_bits_retain(u32::from_be_bytes(
+            command[1..].try_into().unwrap(),
+        ));
+
+        assert_eq!(requested, ds.operational_modes());
+        assert_eq!(operational_modes_version(requested), Defaults::VERSION);
+        assert!(requested.contains(OperationalModes::ReceiveInternalMetadata));
+        assert!(!requested.contains(OperationalModes::ReceiveExternalMetadata));
+        assert_eq!(
+            requested.bits() & OperationalModes::EncodingMask.bits(),
+            OperationalEncoding::UTF8 as u32
+        );
+        assert_eq!(
+            requested.contains(OperationalModes::from_bits_retain(
+                CompressionModes::GZip.bits()
+            )),
+            cfg!(feature = "gzip")
+        );
+        assert!(!ds.is_validated());
+
+        publisher
+            .write_all(&response_frame(
+                ServerResponse::Failed,
+                ServerCommand::DefineOperationalModes,
+                b"STTP protocol version 2 is not supported",
+            ))
+            .unwrap();
+
+        let result = validation.recv_timeout(Duration::from_secs(5)).unwrap();
+
+        assert!(!result.succeeded);
+        assert!(!ds.is_validated());
+
+        let error = error_receiver.recv_timeout(Duration::from_secs(5)).unwrap();
+
+        assert!(error.contains("rejected operational modes for STTP protocol version 2"));
+        assert!(error.contains("disconnecting"));
+
+        // Subscriber closes the connection after the rejection
+        publisher
+            .set_read_timeout(Some(Duration::from_secs(5)))
+            .unwrap();
+
+        let mut buffer = [0u8; 1];
+        assert_eq!(publisher.read(&mut buffer).unwrap(), 0);
+
+        ds.dispose();
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn test_operational_modes_are_validated_by_next_response() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        let mut ds = DataSubscriber::new();
+        ds.compress_metadata = false;
+        ds.compress_signal_index_cache = false;
+
+        let ds = Arc::new(ds);
+        let validation = ds.expect_command_result(ServerCommand::DefineOperationalModes);
+
+        ds.connect("127.0.0.1", port).unwrap();
+
+        let (mut publisher, _) = listener.accept().unwrap();
+        let command = read_command(&mut publisher);
+
+        // GZip is only requested along with metadata or signal index cache compression
+        let requested = OperationalModes::from_bits_retain(u32::from_be_bytes(
+            command[1..].try_into().unwrap(),
+        ));
+
+        assert!(!requested.contains(OperationalModes::from_bits_retain(
+            CompressionModes::GZip.bits()
+        )));
+        assert!(!requested.contains(OperationalModes::CompressMetadata));
+
+        // Publisher does not reply to accepted operational modes
+        assert!(validation.recv_timeout(Duration::from_millis(100)).is_err());
+        assert!(!ds.is_validated());
+
+        let subscription = ds.expect_command_result(ServerCommand::Subscribe);
+        ds.subscribe().unwrap();
+
+        publisher
+            .write_all(&response_frame(
+                ServerResponse::Succeeded,
+                ServerCommand::Subscribe,
+                b"Client subscribed.",
+            ))
+            .unwrap();
+
+        assert!(
+            validation
+                .recv_timeout(Duration::from_secs(5))
+                .unwrap()
+                .succeeded
+        );
+        assert!(ds.is_validated());
+        assert!(
+            subscription
+                .recv_timeout(Duration::from_secs(5))
+                .unwrap()
+                .succeeded
+        );
+        assert!(ds.is_subscribed());
+
+        ds.dispose();
+    }
+
+    #[test]
+    fn test_received_metadata_fills_measurement_registry() {
+        let signal_id = Uuid::parse_str("29a2f3d8-e6b1-4a5a-9a2c-0c4c4d0c7d51").unwrap();
+
+        let metadata = format!(
+            r#"<?xml version="1.0" standalone="yes"?>
+<DataSet>
+  <xs:schema id="DataSet" xmlns="" xmlns:xs="http://www.w3.org/2001/XMLSchema" xmlns:msdata="urn:schemas-microsoft-com:xml-msdata">
+    <xs:element name="DataSet" msdata:IsDataSet="true">
+      <xs:complexType>
+        <xs:choice minOccurs="0" maxOccurs="unbounded">
+          <xs:element name="ActiveMeasurements">
+            <xs:complexType>
+              <xs:sequence>
+                <xs:element name="ID" type="xs:string" minOccurs="0" />
+                <xs:element name="SignalID" msdata:DataType="System.Guid" type="xs:string" minOccurs="0" />
+                <xs:element name="PointTag" type="xs:string" minOccurs="0" />
+                <xs:element name="Device" type="xs:string" minOccurs="0" />
+                <xs:element name="Adder" type="xs:double" minOccurs="0" />
+                <xs:element name="Multiplier" type="xs:double" minOccurs="0" />
+              </xs:sequence>
+            </xs:complexType>
+          </xs:element>
+        </xs:choice>
+      </xs:complexType>
+    </xs:element>
+  </xs:schema>
+  <ActiveMeasurements>
+    <ID>PPA:7</ID>
+    <SignalID>{}</SignalID>
+    <PointTag>SHELBY-PA1:IH</PointTag>
+    <Device>SHELBY</Device>
+    <Adder>1.5</Adder>
+    <Multiplier>2</Multiplier>
+  </ActiveMeasurements>
+</DataSet>"#,
+            signal_id
+        );
+
+        let ds = DataSubscriber::new();
+        ds.handle_metadata_refresh(metadata.as_bytes());
+
+        let mut measurement = BasicMeasurement::default();
+        measurement.set_signal_id(signal_id);
+        measurement.set_value(10.0);
+
+        assert_eq!(ds.adjusted_value(&measurement), 21.5);
+
+        let metadata = ds.metadata(&measurement);
+
+        assert_eq!(metadata.point_tag, "SHELBY-PA1:IH");
+        assert_eq!(metadata.device_acronym, "SHELBY");
+        assert_eq!((metadata.source.as_str(), metadata.id), ("PPA", 7));
+
+        // Unknown measurements are registered with default adjustments
+        measurement.set_signal_id(Uuid::nil());
+
+        assert_eq!(ds.adjusted_value(&measurement), 10.0);
+        assert_eq!(ds.metadata(&measurement).multiplier, 1.0);
+    }
+
+    #[test]
+    fn test_unsupported_version_is_rejected_on_connect() {
+        let mut ds = DataSubscriber::new();
+        ds.version = 4;
+
+        let result = Arc::new(ds).connect("127.0.0.1", 7165);
+
+        assert!(result.unwrap_err().to_string().contains("not supported"));
+    }
+
+    #[test]
+    fn test_listening_accepts_repeated_publisher_connections() {
+        // Reserve an available port for the listener
+        let port = TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+
+        let ds = Arc::new(DataSubscriber::new());
+        let (established_sender, established_receiver) = mpsc::channel();
+
+        *ds.connection_established_callback.lock().unwrap() = Some(Arc::new(move || {
+            established_sender.send(()).unwrap();
+        }));
+
+        let (terminated_sender, terminated_receiver) = mpsc::channel();
+
+        *ds.connection_terminated_callback.lock().unwrap() = Some(Arc::new(move || {
+            terminated_sender.send(()).unwrap();
+        }));
+
+        ds.listen("127.0.0.1", port).unwrap();
+
+        assert!(ds.is_listening());
+        assert!(ds.connect("127.0.0.1", port).is_err());
+
+        for _ in 0..2 {
+            let mut publisher = TcpStream::connect(("127.0.0.1", port)).unwrap();
+            publisher
+                .set_read_timeout(Some(Duration::from_secs(5)))
+                .unwrap();
+
+            established_receiver
+                .recv_timeout(Duration::from_secs(5))
+                .unwrap();
+
+            // Subscriber initiates the STTP session by defining its operational modes
+            assert_eq!(
+                read_command(&mut publisher)[0],
+                ServerCommand::DefineOperationalModes as u8
+            );
+            assert!(ds.is_connected());
+            assert!(ds
+                .get_connection_id()
+                .contains(&publisher.local_addr().unwrap().port().to_string()));
+        }
+
+        ds.disconnect();
+
+        assert!(!ds.is_listening());
+
+        // Replaced and final connections are each reported terminated exactly once
+        for _ in 0..2 {
+            terminated_receiver
+                .recv_timeout(Duration::from_secs(5))
+                .unwrap();
+        }
+
+        assert!(terminated_receiver
+            .recv_timeout(Duration::from_millis(200))
+            .is_err());
+    }
+
+    #[test]
+    fn test_buffer_blocks_are_confirmed_and_delivered_in_sequence() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        let ds = Arc::new(DataSubscriber::new());
+        let (sender, receiver) = mpsc::channel();
+
+        *ds.new_buffer_blocks_callback.lock().unwrap() =
+            Some(Arc::new(move |buffer_blocks: Vec<&BufferBlock>| {
+                let buffers: Vec<Vec<u8>> =
+                    buffer_blocks.iter().map(|b| b.buffer.clone()).collect();
+                sender.send(buffers).unwrap();
+            }));
+
+        ds.connect("127.0.0.1", port).unwrap();
+
+        let (mut publisher, _) = listener.accept().unwrap();
+
+        assert_eq!(
+            read_command(&mut publisher)[0],
+            ServerCommand::DefineOperationalModes as u8
+        );
+
+        // Second block arrives first, then the first block is retransmitted
+        for sequence_number in [1u32, 0, 2, 0] {
+            let mut block = sequence_number.to_be_bytes().to_vec();
+            block.push(0);
+            block.extend_from_slice(&5i32.to_be_bytes());
+            block.push(sequence_number as u8 + 10);
+
+            publisher
+                .write_all(&response_frame(
+                    ServerResponse::BufferBlock,
+                    ServerCommand::Subscribe,
+                    &block,
+                ))
+                .unwrap();
+
+            let confirmation = read_command(&mut publisher);
+
+            assert_eq!(confirmation[0], ServerCommand::ConfirmBufferBlock as u8);
+            assert_eq!(confirmation[1..], sequence_number.to_be_bytes());
+        }
+
+        let timeout = Duration::from_secs(5);
+
+        assert_eq!(receiver.recv_timeout(timeout).unwrap(), [[10], [11]]);
+        assert_eq!(receiver.recv_timeout(timeout).unwrap(), [[12]]);
+        assert!(receiver.recv_timeout(Duration::from_millis(200)).is_err());
+
+        ds.dispose();
+    }
+
+    #[test]
+    fn test_buffer_blocks_beyond_reorder_window_are_not_cached() {
+        let ds = Arc::new(DataSubscriber::new());
+        let (sender, receiver) = mpsc::channel();
+        let (error_sender, error_receiver) = mpsc::channel();
+
+        *ds.new_buffer_blocks_callback.lock().unwrap() =
+            Some(Arc::new(move |buffer_blocks: Vec<&BufferBlock>| {
+                sender.send(buffer_blocks.len()).unwrap();
+            }));
+
+        *ds.error_message_callback.lock().unwrap() = Some(Arc::new(move |message: &str| {
+            error_sender.send(message.to_string()).unwrap();
+        }));
+
+        for sequence_number in [MAX_BUFFER_BLOCK_REORDER_WINDOW + 1, 1, 0] {
+            let mut block = sequence_number.to_be_bytes().to_vec();
+            block.push(0);
+            block.extend_from_slice(&5i32.to_be_bytes());
+
+            let frame = response_frame(
+                ServerResponse::BufferBlock,
+                ServerCommand::Subscribe,
+                &block,
+            );
+
+            ds.process_server_response(&frame[PAYLOAD_HEADER_SIZE..]);
+        }
+
+        assert!(error_receiver.try_recv().unwrap().contains("too far ahead"));
+        assert!(error_receiver.try_recv().is_err());
+
+        // Only the block within the window was cached, then delivered after the missing block
+        assert_eq!(receiver.try_recv().unwrap(), 2);
+        assert!(ds.buffer_block_cache.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_unsolicited_publisher_responses_fire_callbacks() {
+        let ds = Arc::new(DataSubscriber::new());
+        let (sender, receiver) = mpsc::channel();
+
+        let start_time_sender = sender.clone();
+
+        *ds.data_start_time_callback.lock().unwrap() = Some(Arc::new(move |start_time: Ticks| {
+            start_time_sender
+                .send(format!("start time: {}", start_time.value()))
+                .unwrap();
+        }));
+
+        let complete_sender = sender.clone();
+
+        *ds.processing_complete_callback.lock().unwrap() = Some(Arc::new(move |message: &str| {
+            complete_sender
+                .send(format!("complete: {}", message))
+                .unwrap();
+        }));
+
+        *ds.configuration_changed_callback.lock().unwrap() = Some(Arc::new(move || {
+            sender.send("configuration changed".to_string()).unwrap();
+        }));
+
+        let responses = [
+            response_frame(
+                ServerResponse::DataStartTime,
+                ServerCommand::Subscribe,
+                &638_000_000_000_000_000u64.to_be_bytes(),
+            ),
+            response_frame(
+                ServerResponse::ProcessingComplete,
+                ServerCommand::Subscribe,
+                b"End of historical data",
+            ),
+            response_frame(
+                ServerResponse::ConfigurationChanged,
+                ServerCommand::Subscribe,
+                b"",
+            ),
+        ];
+
+        for frame in &responses {
+            ds.process_server_response(&frame[PAYLOAD_HEADER_SIZE..]);
+        }
+
+        assert_eq!(
+            receiver.try_iter().collect::<Vec<String>>(),
+            [
+                "start time: 638000000000000000",
+                "complete: End of historical data",
+                "configuration changed"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_user_command_receives_correlated_user_response() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        let ds = Arc::new(DataSubscriber::new());
+        let (sender, receiver) = mpsc::channel();
+
+        *ds.user_response_callback.lock().unwrap() =
+            Some(Arc::new(move |index: u8, data: &[u8]| {
+                sender.send((index, data.to_vec())).unwrap();
+            }));
+
+        assert!(ds.send_user_command(16, b"").is_err());
+        assert!(ds.send_user_command(3, b"").is_err());
+
+        ds.connect("127.0.0.1", port).unwrap();
+
+        let (mut publisher, _) = listener.accept().unwrap();
+
+        let publisher_thread = thread::spawn(move || {
+            // Skip operational modes command sent on connect
+            read_command(&mut publisher);
+
+            let command = read_command(&mut publisher);
+
+            assert_eq!(command[0], ServerCommand::UserCommand03 as u8);
+            assert_eq!(&command[1..], b"PMU 12 reconfigure");
+
+            publisher
+                .write_all(&response_frame(
+                    ServerResponse::UserResponse03,
+                    ServerCommand::UserCommand03,
+                    b"accepted",
+                ))
+                .unwrap();
+
+            publisher
+        });
+
+        let response = ds
+            .request_user_response(3, b"PMU 12 reconfigure", Duration::from_secs(5))
+            .unwrap();
+
+        assert_eq!(response, b"accepted");
+        assert_eq!(
+            receiver.recv_timeout(Duration::from_secs(5)).unwrap(),
+            (3, b"accepted".to_vec())
+        );
+
+        let _publisher = publisher_thread.join().unwrap();
+
+        assert!(ds
+            .request_user_response(4, b"", Duration::from_millis(50))
+            .is_err());
+
+        ds.dispose();
+    }
+
+    #[test]
+    fn test_late_user_response_is_not_delivered_to_next_request() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        let ds = Arc::new(DataSubscriber::new());
+
+        ds.connect("127.0.0.1", port).unwrap();
+
+        let (mut publisher, _) = listener.accept().unwrap();
+        let (timed_out_sender, timed_out_receiver) = mpsc::channel();
+
+        let publisher_thread = thread::spawn(move || {
+            // Skip operational modes command sent on connect
+            read_command(&mut publisher);
+
+            assert_eq!(&read_command(&mut publisher)[1..], b"first");
+
+            // First request is only answered after it timed out and a second request was sent
+            timed_out_receiver.recv().unwrap();
+
+            assert_eq!(&read_command(&mut publisher)[1..], b"second");
+
+            for reply in [&b"late"[..], b"current"] {
+                publisher
+                    .write_all(&response_frame(
+                        ServerResponse::UserResponse05,
+                        ServerCommand::UserCommand05,
+                        reply,
+                    ))
+                    .unwrap();
+            }
+
+            publisher
+        });
+
+        assert!(ds
+            .request_user_response(5, b"first", Duration::from_millis(100))
+            .is_err());
+
+        timed_out_sender.send(()).unwrap();
+
+        let response = ds
+            .request_user_response(5, b"second", Duration::from_secs(5))
+            .unwrap();
+
+        assert_eq!(response, b"current");
+
+        let _publisher = publisher_thread.join().unwrap();
+
+        ds.dispose();
+    }
+
+    #[test]
+    fn test_rejected_user_request_keeps_other_waiters() {
+        let ds = Arc::new(DataSubscriber::new());
+        let _receiver = ds.expect_user_response(3);
+
+        assert!(ds
+            .request_user_response(3, b"status", Duration::from_secs(1))
+            .is_err());
+        assert_eq!(
+            ds.user_response_waiters.lock().unwrap().waiters[&3].len(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_notification_is_received_and_confirmed() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        let ds = Arc::new(DataSubscriber::new());
+        let (sender, receiver) = mpsc::channel();
+
+        *ds.notification_received_callback.lock().unwrap() =
+            Some(Arc::new(move |message: &str| {
+                sender.send(message.to_string()).unwrap();
+            }));
+
+        ds.connect("127.0.0.1", port).unwrap();
+
+        let (mut publisher, _) = listener.accept().unwrap();
+
+        let mut notification = 0x1234_5678i32.to_be_bytes().to_vec();
+        notification.extend_from_slice("Source configuration reloaded".as_bytes());
+
+        publisher
+            .write_all(&response_frame(
+                ServerResponse::Notify,
+                ServerCommand::Subscribe,
+                &notification,
+            ))
+            .unwrap();
+
+        assert_eq!(
+            receiver.recv_timeout(Duration::from_secs(5)).unwrap(),
+            "Source configuration reloaded"
+        );
+
+        // Skip operational modes command sent on connect
+        read_command(&mut publisher);
+
+        let confirmation = read_command(&mut publisher);
+
+        assert_eq!(confirmation[0], ServerCommand::ConfirmNotification as u8);
+        assert_eq!(confirmation[1..], notification[..4]);
+
+        ds.dispose();
+    }
+
+    #[test]
+    fn test_data_packets_select_flagged_signal_index_cache() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        let mut ds = DataSubscriber::new();
+        ds.compress_signal_index_cache = false;
+
+        ds.set_subscription(SubscriptionInfo {
+            include_time: false,
+            ..SubscriptionInfo::default()
+        });
+
+        let ds = Arc::new(ds);
+        let (sender, receiver) = mpsc::channel();
+
+        *ds.new_measurements_callback.lock().unwrap() =
+            Some(Arc::new(move |measurements: Vec<&dyn Measurement>| {
+                sender.send(measurements[0].signal_id()).unwrap();
+            }));
+
+        ds.connect("127.0.0.1", port).unwrap();
+
+        let (mut publisher, _) = listener.accept().unwrap();
+
+        assert_eq!(
+            read_command(&mut publisher)[0],
+            ServerCommand::DefineOperationalModes as u8
+        );
+
+        // Runtime ID 7 maps to a different signal in each cache slot, the second slot is updated
+        let prior_signal_id = Uuid::parse_str("29a2f3d8-e6b1-4a5a-9a2c-0c4c4d0c7d51").unwrap();
+        let signal_id = Uuid::parse_str("5c5f4b6e-0d2e-4f1a-8d5b-3a6f2e9b1c47").unwrap();
+
+        load_signal_index_cache(&ds, 0, &[(7, prior_signal_id)]);
+
+        let mut update = vec![1u8];
+        update.extend_from_slice(&encoded_signal_index_cache(&[(7, signal_id)]));
+
+        publisher
+            .write_all(&response_frame(
+                ServerResponse::UpdateSignalIndexCache,
+                ServerCommand::Subscribe,
+                &update,
+            ))
+            .unwrap();
+
+        assert_eq!(
+            read_command(&mut publisher)[0],
+            ServerCommand::ConfirmUpdateSignalIndexCache as u8
+        );
+
+        // Compact measurement for runtime ID 7 encoded with each cache slot
+        for flags in [
+            DataPacketFlags::COMPACT | DataPacketFlags::CACHEINDEX,
+            DataPacketFlags::COMPACT,
+        ] {
+            let mut packet = vec![flags.bits()];
+            packet.extend_from_slice(&1u32.to_be_bytes());
+            packet.push(0);
+            packet.extend_from_slice(&7i32.to_be_bytes());
+            packet.extend_from_slice(&1.5f32.to_be_bytes());
+
+            publisher
+                .write_all(&response_frame(
+                    ServerResponse::DataPacket,
+                    ServerCommand::Subscribe,
+                    &packet,
+                ))
+                .unwrap();
+        }
+
+        let timeout = Duration::from_secs(5);
+
+        assert_eq!(receiver.recv_timeout(timeout).unwrap(), signal_id);
+        assert_eq!(receiver.recv_timeout(timeout).unwrap(), prior_signal_id);
+
+        ds.dispose();
+    }
+
+    #[test]
+    fn test_compact_timestamps_are_offset_from_base_times() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        let ds = Arc::new(DataSubscriber::new());
+
+        ds.set_subscription(SubscriptionInfo {
+            include_time: true,
+            use_millisecond_resolution: true,
+            ..SubscriptionInfo::default()
+        });
+
+        let (sender, receiver) = mpsc::channel();
+
+        *ds.new_measurements_callback.lock().unwrap() =
+            Some(Arc::new(move |measurements: Vec<&dyn Measurement>| {
+                sender.send(measurements[0].timestamp()).unwrap();
+            }));
+
+        ds.connect("127.0.0.1", port).unwrap();
+
+        let (mut publisher, _) = listener.accept().unwrap();
+
+        assert_eq!(
+            read_command(&mut publisher)[0],
+            ServerCommand::DefineOperationalModes as u8
+        );
+
+        load_signal_index_cache(&ds, 0, &[(7, Uuid::nil())]);
+
+        let base_time_offsets = [638_000_000_000_000_000u64, 638_000_000_600_000_000];
+        let mut update = 0u32.to_be_bytes().to_vec();
+        update.extend_from_slice(&base_time_offsets[0].to_be_bytes());
+        update.extend_from_slice(&base_time_offsets[1].to_be_bytes());
+
+        publisher
+            .write_all(&response_frame(
+                ServerResponse::UpdateBaseTimes,
+                ServerCommand::Subscribe,
+                &update,
+            ))
+            .unwrap();
+
+        assert_eq!(
+            read_command(&mut publisher)[0],
+            ServerCommand::ConfirmUpdateBaseTimes as u8
+        );
+
+        // Compact measurement with a 2-byte millisecond offset from the second base time
+        let mut packet = vec![DataPacketFlags::COMPACT.bits()];
+        packet.extend_from_slice(&1u32.to_be_bytes());
+        packet.push(0b1100_0000);
+        packet.extend_from_slice(&7i32.to_be_bytes());
+        packet.extend_from_slice(&1.5f32.to_be_bytes());
+        packet.extend_from_slice(&250u16.to_be_bytes());
+
+        publisher
+            .write_all(&response_frame(
+                ServerResponse::DataPacket,
+                ServerCommand::Subscribe,
+                &packet,
+            ))
+            .unwrap();
+
+        assert_eq!(
+            receiver.recv_timeout(Duration::from_secs(5)).unwrap(),
+            Ticks::new(base_time_offsets[1] + 250 * Ticks::PER_MILLISECOND)
+        );
+
+        ds.dispose();
+    }
+
+    #[test]
+    fn test_negative_base_time_offsets_are_rejected() {
+        let ds = Arc::new(DataSubscriber::new());
+        let (sender, receiver) = mpsc::channel();
+
+        *ds.error_message_callback.lock().unwrap() = Some(Arc::new(move |message: &str| {
+            sender.send(message.to_string()).unwrap();
+        }));
+
+        let mut update = 1u32.to_be_bytes().to_vec();
+        update.extend_from_slice(&638_000_000_000_000_000i64.to_be_bytes());
+        update.extend_from_slice(&(-1i64).to_be_bytes());
+
+        let frame = response_frame(
+            ServerResponse::UpdateBaseTimes,
+            ServerCommand::Subscribe,
+            &update,
+        );
+
+        ds.process_server_response(&frame[PAYLOAD_HEADER_SIZE..]);
+
+        assert!(receiver.try_recv().unwrap().contains("invalid offsets"));
+        assert_eq!(*ds.base_time_offsets.lock().unwrap(), [0, 0]);
+        assert_eq!(ds.time_index.load(Ordering::SeqCst), 0);
+    }
+
+    #[test]
+    fn test_data_packets_without_signal_index_cache_are_skipped() {
+        let ds = Arc::new(DataSubscriber::new());
+
+        ds.set_subscription(SubscriptionInfo {
+            include_time: false,
+            ..SubscriptionInfo::default()
+        });
+
+        let (sender, receiver) = mpsc::channel();
+
+        *ds.status_message_callback.lock().unwrap() = Some(Arc::new(move |message: &str| {
+            sender.send(message.to_string()).unwrap();
+        }));
+
+        // Two compact measurements for runtime IDs 7 and 8
+        let mut packet = vec![DataPacketFlags::COMPACT.bits()];
+        packet.extend_from_slice(&2u32.to_be_bytes());
+
+        for signal_index in [7i32, 8] {
+            packet.push(0);
+            packet.extend_from_slice(&signal_index.to_be_bytes());
+            packet.extend_from_slice(&1.5f32.to_be_bytes());
+        }
+
+        let frame = response_frame(
+            ServerResponse::DataPacket,
+            ServerCommand::Subscribe,
+            &packet,
+        );
+
+        // First missing cache only starts the warning interval
+        ds.process_server_response(&frame[PAYLOAD_HEADER_SIZE..]);
+
+        assert!(receiver.try_recv().is_err());
+
+        // Warning is raised once the warning interval has elapsed, then suppressed again
+        *ds.last_missing_cache_warning.lock().unwrap() -= MISSING_CACHE_WARNING_INTERVAL + 1.0;
+
+        ds.process_server_response(&frame[PAYLOAD_HEADER_SIZE..]);
+        ds.process_server_response(&frame[PAYLOAD_HEADER_SIZE..]);
+
+        let warnings: Vec<String> = receiver.try_iter().collect();
+
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].contains("Signal index cache has not arrived"));
+        assert_eq!(ds.total_measurements_received(), 0);
+
+        load_signal_index_cache(&ds, 0, &[(7, Uuid::nil()), (8, Uuid::nil())]);
+
+        ds.process_server_response(&frame[PAYLOAD_HEADER_SIZE..]);
+
+        assert_eq!(ds.total_measurements_received(), 2);
+    }
+
+    #[test]
+    fn test_tssc_decode_error_keeps_partial_batch_and_sequence() {
+        let ds = Arc::new(DataSubscriber::new());
+        let (sender, receiver) = mpsc::channel();
+
+        *ds.error_message_callback.lock().unwrap() = Some(Arc::new(move |message: &str| {
+            sender.send(message.to_string()).unwrap();
+        }));
+
+        let mut signal_index_cache = SignalIndexCache::new();
+        signal_index_cache
+            .decode(&encoded_signal_index_cache(&[
+                (0, Uuid::nil()),
+                (1, Uuid::nil()),
+            ]))
+            .unwrap();
+
+        let mut encoder = Encoder::new();
+        encoder.set_buffer(MAX_PACKET_SIZE);
+
+        for signal_index in [0, 1, 0, 1] {
+            assert!(encoder.try_add_measurement(
+                signal_index,
+                Ticks::new(Ticks::PER_SECOND),
+                StateFlags::NORMAL,
+                1.5,
+            ));
+        }
+
+        let block = encoder.finish_block();
+
+        // Truncated packet fails to decode its final measurements
+        let mut packet = vec![TSSC_VERSION, 0, 0];
+        packet.extend_from_slice(&block[..block.len() - 2]);
+
+        let measurements = ds
+            .parse_tssc_measurements(&signal_index_cache, &packet)
+            .unwrap();
+
+        assert!(!measurements.is_empty() && measurements.len() < 4);
+        assert!(receiver
+            .try_recv()
+            .unwrap()
+            .contains("failed to decode TSSC measurement"));
+
+        // Decoder still expects the publisher's next sequence number
+        let decoder = signal_index_cache.tssc_decoder.lock().unwrap();
+        assert_eq!(decoder.as_ref().unwrap().sequence_number, 1);
+    }
+
+    #[test]
+    fn test_data_packet_count_beyond_payload_is_rejected() {
+        let ds = Arc::new(DataSubscriber::new());
+        let (sender, receiver) = mpsc::channel();
+
+        *ds.error_message_callback.lock().unwrap() = Some(Arc::new(move |message: &str| {
+            sender.send(message.to_string()).unwrap();
+        }));
+
+        ds.set_subscription(SubscriptionInfo {
+            include_time: false,
+            ..SubscriptionInfo::default()
+        });
+
+        load_signal_index_cache(&ds, 0, &[(7, Uuid::nil())]);
+
+        // One compact measurement claiming a count near the maximum
+        let mut packet = vec![DataPacketFlags::COMPACT.bits()];
+        packet.extend_from_slice(&(u32::MAX - 1).to_be_bytes());
+        packet.push(0);
+        packet.extend_from_slice(&7i32.to_be_bytes());
+        packet.extend_from_slice(&1.5f32.to_be_bytes());
+
+        let frame = response_frame(
+            ServerResponse::DataPacket,
+            ServerCommand::Subscribe,
+            &packet,
+        );
+
+        ds.process_server_response(&frame[PAYLOAD_HEADER_SIZE..]);
+
+        assert!(receiver
+            .try_recv()
+            .unwrap()
+            .contains("cannot hold 4294967294 measurements"));
+        assert_eq!(ds.total_measurements_received(), 0);
+    }
+
+    #[test]
+    fn test_terminated_connection_is_reestablished_until_disposed() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        let ds = Arc::new(DataSubscriber::new());
+        let (terminated_sender, terminated_receiver) = mpsc::channel();
+
+        ds.connector.lock().unwrap().retry_interval = 50;
+
+        *ds.connection_terminated_callback.lock().unwrap() = Some(Arc::new(move || {
+            terminated_sender.send(()).unwrap();
+        }));
+
+        ds.connect("127.0.0.1", port).unwrap();
+
+        // Publisher closing its socket terminates the connection and starts auto-reconnect
+        drop(listener.accept().unwrap());
+
+        terminated_receiver
+            .recv_timeout(Duration::from_secs(5))
+            .unwrap();
+
+        let (mut publisher, _) = listener.accept().unwrap();
+        publisher
+            .set_read_timeout(Some(Duration::from_secs(5)))
+            .unwrap();
+
+        // Disposal closes the command channel and waits for its threads to complete
+        ds.dispose();
+
+        assert!(!ds.is_connected());
+        assert!(ds.command_channel_response_thread.lock().unwrap().is_none());
+
+        let mut buffer = Vec::new();
+        publisher.read_to_end(&mut buffer).unwrap();
+
+        terminated_receiver
+            .recv_timeout(Duration::from_secs(5))
+            .unwrap();
+
+        // Termination is only reported once for each connection
+        assert!(terminated_receiver
+            .recv_timeout(Duration::from_millis(200))
+            .is_err());
     }
 }
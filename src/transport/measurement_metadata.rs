@@ -0,0 +1,322 @@
+//******************************************************************************************************
+//  measurement_metadata.rs - Gbtc
+//
+//  Copyright © 2023, Grid Protection Alliance.  All Rights Reserved.
+//
+//  Licensed to the Grid Protection Alliance (GPA) under one or more contributor license agreements. See
+//  the NOTICE file distributed with this work for additional information regarding copyright ownership.
+//  The GPA licenses this file to you under the MIT License (MIT), the "License"; you may not use this
+//  file except in compliance with the License. You may obtain a copy of the License at:
+//
+//      http://opensource.org/licenses/MIT
+//
+//  Unless agreed to in writing, the subject software distributed under the License is distributed on an
+//  "AS-IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied. Refer to the
+//  License for the specific language governing permissions and limitations.
+//
+//  Code Modification History:
+//  ----------------------------------------------------------------------------------------------------
+//  04/01/2023 - J. Ritchie Carroll
+//       Generated original version of source code.
+//
+//******************************************************************************************************
+
+use crate::data::{DataRow, DataSet, DataTable, DataValue};
+use chrono::{DateTime, Utc};
+use rust_decimal::prelude::ToPrimitive;
+use std::collections::HashMap;
+use uuid::Uuid;
+
+/// Represents the ancillary information associated with a `Measurement`.
+/// Metadata gets cached in a registry associated with a `DataSubscriber`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MeasurementMetadata {
+    /// Defines measurement's globally unique identifier.
+    pub signal_id: Uuid,
+
+    /// Defines the additive value modifier. Allows for linear value adjustment. Defaults to zero.
+    pub adder: f64,
+
+    /// Defines the multiplicative value modifier. Allows for linear value adjustment. Defaults to one.
+    pub multiplier: f64,
+
+    /// Defines identification number used in human-readable measurement key.
+    pub id: u64,
+
+    /// Defines source used in human-readable measurement key.
+    pub source: String,
+
+    /// Defines the acronym of the device associated with the measurement.
+    pub device_acronym: String,
+
+    /// Defines the signal type acronym of the measurement, e.g., FREQ.
+    pub signal_type: String,
+
+    /// Defines reference info about a signal based on measurement's original source.
+    pub signal_reference: String,
+
+    /// Defines the measurement's point tag, i.e., its unique string identifier.
+    pub point_tag: String,
+
+    /// Defines a detailed description of the measurement.
+    pub description: String,
+
+    /// Defines the last update time for the measurement metadata.
+    pub updated_on: DateTime<Utc>,
+}
+
+impl MeasurementMetadata {
+    /// Creates a new `MeasurementMetadata` record for the specified signal ID and measurement key.
+    pub fn new(signal_id: Uuid, source: &str, id: u64) -> MeasurementMetadata {
+        MeasurementMetadata {
+            signal_id,
+            source: source.to_string(),
+            id,
+            ..MeasurementMetadata::default()
+        }
+    }
+
+    /// Gets the specified value with the linear adjustments of the measurement's
+    /// adder and multiplier applied.
+    pub fn adjusted_value(&self, value: f64) -> f64 {
+        value * self.multiplier + self.adder
+    }
+}
+
+impl Default for MeasurementMetadata {
+    fn default() -> Self {
+        MeasurementMetadata {
+            signal_id: Uuid::nil(),
+            adder: 0.0,
+            multiplier: 1.0,
+            id: 0,
+            source: String::new(),
+            device_acronym: String::new(),
+            signal_type: String::new(),
+            signal_reference: String::new(),
+            point_tag: String::new(),
+            description: String::new(),
+            updated_on: DateTime::default(),
+        }
+    }
+}
+
+// Metadata tables that define measurement records, later tables update fields of earlier ones
+const MEASUREMENT_TABLE_NAMES: [&str; 2] = ["MeasurementDetail", "ActiveMeasurements"];
+
+// Resolved column indexes for a table that defines measurement records
+struct MeasurementColumns {
+    signal_id: usize,
+    id: Option<usize>,
+    point_tag: Option<usize>,
+    signal_reference: Option<usize>,
+    device_acronym: Option<usize>,
+    signal_type: Option<usize>,
+    adder: Option<usize>,
+    multiplier: Option<usize>,
+    description: Option<usize>,
+    updated_on: Option<usize>,
+}
+
+impl MeasurementColumns {
+    fn new(table: &DataTable) -> Option<MeasurementColumns> {
+        let column = |names: &[&str]| names.iter().find_map(|name| table.column_index(name));
+
+        Some(MeasurementColumns {
+            signal_id: table.column_index("SignalID")?,
+            id: column(&["ID"]),
+            point_tag: column(&["PointTag"]),
+            signal_reference: column(&["SignalReference"]),
+            device_acronym: column(&["DeviceAcronym", "Device"]),
+            signal_type: column(&["SignalAcronym", "SignalType"]),
+            adder: column(&["Adder"]),
+            multiplier: column(&["Multiplier"]),
+            description: column(&["Description"]),
+            updated_on: column(&["UpdatedOn"]),
+        })
+    }
+}
+
+// Updates the registry with the measurement records defined in the data set, returning
+// the number of records loaded
+pub(crate) fn load_measurement_metadata(
+    registry: &mut HashMap<Uuid, MeasurementMetadata>,
+    data_set: &DataSet,
+) -> usize {
+    let mut count = 0;
+
+    for table_name in MEASUREMENT_TABLE_NAMES {
+        let table = match data_set.table(table_name) {
+            Some(table) => table,
+            None => continue,
+        };
+
+        let columns = match MeasurementColumns::new(table) {
+            Some(columns) => columns,
+            None => continue,
+        };
+
+        for row in table.rows() {
+            let signal_id = match row.value(columns.signal_id) {
+                Some(DataValue::Guid(signal_id)) => *signal_id,
+                Some(DataValue::String(signal_id)) => match Uuid::parse_str(signal_id.trim()) {
+                    Ok(signal_id) => signal_id,
+                    Err(_) => continue,
+                },
+                _ => continue,
+            };
+
+            let metadata = registry
+                .entry(signal_id)
+                .or_insert_with(|| MeasurementMetadata::new(signal_id, "", 0));
+
+            update_measurement_metadata(metadata, row, &columns);
+            count += 1;
+        }
+    }
+
+    count
+}
+
+fn update_measurement_metadata(
+    metadata: &mut MeasurementMetadata,
+    row: &DataRow,
+    columns: &MeasurementColumns,
+) {
+    let value = |column: Option<usize>| column.and_then(|column| row.value(column));
+
+    let string = |column: Option<usize>, field: &mut String| {
+        if let Some(DataValue::String(value)) = value(column) {
+            *field = value.clone();
+        }
+    };
+
+    let float = |column: Option<usize>, field: &mut f64| {
+        let number = match value(column) {
+            Some(DataValue::Double(value)) => Some(*value),
+            Some(DataValue::Single(value)) => Some(*value as f64),
+            Some(DataValue::Decimal(value)) => value.to_f64(),
+            _ => None,
+        };
+
+        if let Some(number) = number {
+            *field = number;
+        }
+    };
+
+    // Measurement keys are formatted as "Source:ID"
+    if let Some(DataValue::String(key)) = value(columns.id) {
+        if let Some((source, id)) = key.rsplit_once(':') {
+            if let Ok(id) = id.trim().parse() {
+                metadata.source = source.trim().to_string();
+                metadata.id = id;
+            }
+        }
+    }
+
+    string(columns.point_tag, &mut metadata.point_tag);
+    string(columns.signal_reference, &mut metadata.signal_reference);
+    string(columns.device_acronym, &mut metadata.device_acronym);
+    string(columns.signal_type, &mut metadata.signal_type);
+    string(columns.description, &mut metadata.description);
+    float(columns.adder, &mut metadata.adder);
+    float(columns.multiplier, &mut metadata.multiplier);
+
+    if let Some(DataValue::DateTime(updated_on)) = value(columns.updated_on) {
+        metadata.updated_on = *updated_on;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::{DataColumn, DataType};
+
+    fn add_table(
+        data_set: &mut DataSet,
+        name: &str,
+        columns: &[(&str, DataType)],
+        rows: &[Vec<Option<DataValue>>],
+    ) {
+        let mut table = DataTable::new(name);
+
+        for (column_name, data_type) in columns {
+            table
+                .add_column(DataColumn::new(column_name, *data_type, ""))
+                .unwrap();
+        }
+
+        for values in rows {
+            let mut row = table.create_row();
+
+            for (index, value) in values.iter().enumerate() {
+                row.set_value(index, value.clone()).unwrap();
+            }
+
+            table.add_row(row).unwrap();
+        }
+
+        data_set.add_table(table);
+    }
+
+    #[test]
+    fn test_load_measurement_metadata_merges_tables() {
+        let signal_id = Uuid::parse_str("6f8e8b61-7c31-4fd5-8f5e-6b0c1f4a0e2d").unwrap();
+        let mut data_set = DataSet::new();
+
+        add_table(
+            &mut data_set,
+            "MeasurementDetail",
+            &[
+                ("DeviceAcronym", DataType::String),
+                ("ID", DataType::String),
+                ("SignalID", DataType::Guid),
+                ("PointTag", DataType::String),
+                ("SignalReference", DataType::String),
+                ("SignalAcronym", DataType::String),
+                ("Description", DataType::String),
+            ],
+            &[vec![
+                Some(DataValue::String("SHELBY".to_string())),
+                Some(DataValue::String("PPA:12".to_string())),
+                Some(DataValue::Guid(signal_id)),
+                Some(DataValue::String("SHELBY-PA1:VH".to_string())),
+                Some(DataValue::String("SHELBY-PA1".to_string())),
+                Some(DataValue::String("VPHM".to_string())),
+                Some(DataValue::String("Shelby bus 1 voltage".to_string())),
+            ]],
+        );
+
+        add_table(
+            &mut data_set,
+            "ActiveMeasurements",
+            &[
+                ("SignalID", DataType::String),
+                ("Adder", DataType::Double),
+                ("Multiplier", DataType::Decimal),
+                ("Description", DataType::String),
+            ],
+            &[vec![
+                Some(DataValue::String(signal_id.to_string())),
+                Some(DataValue::Double(-10.0)),
+                Some(DataValue::parse(DataType::Decimal, "0.5").unwrap()),
+                None,
+            ]],
+        );
+
+        let mut registry = HashMap::new();
+
+        assert_eq!(load_measurement_metadata(&mut registry, &data_set), 2);
+
+        let metadata = &registry[&signal_id];
+
+        assert_eq!(metadata.source, "PPA");
+        assert_eq!(metadata.id, 12);
+        assert_eq!(metadata.device_acronym, "SHELBY");
+        assert_eq!(metadata.point_tag, "SHELBY-PA1:VH");
+        assert_eq!(metadata.signal_reference, "SHELBY-PA1");
+        assert_eq!(metadata.signal_type, "VPHM");
+        assert_eq!(metadata.description, "Shelby bus 1 voltage");
+        assert_eq!(metadata.adjusted_value(120.0), 50.0);
+    }
+}
@@ -21,23 +21,26 @@
 //
 //******************************************************************************************************
 
+use crate::transport::tssc::Decoder;
 use std::collections::HashMap;
 use std::collections::HashSet;
 use std::error::Error;
 use std::str;
+use std::sync::Mutex;
 use uuid::Uuid;
 
 /// Represents a mapping of 32-bit runtime IDs to 128-bit globally unique measurement IDs. The class
 /// additionally provides reverse lookup and an extra mapping to human-readable measurement keys.
-#[derive(Default, Debug, Clone)]
+#[derive(Default, Debug)]
 pub struct SignalIndexCache {
     reference: HashMap<i32, u32>,
     signal_id_list: Vec<Uuid>,
     source_list: Vec<String>,
     id_list: Vec<u64>,
     signal_id_cache: HashMap<Uuid, i32>,
+    max_signal_index: u32,
     // binary_length: u32,
-    // tssc_decoder: Option<Decoder>,
+    pub(crate) tssc_decoder: Mutex<Option<Decoder>>,
 }
 
 impl SignalIndexCache {
@@ -49,12 +52,13 @@ impl SignalIndexCache {
             source_list: Vec::new(),
             id_list: Vec::new(),
             signal_id_cache: HashMap::new(),
+            max_signal_index: 0,
             // binary_length: 0,
-            // tssc_decoder: None,
+            tssc_decoder: Mutex::new(None),
         }
     }
 
-    fn add_record(
+    pub(crate) fn add_record(
         &mut self,
         //ds: &Arc<datasubscriber::DataSubscriber>,
         signal_index: i32,
@@ -70,9 +74,9 @@ impl SignalIndexCache {
         self.id_list.push(id);
         self.signal_id_cache.insert(signal_id, signal_index);
 
-        // Lookup measurement metadata, registering it if not defined already
-        // Replace with actual metadata lookup function from DataSubscriber
-        // let metadata = ds.lookup_metadata(signal_id, source, id);
+        if signal_index > 0 && signal_index as u32 > self.max_signal_index {
+            self.max_signal_index = signal_index as u32;
+        }
 
         // Char size here helps provide a rough-estimate on binary length used to reserve
         // bytes for a vector, if exact size is needed call recalculate_binary_length first
@@ -145,6 +149,20 @@ impl SignalIndexCache {
         self.signal_id_cache.len() as u32
     }
 
+    /// Gets the largest signal index defined in the `SignalIndexCache`.
+    pub fn max_signal_index(&self) -> u32 {
+        self.max_signal_index
+    }
+
+    // Gets the signal ID, source and integer ID of each record in the `SignalIndexCache`
+    pub(crate) fn records(&self) -> impl Iterator<Item = (Uuid, &str, u64)> {
+        self.signal_id_list
+            .iter()
+            .zip(&self.source_list)
+            .zip(&self.id_list)
+            .map(|((signal_id, source), id)| (*signal_id, source.as_str(), *id))
+    }
+
     /// Parses a `SignalIndexCache` from the specified byte buffer received from a `DataPublisher`.
     pub fn decode(&mut self, buffer: &[u8]) -> Result<Uuid, Box<dyn Error>> {
         let length = buffer.len();
@@ -153,43 +171,113 @@ impl SignalIndexCache {
             return Err("not enough buffer provided to parse".into());
         }
 
-        let bytes: [u8; 4] = buffer.try_into()?;
-        let binary_length = u32::from_be_bytes(bytes);
+        // Byte size of cache
+        let binary_length = u32::from_be_bytes(buffer[..4].try_into()?) as usize;
         let mut offset = 4;
 
-        if (length as u32) < binary_length {
+        if length < binary_length {
             return Err("not enough buffer provided to parse".into());
         }
 
-        let subscriber_id = Uuid::from_slice(&buffer[offset..offset + 16])?;
-        offset += 16;
+        let buffer = &buffer[..binary_length.max(offset)];
+
+        // Subscriber ID
+        let subscriber_id = Uuid::from_bytes_le(read_bytes(buffer, &mut offset)?);
 
-        let bytes: [u8; 4] = buffer[offset..].try_into()?;
-        let reference_count = u32::from_be_bytes(bytes);
-        offset += 4;
+        // Number of references
+        let reference_count = u32::from_be_bytes(read_bytes(buffer, &mut offset)?);
 
         for _ in 0..reference_count {
-            let bytes: [u8; 4] = buffer[offset..].try_into()?;
-            let signal_index = i32::from_be_bytes(bytes);
-            offset += 4;
+            // Signal index
+            let signal_index = i32::from_be_bytes(read_bytes(buffer, &mut offset)?);
 
-            let signal_id = Uuid::from_slice(&buffer[offset..offset + 16])?;
-            offset += 16;
+            // Signal ID
+            let signal_id = Uuid::from_bytes_le(read_bytes(buffer, &mut offset)?);
 
-            let bytes: [u8; 4] = buffer[offset..].try_into()?;
-            let source_size = u32::from_be_bytes(bytes) as usize;
-            offset += 4;
+            // Source
+            let source_size = u32::from_be_bytes(read_bytes(buffer, &mut offset)?) as usize;
+
+            if offset + source_size > buffer.len() {
+                return Err("not enough buffer provided to parse".into());
+            }
 
             let source = str::from_utf8(&buffer[offset..offset + source_size])?;
             offset += source_size;
 
-            let bytes: [u8; 8] = buffer[offset..].try_into()?;
-            let key_id = u64::from_be_bytes(bytes);
-            offset += 8;
+            // ID
+            let key_id = u64::from_be_bytes(read_bytes(buffer, &mut offset)?);
 
             self.add_record(signal_index, signal_id, source.to_string(), key_id);
         }
 
+        // There is additional data here about unauthorized signal IDs that may need to be parsed in the future...
+
         Ok(subscriber_id)
     }
+
+    /// Serializes the `SignalIndexCache` for the specified subscriber into a byte buffer for transmission
+    /// to a `DataSubscriber`.
+    pub fn encode(&self, subscriber_id: Uuid) -> Vec<u8> {
+        let mut buffer = vec![0u8; 4];
+
+        // Subscriber ID
+        buffer.extend_from_slice(&subscriber_id.to_bytes_le());
+
+        // Number of references
+        buffer.extend_from_slice(&(self.signal_id_list.len() as u32).to_be_bytes());
+
+        for (signal_id, source, id) in self.records() {
+            // Signal index
+            buffer.extend_from_slice(&self.signal_index(signal_id).to_be_bytes());
+
+            // Signal ID
+            buffer.extend_from_slice(&signal_id.to_bytes_le());
+
+            // Source
+            buffer.extend_from_slice(&(source.len() as u32).to_be_bytes());
+            buffer.extend_from_slice(source.as_bytes());
+
+            // ID
+            buffer.extend_from_slice(&id.to_be_bytes());
+        }
+
+        // No unauthorized signal IDs are reported
+        buffer.extend_from_slice(&0u32.to_be_bytes());
+
+        // Byte size of cache
+        let binary_length = buffer.len() as u32;
+        buffer[..4].copy_from_slice(&binary_length.to_be_bytes());
+
+        buffer
+    }
+}
+
+impl Clone for SignalIndexCache {
+    // TSSC decoder state is specific to a single data stream so it is not cloned
+    fn clone(&self) -> Self {
+        SignalIndexCache {
+            reference: self.reference.clone(),
+            signal_id_list: self.signal_id_list.clone(),
+            source_list: self.source_list.clone(),
+            id_list: self.id_list.clone(),
+            signal_id_cache: self.signal_id_cache.clone(),
+            max_signal_index: self.max_signal_index,
+            tssc_decoder: Mutex::new(None),
+        }
+    }
+}
+
+// Reads a fixed number of bytes from the buffer at the specified offset, advancing the offset.
+fn read_bytes<const N: usize>(
+    buffer: &[u8],
+    offset: &mut usize,
+) -> Result<[u8; N], Box<dyn Error>> {
+    if *offset + N > buffer.len() {
+        return Err("not enough buffer provided to parse".into());
+    }
+
+    let bytes: [u8; N] = buffer[*offset..*offset + N].try_into()?;
+    *offset += N;
+
+    Ok(bytes)
 }
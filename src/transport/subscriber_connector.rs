@@ -21,27 +21,57 @@
 //
 //******************************************************************************************************
 
-// TODO: Remove later
-#![allow(dead_code)]
-
 use crate::transport::ConnectStatus;
 use crate::transport::DataSubscriber;
-//use std::ops::{Deref, DerefMut};
 use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::{Arc, Mutex, RwLock};
+use std::sync::{Arc, Condvar, Mutex};
 use std::thread;
 use std::thread::JoinHandle;
-use std::time::{Duration, Instant};
+use std::time::Duration;
+
+type ReconnectHandler = dyn Fn(Arc<DataSubscriber>) + Send + Sync;
+
+// Cancellation flag with a signal to wake any thread waiting to retry a connection
+#[derive(Default)]
+struct CancelSignal {
+    canceled: Mutex<bool>,
+    wake: Condvar,
+}
+
+impl CancelSignal {
+    fn set(&self, canceled: bool) {
+        *self.canceled.lock().unwrap() = canceled;
+
+        if canceled {
+            self.wake.notify_all();
+        }
+    }
+
+    fn is_set(&self) -> bool {
+        *self.canceled.lock().unwrap()
+    }
+
+    // Waits for the specified duration, returning early when canceled. Returns true if canceled.
+    fn wait(&self, duration: Duration) -> bool {
+        let canceled = self.canceled.lock().unwrap();
+
+        let (canceled, _) = self
+            .wake
+            .wait_timeout_while(canceled, duration, |canceled| !*canceled)
+            .unwrap();
+
+        *canceled
+    }
+}
 
 /// Represents a connector that will establish or automatically reestablish a connection
 /// from a `DataSubscriber` to a `DataPublisher`.
-//#[derive(Debug)]
 pub struct SubscriberConnector {
     /// Called when an error message should be logged.
-    pub error_message_callback: Box<dyn Fn(String) + Send + Sync>,
+    pub error_message_callback: Arc<dyn Fn(String) + Send + Sync>,
 
     /// Called when SubscriberConnector attempts to reconnect.
-    pub reconnect_callback: Box<dyn Fn(Arc<DataSubscriber>) + Send + Sync>,
+    pub reconnect_callback: Arc<ReconnectHandler>,
 
     /// Defines the `DataPublisher` DNS name or IP.
     pub hostname: String,
@@ -66,33 +96,16 @@ pub struct SubscriberConnector {
 
     connect_attempt: i32,
     pub(crate) connection_refused: AtomicBool,
-    cancel: AtomicBool,
-    reconnect_thread_mutex: Mutex<Option<JoinHandle<()>>>,
-    wait_timer_mutex: Mutex<Option<JoinHandle<()>>>,
-
-    assigning_handler_mutex: RwLock<()>,
+    cancel: Arc<CancelSignal>,
+    reconnect_thread: Arc<Mutex<Option<JoinHandle<()>>>>,
 }
 
-// impl DerefMut for SubscriberConnector {
-//     fn deref_mut(&mut self) -> &mut SubscriberConnector {
-//         self
-//     }
-// }
-
-// impl Deref for SubscriberConnector {
-//     type Target = SubscriberConnector;
-
-//     fn deref(&self) -> &SubscriberConnector {
-//         &self
-//     }
-// }
-
 impl SubscriberConnector {
     /// Creates a new `SubscriberConnector`
     pub fn new() -> Self {
         Self {
-            error_message_callback: Box::new(|_| {}),
-            reconnect_callback: Box::new(|_| {}),
+            error_message_callback: Arc::new(|_| {}),
+            reconnect_callback: Arc::new(|_| {}),
             hostname: "localhost".to_string(),
             port: 6162,
             max_retries: -1,
@@ -101,255 +114,384 @@ impl SubscriberConnector {
             auto_reconnect: true,
             connect_attempt: 0,
             connection_refused: AtomicBool::new(false),
-            cancel: AtomicBool::new(false),
-            reconnect_thread_mutex: Mutex::new(None),
-            wait_timer_mutex: Mutex::new(None),
-            assigning_handler_mutex: RwLock::new(()),
+            cancel: Arc::new(CancelSignal::default()),
+            reconnect_thread: Arc::new(Mutex::new(None)),
         }
     }
 
-    pub(crate) fn dispose(&self) {
-        self.cancel.store(true, Ordering::SeqCst);
+    // Cancels any pending connection attempts and returns any running reconnect thread so that
+    // it can be joined without holding the connector lock
+    pub(crate) fn dispose(&self) -> Option<JoinHandle<()>> {
+        self.cancel();
+        self.reconnect_thread.lock().unwrap().take()
+    }
 
-        // // Make sure to wait on any running reconnect to complete...
-        // let mut reconnect_thread_guard = self.reconnect_thread_mutex.lock().unwrap();
+    // Initiates a connection cycle of the `DataSubscriber` to the configured `DataPublisher`,
+    // retrying failed attempts with an exponential back-off until connected, canceled or the
+    // maximum number of retries has been attempted.
+    //
+    // Connector is owned by the `DataSubscriber`, so its lock is only held briefly to access
+    // settings, this way a cancel request can be processed while a connection is pending.
+    pub(crate) fn connect(ds: &Arc<DataSubscriber>) -> ConnectStatus {
+        if ds.disposing.load(Ordering::SeqCst) {
+            return ConnectStatus::Canceled;
+        }
 
-        // if let Some(reconnect_thread) = reconnect_thread_guard.take() {
-        //     drop(reconnect_thread_guard);
-        //     reconnect_thread.join().unwrap();
-        // }
+        // Validate settings that would fail every attempt before starting connection cycle
+        let invalid = if ds.is_listening() {
+            Some("subscriber is listening for connections; direct connections disallowed".into())
+        } else {
+            ds.validate_version().err().map(|e| e.to_string())
+        };
 
-        // // Make sure to wait on any running wait timer to complete...
-        // let mut wait_timer_guard = self.wait_timer_mutex.lock().unwrap();
+        if let Some(message) = invalid {
+            Self::dispatch_error_message(ds, message);
+            return ConnectStatus::Failed;
+        }
 
-        // if let Some(wait_timer) = wait_timer_guard.take() {
-        //     drop(wait_timer_guard);
-        //     wait_timer.join().unwrap();
-        // }
+        ds.connector.lock().unwrap().reset_connection();
+
+        Self::connect_attempts(ds)
     }
 
-    fn auto_reconnect(&self, ds: Arc<DataSubscriber>) {
-        if self.cancel.load(Ordering::SeqCst) || ds.disposing.load(Ordering::SeqCst) {
-            return;
-        }
+    fn connect_attempts(ds: &Arc<DataSubscriber>) -> ConnectStatus {
+        // Connection may already be terminated by the time attempts complete, so result of the
+        // connection attempt is tracked rather than current connection state
+        let mut connected = false;
+
+        while !ds.disposing.load(Ordering::SeqCst) {
+            let (hostname, port, retry_interval, cancel) = {
+                let mut connector = ds.connector.lock().unwrap();
+
+                if connector.max_retries != -1 && connector.connect_attempt >= connector.max_retries
+                {
+                    drop(connector);
+
+                    Self::dispatch_error_message(
+                        ds,
+                        "Maximum connection retries attempted. Auto-reconnect canceled.".into(),
+                    );
+                    break;
+                }
+
+                connector.connect_attempt += 1;
+
+                (
+                    connector.hostname.clone(),
+                    connector.port,
+                    connector.retry_interval,
+                    Arc::clone(&connector.cancel),
+                )
+            };
+
+            if cancel.is_set() {
+                return ConnectStatus::Canceled;
+            }
 
-        // Make sure to wait on any running reconnect to complete...
-        let mut reconnect_thread_guard = self.reconnect_thread_mutex.lock().unwrap();
+            // Connection attempts made by connector retain the current attempt count
+            match ds._connect(&hostname, port, true) {
+                Ok(()) => {
+                    connected = true;
+                    break;
+                }
+                Err(e) => {
+                    // Cannot retry an existing connection
+                    if ds.is_connected() {
+                        connected = true;
+                        break;
+                    }
+
+                    Self::dispatch_error_message(ds, e.to_string());
+                }
+            }
+
+            if !ds.disposing.load(Ordering::SeqCst)
+                && retry_interval > 0
+                && Self::wait_for_retry(ds)
+            {
+                return ConnectStatus::Canceled;
+            }
+        }
 
-        if let Some(reconnect_thread) = reconnect_thread_guard.take() {
-            drop(reconnect_thread_guard);
-            reconnect_thread.join().unwrap();
+        if ds.disposing.load(Ordering::SeqCst) {
+            return ConnectStatus::Canceled;
         }
 
-        let sc = Arc::clone(&ds.connector);
-        let reconnect_thread = thread::spawn({
-            move || {
-                // // Reset connection attempt counter if last attempt was not refused
-                // if !sc.connection_refused.load(Ordering::SeqCst) {
-                //     //sc.reset_connection();
-                // }
+        if connected {
+            ConnectStatus::Success
+        } else {
+            ConnectStatus::Failed
+        }
+    }
 
-                // if sc.max_retries != -1 && sc.connect_attempt >= sc.max_retries {
-                //     (sc.error_message_callback)(
-                //         "Maximum connection retries attempted. Auto-reconnect canceled."
-                //             .to_string(),
-                //     );
-                //     return;
-                // }
+    // Starts an automatic reconnection cycle, on a separate thread, for a `DataSubscriber`
+    // whose connection to the `DataPublisher` was terminated.
+    pub(crate) fn auto_reconnect(ds: &Arc<DataSubscriber>) {
+        let (cancel, reconnect_thread) = {
+            let connector = ds.connector.lock().unwrap();
+            (
+                Arc::clone(&connector.cancel),
+                Arc::clone(&connector.reconnect_thread),
+            )
+        };
 
-                // sc.wait_for_retry();
+        if cancel.is_set() || ds.disposing.load(Ordering::SeqCst) {
+            return;
+        }
 
-                // if sc.cancel.load(Ordering::SeqCst) || ds.disposing.load(Ordering::SeqCst) {
-                //     return;
-                // }
+        // Make sure to wait on any running reconnect to complete...
+        let previous_thread = reconnect_thread.lock().unwrap().take();
 
-                // if sc.connect(ds.clone(), true) == ConnectStatus::Canceled {
-                //     return;
-                // }
+        if let Some(thread) = previous_thread {
+            if thread.thread().id() != thread::current().id() && thread.join().is_err() {
+                Self::dispatch_error_message(ds, "failed to join reconnect thread".into());
+            }
+        }
 
-                // // Notify the user that reconnect attempt was completed.
-                // sc.begin_callback_sync();
+        let ds = Arc::clone(ds);
 
-                // if !sc.cancel.load(Ordering::SeqCst) {
-                //     (sc.reconnect_callback)(ds.clone());
-                // }
+        *reconnect_thread.lock().unwrap() = Some(thread::spawn(move || {
+            {
+                let mut connector = ds.connector.lock().unwrap();
 
-                // sc.end_callback_sync();
-            }
-        });
+                // Reset connection attempt counter if last attempt was not refused
+                if !connector.connection_refused.load(Ordering::SeqCst) {
+                    connector.connect_attempt = 0;
+                }
 
-        let mut reconnect_thread_guard = self.reconnect_thread_mutex.lock().unwrap();
-        *reconnect_thread_guard = Some(reconnect_thread);
-    }
+                if connector.max_retries != -1 && connector.connect_attempt >= connector.max_retries
+                {
+                    drop(connector);
 
-    // fn wait_for_retry(&self) {
-    //     let mut wait_timer_mutex = self.wait_timer_mutex.lock().unwrap();
-    //     let mut wait_timer = wait_timer_mutex.take().unwrap();
+                    Self::dispatch_error_message(
+                        &ds,
+                        "Maximum connection retries attempted. Auto-reconnect canceled.".into(),
+                    );
+                    return;
+                }
+            }
 
-    //     if wait_timer.is_none() {
-    //         *wait_timer = Some(std::time::Instant::now());
-    //     }
+            if Self::wait_for_retry(&ds) || ds.disposing.load(Ordering::SeqCst) {
+                return;
+            }
 
-    //     let elapsed = wait_timer.unwrap().elapsed().as_millis() as i32;
+            let status = Self::connect_attempts(&ds);
 
-    //     if elapsed < self.retry_interval {
-    //         thread::sleep(Duration::from_millis(
-    //             (self.retry_interval - elapsed) as u64,
-    //         ));
-    //     }
+            if status == ConnectStatus::Canceled || cancel.is_set() {
+                return;
+            }
 
-    //     *wait_timer_mutex = Some(wait_timer);
-    // }
+            // Notify the user that reconnect attempt was completed.
+            if status == ConnectStatus::Success {
+                let callback = ds.auto_reconnect_callback.lock().unwrap().clone();
 
-    fn wait_for_retry(&self) {
-        // Apply exponential back-off algorithm for retry attempt delays
-        let exponent: i32 = if self.connect_attempt > 13 {
-            12
-        } else {
-            self.connect_attempt - 1
-        };
+                if let Some(callback) = callback {
+                    callback();
+                }
+            }
 
-        let mut retry_interval = 0;
+            let reconnect_callback = Arc::clone(&ds.connector.lock().unwrap().reconnect_callback);
 
-        if self.connect_attempt > 0 {
-            retry_interval = self.retry_interval * 2i32.pow(exponent as u32);
-        }
+            reconnect_callback(Arc::clone(&ds));
+        }));
+    }
 
-        retry_interval = i32::min(retry_interval, self.max_retry_interval);
+    // Waits for the exponential back-off delay of the current connection attempt.
+    // Returns true if the wait was canceled.
+    fn wait_for_retry(ds: &Arc<DataSubscriber>) -> bool {
+        let (message, retry_interval, cancel) = {
+            let connector = ds.connector.lock().unwrap();
+
+            // Apply exponential back-off algorithm for retry attempt delays
+            let exponent = if connector.connect_attempt > 13 {
+                12
+            } else {
+                connector.connect_attempt - 1
+            };
+
+            let mut retry_interval = 0;
+
+            if connector.connect_attempt > 0 {
+                retry_interval = connector
+                    .retry_interval
+                    .saturating_mul(2i32.pow(exponent as u32));
+            }
 
-        // Notify the user that we are attempting to reconnect.
-        let message = if self.connect_attempt > 0 {
-            format!(
-                "Connection attempt {}, to \"{}:{}\" was terminated. Attempting to reconnect in {:.2} seconds...",
-                self.connect_attempt + 1,
-                self.hostname,
-                self.port,
-                retry_interval
-            )
-        } else {
-            format!(
-                "Connection to \"{}:{}\" was terminated. Attempting to reconnect...",
-                self.hostname, self.port
-            )
+            retry_interval = i32::min(retry_interval, connector.max_retry_interval).max(0);
+
+            // Notify the user that we are attempting to reconnect.
+            let message = if connector.connect_attempt > 0 {
+                format!(
+                    "Connection attempt {} to \"{}:{}\" was terminated. Attempting to reconnect in {:.2} seconds...",
+                    connector.connect_attempt + 1,
+                    connector.hostname,
+                    connector.port,
+                    retry_interval as f64 / 1000.0
+                )
+            } else {
+                format!(
+                    "Connection to \"{}:{}\" was terminated. Attempting to reconnect...",
+                    connector.hostname, connector.port
+                )
+            };
+
+            (message, retry_interval, Arc::clone(&connector.cancel))
         };
 
-        //self.dispatch_error_message(&message);
+        Self::dispatch_error_message(ds, message);
 
-        // Lock the wait timer mutex and update the value
-        {
-            let mut wait_timer_guard = self.wait_timer_mutex.lock().unwrap();
-            *wait_timer_guard = Some(thread::spawn(move || {
-                thread::sleep(Duration::from_secs(retry_interval as u64));
-            }));
-        }
+        cancel.wait(Duration::from_millis(retry_interval as u64))
+    }
 
-        // Wait for the timer to complete
-        if let Some(handle) = self.wait_timer_mutex.lock().unwrap().take() {
-            handle.join().unwrap();
-        }
+    fn dispatch_error_message(ds: &DataSubscriber, message: String) {
+        let callback = Arc::clone(&ds.connector.lock().unwrap().error_message_callback);
+        callback(message);
     }
 
-    fn begin_callback_sync(&self) {
-        let mut assigning_handler_mutex = self.assigning_handler_mutex.write().unwrap();
-        let mut reconnect_thread_mutex = self.reconnect_thread_mutex.lock().unwrap();
+    /// Cancels any pending connection attempts, including any wait before the next retry.
+    pub(crate) fn cancel(&self) {
+        self.cancel.set(true);
+    }
 
-        if let Some(reconnect_thread) = reconnect_thread_mutex.take() {
-            drop(reconnect_thread_mutex);
-            reconnect_thread.join().unwrap();
-        }
+    pub(crate) fn reset_connection(&mut self) {
+        self.connect_attempt = 0;
+        self.cancel.set(false);
+    }
+}
 
-        drop(assigning_handler_mutex);
+impl Default for SubscriberConnector {
+    fn default() -> Self {
+        Self::new()
     }
+}
 
-    fn end_callback_sync(&self) {
-        let mut assigning_handler_mutex = self.assigning_handler_mutex.write().unwrap();
-        let mut reconnect_thread_mutex = self.reconnect_thread_mutex.lock().unwrap();
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::TcpListener;
+    use std::sync::mpsc;
+
+    fn unused_port() -> u16 {
+        TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port()
+    }
 
-        if let Some(reconnect_thread) = reconnect_thread_mutex.take() {
-            drop(reconnect_thread_mutex);
-            reconnect_thread.join().unwrap();
-        }
+    fn configure(ds: &DataSubscriber, port: u16, retry_interval: i32) -> mpsc::Receiver<String> {
+        let (sender, receiver) = mpsc::channel();
+        let sender = Mutex::new(sender);
+        let mut connector = ds.connector.lock().unwrap();
+
+        connector.hostname = "127.0.0.1".to_string();
+        connector.port = port;
+        connector.retry_interval = retry_interval;
+        connector.max_retry_interval = retry_interval * 4;
+        connector.error_message_callback = Arc::new(move |message: String| {
+            let _ = sender.lock().unwrap().send(message);
+        });
 
-        drop(assigning_handler_mutex);
+        receiver
     }
 
-    fn connect(&self, ds: Arc<DataSubscriber>, auto_reconnect: bool) -> ConnectStatus {
-        //     if self.cancel.load(Ordering::SeqCst) || ds.disposing.load(Ordering::SeqCst) {
-        //         return ConnectStatus::Canceled;
-        //     }
+    #[test]
+    fn test_connect_retries_until_publisher_is_available() {
+        let ds = Arc::new(DataSubscriber::new());
+        let port = unused_port();
+        let messages = configure(&ds, port, 50);
 
-        //     let mut wait_timer_mutex = self.wait_timer_mutex.lock().unwrap();
-        //     let mut wait_timer = wait_timer_mutex.take().unwrap();
+        let publisher = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(300));
 
-        //     if wait_timer.is_none() {
-        //         *wait_timer = Some(std::time::Instant::now());
-        //     }
-
-        //     let elapsed = wait_timer.unwrap().elapsed().as_millis() as i32;
+            let listener = TcpListener::bind(("127.0.0.1", port)).unwrap();
+            listener.accept().unwrap().0
+        });
 
-        //     if elapsed < self.retry_interval {
-        //         thread::sleep(Duration::from_millis(
-        //             (self.retry_interval - elapsed) as u64,
-        //         ));
-        //     }
+        assert_eq!(SubscriberConnector::connect(&ds), ConnectStatus::Success);
+        assert!(ds.is_connected());
+        assert!(ds.connector.lock().unwrap().connect_attempt > 1);
 
-        //     *wait_timer_mutex = Some(wait_timer);
+        let _stream = publisher.join().unwrap();
+        let retries: Vec<String> = messages.try_iter().collect();
 
-        //     if self.cancel.load(Ordering::SeqCst) || ds.disposing.load(Ordering::SeqCst) {
-        //         return ConnectStatus::Canceled;
-        //     }
+        // Retry delays back-off exponentially from retry interval
+        assert!(retries
+            .iter()
+            .any(|m| m.contains("reconnect in 0.05 seconds")));
+        assert!(retries
+            .iter()
+            .any(|m| m.contains("reconnect in 0.10 seconds")));
 
-        //     let mut reconnect_thread_mutex = self.reconnect_thread_mutex.lock().unwrap();
-        //     if let Some(reconnect_thread) = reconnect_thread_mutex.take() {
-        //         drop(reconnect_thread_mutex);
-        //         reconnect_thread.join().unwrap();
-        //     }
+        ds.dispose();
+    }
 
-        //     let mut wait_timer_mutex = self.wait_timer_mutex.lock().unwrap();
-        //     let mut wait_timer = wait_timer_mutex.take().unwrap();
+    #[test]
+    fn test_cancel_wakes_retry_wait() {
+        let ds = Arc::new(DataSubscriber::new());
+        let messages = configure(&ds, unused_port(), 60_000);
+        let (status_sender, status_receiver) = mpsc::channel();
 
-        //     if wait_timer.is_none() {
-        //         *wait_timer = Some(std::time::Instant::now());
-        //     }
+        let connecting_ds = Arc::clone(&ds);
 
-        //     let elapsed = wait_timer.unwrap().elapsed().as_millis() as i32;
+        thread::spawn(move || {
+            status_sender
+                .send(SubscriberConnector::connect(&connecting_ds))
+                .unwrap();
+        });
 
-        //     if elapsed < self.retry_interval {
-        //         thread::sleep(Duration::from_millis(
-        //             (self.retry_interval - elapsed) as u64,
-        //         ));
-        //     }
+        // Wait for the connector to begin its retry delay
+        while !messages
+            .recv_timeout(Duration::from_secs(5))
+            .unwrap()
+            .contains("Attempting to reconnect in 60.00 seconds")
+        {}
+
+        ds.connector.lock().unwrap().cancel();
+
+        assert_eq!(
+            status_receiver
+                .recv_timeout(Duration::from_secs(5))
+                .unwrap(),
+            ConnectStatus::Canceled
+        );
+    }
 
-        //     *wait_timer_mutex = Some(wait_timer);
+    #[test]
+    fn test_auto_reconnect_notifies_callbacks() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let ds = Arc::new(DataSubscriber::new());
+        let _messages = configure(&ds, listener.local_addr().unwrap().port(), 50);
 
-        //     if self.cancel.load(Ordering::SeqCst) || ds.disposing.load(Ordering::SeqCst) {
-        //         return ConnectStatus::Canceled;
-        //     }
+        let (auto_reconnect_sender, auto_reconnect_receiver) = mpsc::channel();
+        let (reconnect_sender, reconnect_receiver) = mpsc::channel();
+        let reconnect_sender = Mutex::new(reconnect_sender);
 
-        //     let mut reconnect_thread_mutex = self.reconnect_thread_mutex.lock().unwrap();
-        //     if let Some(reconnect_thread) = reconnect_thread_mutex.take() {
-        //         drop(reconnect_thread_mutex);
-        //         reconnect_thread.join().unwrap();
-        //     }
+        *ds.auto_reconnect_callback.lock().unwrap() = Some(Arc::new(move || {
+            auto_reconnect_sender.send(()).unwrap();
+        }));
 
-        //     let mut wait_timer_mutex = self.wait_timer_mutex.lock().unwrap();
-        //     let mut wait_timer = wait_timer_mutex.take().unwrap();
+        ds.connector.lock().unwrap().reconnect_callback =
+            Arc::new(move |ds: Arc<DataSubscriber>| {
+                reconnect_sender
+                    .lock()
+                    .unwrap()
+                    .send(ds.is_connected())
+                    .unwrap();
+            });
 
-        //     if wait_timer.is_none() {
-        //         *wait_timer = Some(std::time::Instant::now());
-        //     }
+        SubscriberConnector::auto_reconnect(&ds);
 
-        ConnectStatus::Success
-    }
+        let _stream = listener.accept().unwrap();
 
-    pub(crate) fn cancel(&self) {
-        self.cancel.store(true, Ordering::SeqCst);
+        auto_reconnect_receiver
+            .recv_timeout(Duration::from_secs(5))
+            .unwrap();
 
-        // TODO: Implement
-    }
+        assert!(reconnect_receiver
+            .recv_timeout(Duration::from_secs(5))
+            .unwrap());
 
-    pub(crate) fn reset_connection(&mut self) {
-        self.connect_attempt = 0;
-        self.cancel.store(false, Ordering::SeqCst);
+        ds.dispose();
     }
 }
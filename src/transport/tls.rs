@@ -0,0 +1,523 @@
+//******************************************************************************************************
+//  tls.rs - Gbtc
+//
+//  Copyright © 2023, Grid Protection Alliance.  All Rights Reserved.
+//
+//  Licensed to the Grid Protection Alliance (GPA) under one or more contributor license agreements. See
+//  the NOTICE file distributed with this work for additional information regarding copyright ownership.
+//  The GPA licenses this file to you under the MIT License (MIT), the "License"; you may not use this
+//  file except in compliance with the License. You may obtain a copy of the License at:
+//
+//      http://opensource.org/licenses/MIT
+//
+//  Unless agreed to in writing, the subject software distributed under the License is distributed on an
+//  "AS-IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied. Refer to the
+//  License for the specific language governing permissions and limitations.
+//
+//  Code Modification History:
+//  ----------------------------------------------------------------------------------------------------
+//  04/01/2023 - J. Ritchie Carroll
+//       Generated original version of source code.
+//
+//******************************************************************************************************
+
+// TLS secured command channel streams. When the `tls` feature is disabled, connecting returns
+// an error and the stream type is uninhabited.
+
+pub(crate) use imp::{connect, TlsStream};
+
+#[cfg(feature = "tls")]
+mod imp {
+    use crate::transport::TlsConfig;
+    use ring::digest::{digest, SHA256};
+    use rustls::client::danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier};
+    use rustls::crypto::CryptoProvider;
+    use rustls::crypto::{ring as provider, verify_tls12_signature, verify_tls13_signature};
+    use rustls::pki_types::pem::PemObject;
+    use rustls::pki_types::{CertificateDer, PrivateKeyDer, ServerName, UnixTime};
+    use rustls::{ClientConfig, ClientConnection, DigitallySignedStruct, RootCertStore};
+    use rustls::{Error as TlsError, SignatureScheme};
+    use std::error::Error;
+    use std::io::{self, ErrorKind, Read, Write};
+    use std::net::{Shutdown, SocketAddr, TcpStream};
+    use std::sync::{Arc, Mutex};
+
+    const RECEIVE_BUFFER_SIZE: usize = 16 * 1024;
+
+    // TLS session over a TCP socket that supports a reader thread and concurrent writers. The
+    // session lock is only held while processing TLS records, never while blocked on the socket.
+    pub(crate) struct TlsStream {
+        connection: Mutex<ClientConnection>,
+        socket: TcpStream,
+        // Buffer for records received from the socket, allocated on first socket read and reused
+        receive_buffer: Mutex<Vec<u8>>,
+    }
+
+    // Establishes a TLS session over the connected socket, completing the handshake so that any
+    // certificate validation failure is reported as a connection error
+    pub(crate) fn connect(
+        mut socket: TcpStream,
+        hostname: &str,
+        config: &TlsConfig,
+    ) -> Result<TlsStream, Box<dyn Error>> {
+        let server_name = if config.server_name.is_empty() {
+            hostname
+        } else {
+            config.server_name.as_str()
+        };
+
+        let server_name = ServerName::try_from(server_name.to_string())
+            .map_err(|e| format!("invalid TLS server name \"{}\": {}", server_name, e))?;
+
+        let mut connection = ClientConnection::new(Arc::new(client_config(config)?), server_name)?;
+
+        while connection.is_handshaking() {
+            connection
+                .complete_io(&mut socket)
+                .map_err(|e| format!("TLS handshake failed: {}", e))?;
+        }
+
+        Ok(TlsStream {
+            connection: Mutex::new(connection),
+            socket,
+            receive_buffer: Mutex::new(Vec::new()),
+        })
+    }
+
+    impl TlsStream {
+        pub(crate) fn read(&self, buffer: &mut [u8]) -> io::Result<usize> {
+            let mut received = self.receive_buffer.lock().unwrap();
+
+            loop {
+                {
+                    let mut connection = self.connection.lock().unwrap();
+
+                    match connection.reader().read(buffer) {
+                        Ok(length) => return Ok(length),
+                        Err(e) if e.kind() == ErrorKind::WouldBlock => {}
+                        Err(e) => return Err(e),
+                    }
+                }
+
+                if received.is_empty() {
+                    received.resize(RECEIVE_BUFFER_SIZE, 0);
+                }
+
+                let length = (&self.socket).read(&mut received)?;
+
+                if length == 0 {
+                    return Err(ErrorKind::UnexpectedEof.into());
+                }
+
+                let mut connection = self.connection.lock().unwrap();
+                let mut records = &received[..length];
+
+                while !records.is_empty() {
+                    connection.read_tls(&mut records)?;
+
+                    connection
+                        .process_new_packets()
+                        .map_err(|e| io::Error::new(ErrorKind::InvalidData, e))?;
+                }
+
+                // Processing records may queue a response, e.g., a key update
+                while connection.wants_write() {
+                    connection.write_tls(&mut &self.socket)?;
+                }
+            }
+        }
+
+        pub(crate) fn write_all(&self, data: &[u8]) -> io::Result<()> {
+            let mut connection = self.connection.lock().unwrap();
+
+            connection.writer().write_all(data)?;
+
+            while connection.wants_write() {
+                connection.write_tls(&mut &self.socket)?;
+            }
+
+            Ok(())
+        }
+
+        pub(crate) fn peer_addr(&self) -> io::Result<SocketAddr> {
+            self.socket.peer_addr()
+        }
+
+        pub(crate) fn shutdown(&self) -> io::Result<()> {
+            let mut connection = self.connection.lock().unwrap();
+
+            connection.send_close_notify();
+
+            // Best effort delivery of close notification before closing socket
+            while connection.wants_write() {
+                if connection.write_tls(&mut &self.socket).is_err() {
+                    break;
+                }
+            }
+
+            self.socket.shutdown(Shutdown::Both)
+        }
+    }
+
+    fn client_config(config: &TlsConfig) -> Result<ClientConfig, Box<dyn Error>> {
+        let builder = ClientConfig::builder_with_provider(Arc::new(provider::default_provider()))
+            .with_safe_default_protocol_versions()?;
+
+        let builder = if !config.trusted_fingerprint.is_empty() {
+            let verifier = FingerprintVerifier::new(&config.trusted_fingerprint)?;
+
+            builder
+                .dangerous()
+                .with_custom_certificate_verifier(Arc::new(verifier))
+        } else if !config.ca_bundle_path.is_empty() {
+            let mut roots = RootCertStore::empty();
+
+            for certificate in load_certificates(&config.ca_bundle_path)? {
+                roots.add(certificate)?;
+            }
+
+            builder.with_root_certificates(roots)
+        } else {
+            return Err(
+                "TLS requires a CA bundle or a trusted fingerprint to validate the publisher certificate"
+                    .into(),
+            );
+        };
+
+        if config.client_certificate_path.is_empty() {
+            return Ok(builder.with_no_client_auth());
+        }
+
+        let certificates = load_certificates(&config.client_certificate_path)?;
+
+        let key = PrivateKeyDer::from_pem_file(&config.client_key_path).map_err(|e| {
+            format!(
+                "failed to load TLS client key \"{}\": {}",
+                config.client_key_path, e
+            )
+        })?;
+
+        Ok(builder.with_client_auth_cert(certificates, key)?)
+    }
+
+    fn load_certificates(path: &str) -> Result<Vec<CertificateDer<'static>>, Box<dyn Error>> {
+        let certificates = CertificateDer::pem_file_iter(path)
+            .and_then(|certificates| certificates.collect::<Result<Vec<_>, _>>())
+            .map_err(|e| format!("failed to load TLS certificates \"{}\": {}", path, e))?;
+
+        if certificates.is_empty() {
+            return Err(format!("no TLS certificates found in \"{}\"", path).into());
+        }
+
+        Ok(certificates)
+    }
+
+    // Trusts a publisher certificate by its SHA-256 fingerprint, handshake signatures are still verified
+    #[derive(Debug)]
+    struct FingerprintVerifier {
+        fingerprint: Vec<u8>,
+        provider: CryptoProvider,
+    }
+
+    impl FingerprintVerifier {
+        fn new(fingerprint: &str) -> Result<Self, Box<dyn Error>> {
+            let digits: String = fingerprint
+                .chars()
+                .filter(|c| !matches!(c, ':' | ' '))
+                .collect();
+
+            let invalid = || format!("invalid TLS certificate fingerprint \"{}\"", fingerprint);
+
+            if digits.len() != 64 {
+                return Err(invalid().into());
+            }
+
+            let fingerprint = (0..digits.len())
+                .step_by(2)
+                .map(|i| u8::from_str_radix(&digits[i..i + 2], 16))
+                .collect::<Result<Vec<u8>, _>>()
+                .map_err(|_| invalid())?;
+
+            Ok(FingerprintVerifier {
+                fingerprint,
+                provider: provider::default_provider(),
+            })
+        }
+    }
+
+    impl ServerCertVerifier for FingerprintVerifier {
+        fn verify_server_cert(
+            &self,
+            end_entity: &CertificateDer<'_>,
+            _intermediates: &[CertificateDer<'_>],
+            _server_name: &ServerName<'_>,
+            _ocsp_response: &[u8],
+            _now: UnixTime,
+        ) -> Result<ServerCertVerified, TlsError> {
+            if digest(&SHA256, end_entity).as_ref() == self.fingerprint.as_slice() {
+                Ok(ServerCertVerified::assertion())
+            } else {
+                Err(TlsError::General(
+                    "publisher certificate does not match trusted fingerprint".to_string(),
+                ))
+            }
+        }
+
+        fn verify_tls12_signature(
+            &self,
+            message: &[u8],
+            certificate: &CertificateDer<'_>,
+            signature: &DigitallySignedStruct,
+        ) -> Result<HandshakeSignatureValid, TlsError> {
+            verify_tls12_signature(
+                message,
+                certificate,
+                signature,
+                &self.provider.signature_verification_algorithms,
+            )
+        }
+
+        fn verify_tls13_signature(
+            &self,
+            message: &[u8],
+            certificate: &CertificateDer<'_>,
+            signature: &DigitallySignedStruct,
+        ) -> Result<HandshakeSignatureValid, TlsError> {
+            verify_tls13_signature(
+                message,
+                certificate,
+                signature,
+                &self.provider.signature_verification_algorithms,
+            )
+        }
+
+        fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+            self.provider
+                .signature_verification_algorithms
+                .supported_schemes()
+        }
+    }
+}
+
+#[cfg(not(feature = "tls"))]
+mod imp {
+    use crate::transport::TlsConfig;
+    use std::error::Error;
+    use std::io;
+    use std::net::{SocketAddr, TcpStream};
+
+    const FEATURE_DISABLED: &str = "STTP TLS support requires the \"tls\" feature";
+
+    pub(crate) enum TlsStream {}
+
+    pub(crate) fn connect(
+        _socket: TcpStream,
+        _hostname: &str,
+        _config: &TlsConfig,
+    ) -> Result<TlsStream, Box<dyn Error>> {
+        Err(FEATURE_DISABLED.into())
+    }
+
+    impl TlsStream {
+        pub(crate) fn read(&self, _buffer: &mut [u8]) -> io::Result<usize> {
+            match *self {}
+        }
+
+        pub(crate) fn write_all(&self, _data: &[u8]) -> io::Result<()> {
+            match *self {}
+        }
+
+        pub(crate) fn peer_addr(&self) -> io::Result<SocketAddr> {
+            match *self {}
+        }
+
+        pub(crate) fn shutdown(&self) -> io::Result<()> {
+            match *self {}
+        }
+    }
+}
+
+#[cfg(all(test, feature = "tls"))]
+mod tests {
+    use super::*;
+    use crate::transport::TlsConfig;
+    use rcgen::{BasicConstraints, Certificate, CertificateParams, IsCa, KeyPair};
+    use ring::digest::{digest, SHA256};
+    use rustls::crypto::ring::default_provider;
+    use rustls::pki_types::{PrivateKeyDer, PrivatePkcs8KeyDer};
+    use rustls::server::WebPkiClientVerifier;
+    use rustls::{RootCertStore, ServerConfig, ServerConnection, StreamOwned};
+    use std::io::{Read, Write};
+    use std::net::{TcpListener, TcpStream};
+    use std::path::PathBuf;
+    use std::sync::Arc;
+    use std::thread::{self, JoinHandle};
+
+    struct Credentials {
+        ca: Certificate,
+        server: Certificate,
+        server_key: KeyPair,
+        client: Certificate,
+        client_key: KeyPair,
+    }
+
+    fn credentials() -> Credentials {
+        let ca_key = KeyPair::generate().unwrap();
+        let mut ca_params = CertificateParams::new(Vec::<String>::new()).unwrap();
+        ca_params.is_ca = IsCa::Ca(BasicConstraints::Unconstrained);
+        let ca = ca_params.self_signed(&ca_key).unwrap();
+
+        let server_key = KeyPair::generate().unwrap();
+        let server = CertificateParams::new(vec!["localhost".to_string()])
+            .unwrap()
+            .signed_by(&server_key, &ca, &ca_key)
+            .unwrap();
+
+        let client_key = KeyPair::generate().unwrap();
+        let client = CertificateParams::new(vec!["subscriber".to_string()])
+            .unwrap()
+            .signed_by(&client_key, &ca, &ca_key)
+            .unwrap();
+
+        Credentials {
+            ca,
+            server,
+            server_key,
+            client,
+            client_key,
+        }
+    }
+
+    fn temp_file(name: &str, contents: &str) -> String {
+        let path: PathBuf = std::env::temp_dir().join(format!(
+            "sttp-tls-{}-{:?}-{}",
+            std::process::id(),
+            thread::current().id(),
+            name
+        ));
+
+        std::fs::write(&path, contents).unwrap();
+        path.to_string_lossy().to_string()
+    }
+
+    // Starts a publisher stand-in that requires mutual TLS and answers "hello" with "world"
+    fn start_server(credentials: &Credentials) -> (u16, JoinHandle<Result<(), String>>) {
+        let mut roots = RootCertStore::empty();
+        roots.add(credentials.ca.der().clone()).unwrap();
+
+        let provider = Arc::new(default_provider());
+
+        let client_verifier =
+            WebPkiClientVerifier::builder_with_provider(Arc::new(roots), Arc::clone(&provider))
+                .build()
+                .unwrap();
+
+        let key = PrivateKeyDer::Pkcs8(PrivatePkcs8KeyDer::from(
+            credentials.server_key.serialize_der(),
+        ));
+
+        let config = ServerConfig::builder_with_provider(provider)
+            .with_safe_default_protocol_versions()
+            .unwrap()
+            .with_client_cert_verifier(client_verifier)
+            .with_single_cert(vec![credentials.server.der().clone()], key)
+            .unwrap();
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        let server = thread::spawn(move || {
+            let (socket, _) = listener.accept().map_err(|e| e.to_string())?;
+            let connection = ServerConnection::new(Arc::new(config)).map_err(|e| e.to_string())?;
+            let mut stream = StreamOwned::new(connection, socket);
+
+            let mut request = [0u8; 5];
+            stream.read_exact(&mut request).map_err(|e| e.to_string())?;
+
+            if &request != b"hello" {
+                return Err("unexpected request".to_string());
+            }
+
+            stream.write_all(b"world").map_err(|e| e.to_string())?;
+            stream.flush().map_err(|e| e.to_string())
+        });
+
+        (port, server)
+    }
+
+    fn client_config(credentials: &Credentials) -> TlsConfig {
+        TlsConfig {
+            client_certificate_path: temp_file("client.pem", &credentials.client.pem()),
+            client_key_path: temp_file("client.key", &credentials.client_key.serialize_pem()),
+            ..TlsConfig::default()
+        }
+    }
+
+    fn exchange(stream: &TlsStream) -> Vec<u8> {
+        stream.write_all(b"hello").unwrap();
+
+        let mut response = Vec::new();
+
+        while response.len() < 5 {
+            let mut buffer = [0u8; 5];
+            let length = stream.read(&mut buffer).unwrap();
+            response.extend_from_slice(&buffer[..length]);
+        }
+
+        response
+    }
+
+    #[test]
+    fn test_mutual_tls_with_ca_bundle_and_server_name() {
+        let credentials = credentials();
+        let (port, server) = start_server(&credentials);
+
+        let config = TlsConfig {
+            ca_bundle_path: temp_file("ca.pem", &credentials.ca.pem()),
+            server_name: "localhost".to_string(),
+            ..client_config(&credentials)
+        };
+
+        let socket = TcpStream::connect(("127.0.0.1", port)).unwrap();
+        let stream = connect(socket, "127.0.0.1", &config).unwrap();
+
+        assert_eq!(exchange(&stream), b"world");
+        assert_eq!(server.join().unwrap(), Ok(()));
+    }
+
+    #[test]
+    fn test_trusted_fingerprint() {
+        let credentials = credentials();
+        let fingerprint = digest(&SHA256, credentials.server.der())
+            .as_ref()
+            .iter()
+            .map(|byte| format!("{:02X}", byte))
+            .collect::<Vec<_>>();
+
+        let (port, server) = start_server(&credentials);
+
+        let config = TlsConfig {
+            trusted_fingerprint: fingerprint.join(":"),
+            ..client_config(&credentials)
+        };
+
+        let socket = TcpStream::connect(("127.0.0.1", port)).unwrap();
+        let stream = connect(socket, "127.0.0.1", &config).unwrap();
+
+        assert_eq!(exchange(&stream), b"world");
+        assert_eq!(server.join().unwrap(), Ok(()));
+
+        let (port, server) = start_server(&credentials);
+
+        let config = TlsConfig {
+            trusted_fingerprint: "00".repeat(32),
+            ..client_config(&credentials)
+        };
+
+        let socket = TcpStream::connect(("127.0.0.1", port)).unwrap();
+        let result = connect(socket, "127.0.0.1", &config);
+
+        assert!(result.err().unwrap().to_string().contains("fingerprint"));
+        assert!(server.join().unwrap().is_err());
+    }
+}
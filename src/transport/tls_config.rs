@@ -0,0 +1,51 @@
+//******************************************************************************************************
+//  tls_config.rs - Gbtc
+//
+//  Copyright © 2023, Grid Protection Alliance.  All Rights Reserved.
+//
+//  Licensed to the Grid Protection Alliance (GPA) under one or more contributor license agreements. See
+//  the NOTICE file distributed with this work for additional information regarding copyright ownership.
+//  The GPA licenses this file to you under the MIT License (MIT), the "License"; you may not use this
+//  file except in compliance with the License. You may obtain a copy of the License at:
+//
+//      http://opensource.org/licenses/MIT
+//
+//  Unless agreed to in writing, the subject software distributed under the License is distributed on an
+//  "AS-IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied. Refer to the
+//  License for the specific language governing permissions and limitations.
+//
+//  Code Modification History:
+//  ----------------------------------------------------------------------------------------------------
+//  04/01/2023 - J. Ritchie Carroll
+//       Generated original version of source code.
+//
+//******************************************************************************************************
+
+/// Defines the TLS settings used to secure the command channel of a `DataSubscriber` connection
+/// when its security mode is `SecurityMode::TLS`.
+///
+/// Publisher certificates are validated against the certificate authorities in `ca_bundle_path`,
+/// or, when `trusted_fingerprint` is defined, against the fingerprint alone. One of the two must
+/// be provided. TLS support requires the `tls` cargo feature, enabled by default.
+#[derive(Debug, Clone, Default)]
+pub struct TlsConfig {
+    /// Defines the path to a PEM file of trusted certificate authorities used to validate the
+    /// publisher certificate chain.
+    pub ca_bundle_path: String,
+
+    /// Defines the path to a PEM file with the client certificate chain presented to publishers
+    /// that require mutual TLS. Leave empty when client authentication is not required.
+    pub client_certificate_path: String,
+
+    /// Defines the path to a PEM file with the private key of the client certificate.
+    pub client_key_path: String,
+
+    /// Defines the server name expected in the publisher certificate. Defaults to the
+    /// connection hostname when empty.
+    pub server_name: String,
+
+    /// Defines the SHA-256 fingerprint of a publisher certificate to trust directly, as
+    /// hexadecimal with optional colon separators. When defined, the publisher certificate
+    /// is trusted only when it matches and certificate authority validation is skipped.
+    pub trusted_fingerprint: String,
+}
@@ -0,0 +1,37 @@
+//******************************************************************************************************
+//  tssc.rs - Gbtc
+//
+//  Copyright © 2023, Grid Protection Alliance.  All Rights Reserved.
+//
+//  Licensed to the Grid Protection Alliance (GPA) under one or more contributor license agreements. See
+//  the NOTICE file distributed with this work for additional information regarding copyright ownership.
+//  The GPA licenses this file to you under the MIT License (MIT), the "License"; you may not use this
+//  file except in compliance with the License. You may obtain a copy of the License at:
+//
+//      http://opensource.org/licenses/MIT
+//
+//  Unless agreed to in writing, the subject software distributed under the License is distributed on an
+//  "AS-IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied. Refer to the
+//  License for the specific language governing permissions and limitations.
+//
+//  Code Modification History:
+//  ----------------------------------------------------------------------------------------------------
+//  04/01/2023 - J. Ritchie Carroll
+//       Generated original version of source code.
+//
+//******************************************************************************************************
+
+//! Time-series special compression (TSSC) used by STTP for compressed data packet payloads.
+
+// Expose local submodules as public passthroughs of sttp::transport::tssc module
+// for better organization and ease of use of public API
+
+mod code_words;
+
+mod point_metadata;
+
+mod decoder;
+pub use crate::transport::tssc::decoder::Decoder; // >> sttp::transport::tssc::Decoder
+
+mod encoder;
+pub use crate::transport::tssc::encoder::Encoder; // >> sttp::transport::tssc::Encoder
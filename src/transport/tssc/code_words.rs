@@ -0,0 +1,64 @@
+//******************************************************************************************************
+//  code_words.rs - Gbtc
+//
+//  Copyright © 2023, Grid Protection Alliance.  All Rights Reserved.
+//
+//  Licensed to the Grid Protection Alliance (GPA) under one or more contributor license agreements. See
+//  the NOTICE file distributed with this work for additional information regarding copyright ownership.
+//  The GPA licenses this file to you under the MIT License (MIT), the "License"; you may not use this
+//  file except in compliance with the License. You may obtain a copy of the License at:
+//
+//      http://opensource.org/licenses/MIT
+//
+//  Unless agreed to in writing, the subject software distributed under the License is distributed on an
+//  "AS-IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied. Refer to the
+//  License for the specific language governing permissions and limitations.
+//
+//  Code Modification History:
+//  ----------------------------------------------------------------------------------------------------
+//  04/01/2023 - J. Ritchie Carroll
+//       Generated original version of source code.
+//
+//******************************************************************************************************
+
+/// Defines the code words used to encode TSSC operations.
+pub(crate) struct CodeWords;
+
+impl CodeWords {
+    pub(crate) const END_OF_STREAM: u8 = 0;
+
+    pub(crate) const POINT_ID_XOR4: u8 = 1;
+    pub(crate) const POINT_ID_XOR8: u8 = 2;
+    pub(crate) const POINT_ID_XOR12: u8 = 3;
+    pub(crate) const POINT_ID_XOR16: u8 = 4;
+    pub(crate) const POINT_ID_XOR20: u8 = 5;
+    pub(crate) const POINT_ID_XOR24: u8 = 6;
+    pub(crate) const POINT_ID_XOR32: u8 = 7;
+
+    pub(crate) const TIME_DELTA1_FORWARD: u8 = 8;
+    pub(crate) const TIME_DELTA2_FORWARD: u8 = 9;
+    pub(crate) const TIME_DELTA3_FORWARD: u8 = 10;
+    pub(crate) const TIME_DELTA4_FORWARD: u8 = 11;
+    pub(crate) const TIME_DELTA1_REVERSE: u8 = 12;
+    pub(crate) const TIME_DELTA2_REVERSE: u8 = 13;
+    pub(crate) const TIME_DELTA3_REVERSE: u8 = 14;
+    pub(crate) const TIME_DELTA4_REVERSE: u8 = 15;
+    pub(crate) const TIMESTAMP2: u8 = 16;
+    pub(crate) const TIME_XOR_7BIT: u8 = 17;
+
+    pub(crate) const STATE_FLAGS2: u8 = 18;
+    pub(crate) const STATE_FLAGS_7BIT32: u8 = 19;
+
+    pub(crate) const VALUE1: u8 = 20;
+    pub(crate) const VALUE2: u8 = 21;
+    pub(crate) const VALUE3: u8 = 22;
+    pub(crate) const VALUE_ZERO: u8 = 23;
+    pub(crate) const VALUE_XOR4: u8 = 24;
+    pub(crate) const VALUE_XOR8: u8 = 25;
+    pub(crate) const VALUE_XOR12: u8 = 26;
+    pub(crate) const VALUE_XOR16: u8 = 27;
+    pub(crate) const VALUE_XOR20: u8 = 28;
+    pub(crate) const VALUE_XOR24: u8 = 29;
+    pub(crate) const VALUE_XOR28: u8 = 30;
+    pub(crate) const VALUE_XOR32: u8 = 31;
+}
@@ -0,0 +1,502 @@
+//******************************************************************************************************
+//  decoder.rs - Gbtc
+//
+//  Copyright © 2023, Grid Protection Alliance.  All Rights Reserved.
+//
+//  Licensed to the Grid Protection Alliance (GPA) under one or more contributor license agreements. See
+//  the NOTICE file distributed with this work for additional information regarding copyright ownership.
+//  The GPA licenses this file to you under the MIT License (MIT), the "License"; you may not use this
+//  file except in compliance with the License. You may obtain a copy of the License at:
+//
+//      http://opensource.org/licenses/MIT
+//
+//  Unless agreed to in writing, the subject software distributed under the License is distributed on an
+//  "AS-IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied. Refer to the
+//  License for the specific language governing permissions and limitations.
+//
+//  Code Modification History:
+//  ----------------------------------------------------------------------------------------------------
+//  04/01/2023 - J. Ritchie Carroll
+//       Generated original version of source code.
+//
+//******************************************************************************************************
+
+use crate::transport::tssc::code_words::CodeWords;
+use crate::transport::tssc::point_metadata::PointMetadata;
+use crate::transport::StateFlags;
+use crate::Ticks;
+use std::error::Error;
+use std::mem;
+
+// Decoded measurement as a tuple of signal index, timestamp, state flags and value
+type DecodedMeasurement = (i32, Ticks, StateFlags, f32);
+
+// Maintains the byte position and bit stream state while reading a TSSC buffer. Bit codes and whole
+// bytes are interleaved in the stream: a new bit stream byte is read at the current position only
+// once all bits of the prior bit stream byte have been consumed.
+#[derive(Debug, Default)]
+pub(crate) struct Reader {
+    data: Vec<u8>,
+    position: usize,
+    last_position: usize,
+    bit_stream_cache: u8,
+    bit_stream_count: u8,
+}
+
+impl Reader {
+    fn set_buffer(&mut self, data: &[u8]) {
+        self.clear_bit_stream();
+        self.data = data.to_vec();
+        self.position = 0;
+        self.last_position = data.len();
+    }
+
+    fn bit_stream_is_empty(&self) -> bool {
+        self.bit_stream_count == 0
+    }
+
+    fn clear_bit_stream(&mut self) {
+        self.bit_stream_count = 0;
+        self.bit_stream_cache = 0;
+    }
+
+    fn read_byte(&mut self) -> Result<u8, Box<dyn Error>> {
+        if self.position >= self.last_position {
+            return Err(format!(
+                "unexpected end of TSSC stream at position {} with last position {}",
+                self.position, self.last_position
+            )
+            .into());
+        }
+
+        let value = self.data[self.position];
+        self.position += 1;
+
+        Ok(value)
+    }
+
+    pub(crate) fn read_bit(&mut self) -> Result<u8, Box<dyn Error>> {
+        if self.bit_stream_count == 0 {
+            self.bit_stream_cache = self.read_byte()?;
+            self.bit_stream_count = 8;
+        }
+
+        self.bit_stream_count -= 1;
+
+        Ok((self.bit_stream_cache >> self.bit_stream_count) & 1)
+    }
+
+    pub(crate) fn read_bits4(&mut self) -> Result<u8, Box<dyn Error>> {
+        Ok(self.read_bit()? << 3
+            | self.read_bit()? << 2
+            | self.read_bit()? << 1
+            | self.read_bit()?)
+    }
+
+    pub(crate) fn read_bits5(&mut self) -> Result<u8, Box<dyn Error>> {
+        Ok(self.read_bit()? << 4 | self.read_bits4()?)
+    }
+
+    // Reads a 4-bit prefix from the bit stream followed by the specified number of whole bytes, with
+    // each byte shifted above the prior bits. This matches the layout of the XOR-encoded codes.
+    fn read_xor_bits(
+        &mut self,
+        with_bits4: bool,
+        byte_count: usize,
+    ) -> Result<u32, Box<dyn Error>> {
+        let mut value = 0u32;
+        let mut shift = 0;
+
+        if with_bits4 {
+            value = self.read_bits4()? as u32;
+            shift = 4;
+        }
+
+        for _ in 0..byte_count {
+            value |= (self.read_byte()? as u32) << shift;
+            shift += 8;
+        }
+
+        Ok(value)
+    }
+
+    // Reads a 7-bit encoded 32-bit unsigned integer.
+    fn read_7bit_u32(&mut self) -> Result<u32, Box<dyn Error>> {
+        let mut value = 0u32;
+
+        for i in 0..4 {
+            let byte = self.read_byte()?;
+            value |= ((byte & 0x7F) as u32) << (7 * i);
+
+            if byte < 0x80 {
+                return Ok(value);
+            }
+        }
+
+        Ok(value | (self.read_byte()? as u32) << 28)
+    }
+
+    // Reads a 7-bit encoded 64-bit unsigned integer, the ninth byte, when present, uses all 8 bits.
+    fn read_7bit_u64(&mut self) -> Result<u64, Box<dyn Error>> {
+        let mut value = 0u64;
+
+        for i in 0..8 {
+            let byte = self.read_byte()?;
+            value |= ((byte & 0x7F) as u64) << (7 * i);
+
+            if byte < 0x80 {
+                return Ok(value);
+            }
+        }
+
+        Ok(value | (self.read_byte()? as u64) << 56)
+    }
+}
+
+/// The decoder for the time-series special compression (TSSC) algorithm of STTP.
+///
+/// A `Decoder` is stateful: each compressed data packet must be decoded in the order it was encoded,
+/// starting from a freshly created `Decoder` when the publisher resets the algorithm.
+#[derive(Debug)]
+pub struct Decoder {
+    reader: Reader,
+
+    prev_timestamp1: i64,
+    prev_timestamp2: i64,
+
+    prev_time_delta1: i64,
+    prev_time_delta2: i64,
+    prev_time_delta3: i64,
+    prev_time_delta4: i64,
+
+    initial_point: PointMetadata,
+    last_point: Option<usize>,
+    points: Vec<Option<PointMetadata>>,
+
+    /// Defines the sequence number of the next expected TSSC data packet.
+    pub sequence_number: u16,
+}
+
+impl Decoder {
+    /// Creates a new TSSC `Decoder` sized for the specified maximum signal index.
+    pub fn new(max_signal_index: u32) -> Self {
+        let mut points = Vec::new();
+        points.resize_with(max_signal_index as usize + 1, || None);
+
+        Decoder {
+            reader: Reader::default(),
+            prev_timestamp1: 0,
+            prev_timestamp2: 0,
+            prev_time_delta1: i64::MAX,
+            prev_time_delta2: i64::MAX,
+            prev_time_delta3: i64::MAX,
+            prev_time_delta4: i64::MAX,
+            initial_point: PointMetadata::new(),
+            last_point: None,
+            points,
+            sequence_number: 0,
+        }
+    }
+
+    /// Assigns the working buffer to use for decoding measurements.
+    pub fn set_buffer(&mut self, data: &[u8]) {
+        self.reader.set_buffer(data);
+    }
+
+    /// Reads the next measurement from the working buffer as a tuple of signal index, timestamp,
+    /// state flags and value. Returns `None` once the end of the buffer has been reached.
+    pub fn try_get_measurement(&mut self) -> Result<Option<DecodedMeasurement>, Box<dyn Error>> {
+        if self.reader.position == self.reader.last_position && self.reader.bit_stream_is_empty() {
+            self.reader.clear_bit_stream();
+            return Ok(None);
+        }
+
+        // Since the incoming point ID is not known in advance, the last point's
+        // stream mode is used to decode the next code
+        let mut code = self.read_code()?;
+
+        if code == CodeWords::END_OF_STREAM {
+            self.reader.clear_bit_stream();
+            return Ok(None);
+        }
+
+        if code <= CodeWords::POINT_ID_XOR32 {
+            self.decode_point_id(code)?;
+            code = self.read_code()?;
+
+            if code < CodeWords::TIME_DELTA1_FORWARD {
+                return Err(self.unexpected_code_error(CodeWords::TIME_DELTA1_FORWARD));
+            }
+        }
+
+        let id = self.last_point_mut().prev_next_point_id1;
+        let index = self.point_index(id)?;
+
+        if self.points[index].is_none() {
+            let mut point = PointMetadata::new();
+            point.prev_next_point_id1 = id + 1;
+            self.points[index] = Some(point);
+        }
+
+        let timestamp = if code <= CodeWords::TIME_XOR_7BIT {
+            let timestamp = self.decode_timestamp(code)?;
+            code = self.read_code()?;
+
+            if code < CodeWords::STATE_FLAGS2 {
+                return Err(self.unexpected_code_error(CodeWords::STATE_FLAGS2));
+            }
+
+            timestamp
+        } else {
+            self.prev_timestamp1
+        };
+
+        let state_flags = if code <= CodeWords::STATE_FLAGS_7BIT32 {
+            let state_flags = self.decode_state_flags(code, index)?;
+            code = self.read_code()?;
+
+            if code < CodeWords::VALUE1 {
+                return Err(self.unexpected_code_error(CodeWords::VALUE1));
+            }
+
+            state_flags
+        } else {
+            self.points[index].as_ref().unwrap().prev_state_flags1
+        };
+
+        let value = self.decode_value(code, index)?;
+
+        self.last_point = Some(index);
+
+        Ok(Some((
+            id,
+            Ticks::new(timestamp as u64),
+            StateFlags::from_bits_retain(state_flags),
+            f32::from_bits(value),
+        )))
+    }
+
+    fn last_point_mut(&mut self) -> &mut PointMetadata {
+        match self.last_point {
+            Some(index) => self.points[index].as_mut().unwrap(),
+            None => &mut self.initial_point,
+        }
+    }
+
+    fn read_code(&mut self) -> Result<u8, Box<dyn Error>> {
+        let point = match self.last_point {
+            Some(index) => self.points[index].as_mut().unwrap(),
+            None => &mut self.initial_point,
+        };
+
+        point.read_code(&mut self.reader)
+    }
+
+    fn point_index(&mut self, id: i32) -> Result<usize, Box<dyn Error>> {
+        // Guard against unbounded allocations from a corrupted stream, point IDs are
+        // expected to stay within the range of signal indexes defined by the publisher
+        let max_index = (self.points.len() - 1).max(u16::MAX as usize);
+
+        if id < 0 || id as usize > max_index {
+            return Err(format!(
+                "decoded invalid point ID {} at position {} with last position {}",
+                id, self.reader.position, self.reader.last_position
+            )
+            .into());
+        }
+
+        let index = id as usize;
+
+        if index >= self.points.len() {
+            self.points.resize_with(index + 1, || None);
+        }
+
+        Ok(index)
+    }
+
+    fn unexpected_code_error(&self, expected_code: u8) -> Box<dyn Error> {
+        format!(
+            "expecting code >= {} at position {} with last position {}",
+            expected_code, self.reader.position, self.reader.last_position
+        )
+        .into()
+    }
+
+    fn invalid_code_error(&self, code: u8) -> Box<dyn Error> {
+        format!(
+            "invalid code received {} at position {} with last position {}",
+            code, self.reader.position, self.reader.last_position
+        )
+        .into()
+    }
+
+    fn decode_point_id(&mut self, code: u8) -> Result<(), Box<dyn Error>> {
+        let bits_changed = match code {
+            CodeWords::POINT_ID_XOR4 => self.reader.read_xor_bits(true, 0)?,
+            CodeWords::POINT_ID_XOR8 => self.reader.read_xor_bits(false, 1)?,
+            CodeWords::POINT_ID_XOR12 => self.reader.read_xor_bits(true, 1)?,
+            CodeWords::POINT_ID_XOR16 => self.reader.read_xor_bits(false, 2)?,
+            CodeWords::POINT_ID_XOR20 => self.reader.read_xor_bits(true, 2)?,
+            CodeWords::POINT_ID_XOR24 => self.reader.read_xor_bits(false, 3)?,
+            CodeWords::POINT_ID_XOR32 => self.reader.read_xor_bits(false, 4)?,
+            _ => return Err(self.invalid_code_error(code)),
+        };
+
+        self.last_point_mut().prev_next_point_id1 ^= bits_changed as i32;
+
+        Ok(())
+    }
+
+    fn decode_timestamp(&mut self, code: u8) -> Result<i64, Box<dyn Error>> {
+        let timestamp = match code {
+            CodeWords::TIME_DELTA1_FORWARD => {
+                self.prev_timestamp1.wrapping_add(self.prev_time_delta1)
+            }
+            CodeWords::TIME_DELTA2_FORWARD => {
+                self.prev_timestamp1.wrapping_add(self.prev_time_delta2)
+            }
+            CodeWords::TIME_DELTA3_FORWARD => {
+                self.prev_timestamp1.wrapping_add(self.prev_time_delta3)
+            }
+            CodeWords::TIME_DELTA4_FORWARD => {
+                self.prev_timestamp1.wrapping_add(self.prev_time_delta4)
+            }
+            CodeWords::TIME_DELTA1_REVERSE => {
+                self.prev_timestamp1.wrapping_sub(self.prev_time_delta1)
+            }
+            CodeWords::TIME_DELTA2_REVERSE => {
+                self.prev_timestamp1.wrapping_sub(self.prev_time_delta2)
+            }
+            CodeWords::TIME_DELTA3_REVERSE => {
+                self.prev_timestamp1.wrapping_sub(self.prev_time_delta3)
+            }
+            CodeWords::TIME_DELTA4_REVERSE => {
+                self.prev_timestamp1.wrapping_sub(self.prev_time_delta4)
+            }
+            CodeWords::TIMESTAMP2 => self.prev_timestamp2,
+            CodeWords::TIME_XOR_7BIT => self.prev_timestamp1 ^ self.reader.read_7bit_u64()? as i64,
+            _ => return Err(self.invalid_code_error(code)),
+        };
+
+        // Save the smallest delta time
+        let min_delta = self.prev_timestamp1.wrapping_sub(timestamp).wrapping_abs();
+
+        if min_delta < self.prev_time_delta4
+            && min_delta != self.prev_time_delta1
+            && min_delta != self.prev_time_delta2
+            && min_delta != self.prev_time_delta3
+        {
+            if min_delta < self.prev_time_delta1 {
+                self.prev_time_delta4 = self.prev_time_delta3;
+                self.prev_time_delta3 = self.prev_time_delta2;
+                self.prev_time_delta2 = self.prev_time_delta1;
+                self.prev_time_delta1 = min_delta;
+            } else if min_delta < self.prev_time_delta2 {
+                self.prev_time_delta4 = self.prev_time_delta3;
+                self.prev_time_delta3 = self.prev_time_delta2;
+                self.prev_time_delta2 = min_delta;
+            } else if min_delta < self.prev_time_delta3 {
+                self.prev_time_delta4 = self.prev_time_delta3;
+                self.prev_time_delta3 = min_delta;
+            } else {
+                self.prev_time_delta4 = min_delta;
+            }
+        }
+
+        self.prev_timestamp2 = self.prev_timestamp1;
+        self.prev_timestamp1 = timestamp;
+
+        Ok(timestamp)
+    }
+
+    fn decode_state_flags(&mut self, code: u8, index: usize) -> Result<u32, Box<dyn Error>> {
+        let next_point = self.points[index].as_mut().unwrap();
+
+        let state_flags = match code {
+            CodeWords::STATE_FLAGS2 => next_point.prev_state_flags2,
+            CodeWords::STATE_FLAGS_7BIT32 => self.reader.read_7bit_u32()?,
+            _ => return Err(self.invalid_code_error(code)),
+        };
+
+        next_point.prev_state_flags2 = next_point.prev_state_flags1;
+        next_point.prev_state_flags1 = state_flags;
+
+        Ok(state_flags)
+    }
+
+    fn decode_value(&mut self, code: u8, index: usize) -> Result<u32, Box<dyn Error>> {
+        let next_point = self.points[index].as_mut().unwrap();
+
+        let value = match code {
+            CodeWords::VALUE1 => return Ok(next_point.prev_value1),
+            CodeWords::VALUE2 => {
+                mem::swap(&mut next_point.prev_value1, &mut next_point.prev_value2);
+                return Ok(next_point.prev_value1);
+            }
+            CodeWords::VALUE3 => next_point.prev_value3,
+            CodeWords::VALUE_ZERO => 0,
+            CodeWords::VALUE_XOR4 => self.reader.read_xor_bits(true, 0)? ^ next_point.prev_value1,
+            CodeWords::VALUE_XOR8 => self.reader.read_xor_bits(false, 1)? ^ next_point.prev_value1,
+            CodeWords::VALUE_XOR12 => self.reader.read_xor_bits(true, 1)? ^ next_point.prev_value1,
+            CodeWords::VALUE_XOR16 => self.reader.read_xor_bits(false, 2)? ^ next_point.prev_value1,
+            CodeWords::VALUE_XOR20 => self.reader.read_xor_bits(true, 2)? ^ next_point.prev_value1,
+            CodeWords::VALUE_XOR24 => self.reader.read_xor_bits(false, 3)? ^ next_point.prev_value1,
+            CodeWords::VALUE_XOR28 => self.reader.read_xor_bits(true, 3)? ^ next_point.prev_value1,
+            CodeWords::VALUE_XOR32 => self.reader.read_xor_bits(false, 4)? ^ next_point.prev_value1,
+            _ => return Err(self.invalid_code_error(code)),
+        };
+
+        next_point.prev_value3 = next_point.prev_value2;
+        next_point.prev_value2 = next_point.prev_value1;
+        next_point.prev_value1 = value;
+
+        Ok(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Hand-encoded stream of two measurements, the first point sends all fields in full and the
+    // second point relies on the implied next point ID, the prior timestamp and prior state flags
+    const TEST_STREAM: [u8; 10] = [
+        0x11, // code: TIME_XOR_7BIT
+        0x01, // 7-bit encoded timestamp XOR
+        0x13, // code: STATE_FLAGS_7BIT32
+        0x00, // 7-bit encoded state flags
+        0x1F, // code: VALUE_XOR32
+        0x00, 0x00, 0x80, 0x3F, // value XOR, i.e., 1.0f32
+        0x17, // code: VALUE_ZERO
+    ];
+
+    #[test]
+    fn test_decoder_decodes_measurements() {
+        let mut decoder = Decoder::new(1);
+        decoder.set_buffer(&TEST_STREAM);
+
+        let (id, timestamp, flags, value) = decoder.try_get_measurement().unwrap().unwrap();
+
+        assert_eq!(id, 0);
+        assert_eq!(timestamp, Ticks::new(1));
+        assert_eq!(flags, StateFlags::NORMAL);
+        assert_eq!(value, 1.0);
+
+        let (id, timestamp, flags, value) = decoder.try_get_measurement().unwrap().unwrap();
+
+        assert_eq!(id, 1);
+        assert_eq!(timestamp, Ticks::new(1));
+        assert_eq!(flags, StateFlags::NORMAL);
+        assert_eq!(value, 0.0);
+
+        assert!(decoder.try_get_measurement().unwrap().is_none());
+    }
+
+    #[test]
+    fn test_decoder_rejects_truncated_stream() {
+        let mut decoder = Decoder::new(1);
+        decoder.set_buffer(&TEST_STREAM[..7]);
+
+        assert!(decoder.try_get_measurement().is_err());
+    }
+}
@@ -0,0 +1,563 @@
+//******************************************************************************************************
+//  encoder.rs - Gbtc
+//
+//  Copyright © 2023, Grid Protection Alliance.  All Rights Reserved.
+//
+//  Licensed to the Grid Protection Alliance (GPA) under one or more contributor license agreements. See
+//  the NOTICE file distributed with this work for additional information regarding copyright ownership.
+//  The GPA licenses this file to you under the MIT License (MIT), the "License"; you may not use this
+//  file except in compliance with the License. You may obtain a copy of the License at:
+//
+//      http://opensource.org/licenses/MIT
+//
+//  Unless agreed to in writing, the subject software distributed under the License is distributed on an
+//  "AS-IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied. Refer to the
+//  License for the specific language governing permissions and limitations.
+//
+//  Code Modification History:
+//  ----------------------------------------------------------------------------------------------------
+//  04/01/2023 - J. Ritchie Carroll
+//       Generated original version of source code.
+//
+//******************************************************************************************************
+
+use crate::transport::tssc::code_words::CodeWords;
+use crate::transport::tssc::point_metadata::PointMetadata;
+use crate::transport::StateFlags;
+use crate::Ticks;
+
+const BITS28: u32 = 0x0FFF_FFFF;
+const BITS24: u32 = 0x00FF_FFFF;
+const BITS20: u32 = 0x000F_FFFF;
+const BITS16: u32 = 0x0000_FFFF;
+const BITS12: u32 = 0x0000_0FFF;
+const BITS8: u32 = 0x0000_00FF;
+const BITS4: u32 = 0x0000_000F;
+
+// Minimum remaining space in a block, in bytes, required to add another measurement
+const MIN_BLOCK_SPACE: usize = 100;
+
+// Maintains the output buffer and bit stream state while writing a TSSC block. When bits are first
+// written, a byte is reserved at the current position for the bit stream, whole bytes continue to be
+// appended after it, and the reserved byte is filled once 8 bits have been written.
+#[derive(Debug, Default)]
+pub(crate) struct Writer {
+    data: Vec<u8>,
+    bit_stream_buffer_index: Option<usize>,
+    bit_stream_cache_bit_count: u32,
+    bit_stream_cache: u32,
+}
+
+impl Writer {
+    fn reserve_bit_stream_byte(&mut self) -> usize {
+        self.data.push(0);
+        self.data.len() - 1
+    }
+
+    fn write_byte(&mut self, value: u8) {
+        self.data.push(value);
+    }
+
+    pub(crate) fn write_bits(&mut self, code: u32, length: u32) {
+        if self.bit_stream_buffer_index.is_none() {
+            self.bit_stream_buffer_index = Some(self.reserve_bit_stream_byte());
+        }
+
+        self.bit_stream_cache = (self.bit_stream_cache << length) | code;
+        self.bit_stream_cache_bit_count += length;
+
+        if self.bit_stream_cache_bit_count > 7 {
+            self.end_write_bits();
+        }
+    }
+
+    fn end_write_bits(&mut self) {
+        while self.bit_stream_cache_bit_count > 7 {
+            let index = self.bit_stream_buffer_index.unwrap();
+
+            self.data[index] =
+                (self.bit_stream_cache >> (self.bit_stream_cache_bit_count - 8)) as u8;
+            self.bit_stream_cache_bit_count -= 8;
+
+            self.bit_stream_buffer_index = if self.bit_stream_cache_bit_count > 0 {
+                Some(self.reserve_bit_stream_byte())
+            } else {
+                None
+            };
+        }
+
+        // Only the unwritten bits need to be retained
+        self.bit_stream_cache &= (1 << self.bit_stream_cache_bit_count) - 1;
+    }
+
+    // Writes the low order 4 bits to the bit stream followed by the specified number of whole bytes,
+    // taken from the bits above the prior bits. This matches the layout of the XOR-encoded codes.
+    fn write_xor_bits(&mut self, value: u32, with_bits4: bool, byte_count: usize) {
+        let mut shift = 0;
+
+        if with_bits4 {
+            self.write_bits(value & 0xF, 4);
+            shift = 4;
+        }
+
+        for _ in 0..byte_count {
+            self.write_byte((value >> shift) as u8);
+            shift += 8;
+        }
+    }
+
+    // Writes a 7-bit encoded 32-bit unsigned integer.
+    fn write_7bit_u32(&mut self, mut value: u32) {
+        for _ in 0..4 {
+            if value < 0x80 {
+                self.write_byte(value as u8);
+                return;
+            }
+
+            self.write_byte(value as u8 | 0x80);
+            value >>= 7;
+        }
+
+        self.write_byte(value as u8);
+    }
+
+    // Writes a 7-bit encoded 64-bit unsigned integer, the ninth byte, when needed, uses all 8 bits.
+    fn write_7bit_u64(&mut self, mut value: u64) {
+        for _ in 0..8 {
+            if value < 0x80 {
+                self.write_byte(value as u8);
+                return;
+            }
+
+            self.write_byte(value as u8 | 0x80);
+            value >>= 7;
+        }
+
+        self.write_byte(value as u8);
+    }
+}
+
+/// The encoder for the time-series special compression (TSSC) algorithm of STTP.
+///
+/// Measurements are added to a block with `try_add_measurement` and the compressed block is completed
+/// with `finish_block`. An `Encoder` is stateful: blocks must be decoded in the order they were
+/// encoded by a `Decoder` that was created when this `Encoder` was created.
+#[derive(Debug)]
+pub struct Encoder {
+    writer: Writer,
+    block_size: usize,
+
+    prev_timestamp1: i64,
+    prev_timestamp2: i64,
+
+    prev_time_delta1: i64,
+    prev_time_delta2: i64,
+    prev_time_delta3: i64,
+    prev_time_delta4: i64,
+
+    initial_point: PointMetadata,
+    last_point: Option<usize>,
+    points: Vec<Option<PointMetadata>>,
+
+    /// Defines the sequence number of the next TSSC data packet.
+    pub sequence_number: u16,
+}
+
+impl Encoder {
+    /// Creates a new TSSC `Encoder`.
+    pub fn new() -> Self {
+        Encoder {
+            writer: Writer::default(),
+            block_size: 0,
+            prev_timestamp1: 0,
+            prev_timestamp2: 0,
+            prev_time_delta1: i64::MAX,
+            prev_time_delta2: i64::MAX,
+            prev_time_delta3: i64::MAX,
+            prev_time_delta4: i64::MAX,
+            initial_point: PointMetadata::new(),
+            last_point: None,
+            points: Vec::new(),
+            sequence_number: 0,
+        }
+    }
+
+    /// Starts a new block that will hold up to `block_size` bytes of encoded measurements.
+    pub fn set_buffer(&mut self, block_size: usize) {
+        self.writer = Writer {
+            data: Vec::with_capacity(block_size),
+            ..Writer::default()
+        };
+
+        self.block_size = block_size;
+    }
+
+    /// Completes the current block and returns its encoded bytes.
+    pub fn finish_block(&mut self) -> Vec<u8> {
+        self.bit_stream_flush();
+        std::mem::take(&mut self.writer.data)
+    }
+
+    /// Adds a measurement to the current block. Returns `false` when the block does not have enough
+    /// remaining space for the measurement, in which case the block should be finished and sent.
+    pub fn try_add_measurement(
+        &mut self,
+        signal_index: i32,
+        timestamp: Ticks,
+        flags: StateFlags,
+        value: f32,
+    ) -> bool {
+        // If there are fewer than 100 bytes available in the block, assume no more can be added
+        if self.writer.data.len() + MIN_BLOCK_SPACE > self.block_size {
+            return false;
+        }
+
+        let signal_index = signal_index.max(0);
+        let index = signal_index as usize;
+
+        if index >= self.points.len() {
+            self.points.resize_with(index + 1, || None);
+        }
+
+        if self.points[index].is_none() {
+            let mut point = PointMetadata::new();
+            point.prev_next_point_id1 = signal_index + 1;
+            self.points[index] = Some(point);
+        }
+
+        // Since the decoder will not know the incoming point ID, the most recent
+        // measurement received will be the one that contains the coding algorithm
+        // for this measurement. Since measurements generally have some sort of
+        // sequence to them, this still ends up being a good enough assumption.
+        if self.last_point_mut().prev_next_point_id1 != signal_index {
+            self.write_point_id_change(signal_index);
+        }
+
+        let timestamp = timestamp.value() as i64;
+
+        if self.prev_timestamp1 != timestamp {
+            self.write_timestamp_change(timestamp);
+        }
+
+        let state_flags = flags.bits();
+
+        if self.points[index].as_ref().unwrap().prev_state_flags1 != state_flags {
+            self.write_state_flags_change(state_flags, index);
+        }
+
+        self.write_value(value.to_bits(), index);
+
+        self.last_point = Some(index);
+
+        true
+    }
+
+    fn last_point_mut(&mut self) -> &mut PointMetadata {
+        match self.last_point {
+            Some(index) => self.points[index].as_mut().unwrap(),
+            None => &mut self.initial_point,
+        }
+    }
+
+    fn write_code(&mut self, code: u8) {
+        let point = match self.last_point {
+            Some(index) => self.points[index].as_mut().unwrap(),
+            None => &mut self.initial_point,
+        };
+
+        point.write_code(code, &mut self.writer);
+    }
+
+    fn bit_stream_flush(&mut self) {
+        if self.writer.bit_stream_cache_bit_count == 0 {
+            return;
+        }
+
+        if self.writer.bit_stream_buffer_index.is_none() {
+            self.writer.bit_stream_buffer_index = Some(self.writer.reserve_bit_stream_byte());
+        }
+
+        self.write_code(CodeWords::END_OF_STREAM);
+
+        if self.writer.bit_stream_cache_bit_count > 7 {
+            self.writer.end_write_bits();
+        }
+
+        if self.writer.bit_stream_cache_bit_count > 0 {
+            // Make up 8 bits by padding
+            let writer = &mut self.writer;
+            let index = writer.bit_stream_buffer_index.unwrap();
+
+            writer.data[index] =
+                (writer.bit_stream_cache << (8 - writer.bit_stream_cache_bit_count)) as u8;
+            writer.bit_stream_cache = 0;
+            writer.bit_stream_buffer_index = None;
+            writer.bit_stream_cache_bit_count = 0;
+        }
+    }
+
+    fn write_point_id_change(&mut self, signal_index: i32) {
+        let bits_changed = (signal_index ^ self.last_point_mut().prev_next_point_id1) as u32;
+
+        let (code, with_bits4, byte_count) = if bits_changed <= BITS4 {
+            (CodeWords::POINT_ID_XOR4, true, 0)
+        } else if bits_changed <= BITS8 {
+            (CodeWords::POINT_ID_XOR8, false, 1)
+        } else if bits_changed <= BITS12 {
+            (CodeWords::POINT_ID_XOR12, true, 1)
+        } else if bits_changed <= BITS16 {
+            (CodeWords::POINT_ID_XOR16, false, 2)
+        } else if bits_changed <= BITS20 {
+            (CodeWords::POINT_ID_XOR20, true, 2)
+        } else if bits_changed <= BITS24 {
+            (CodeWords::POINT_ID_XOR24, false, 3)
+        } else {
+            (CodeWords::POINT_ID_XOR32, false, 4)
+        };
+
+        self.write_code(code);
+        self.writer
+            .write_xor_bits(bits_changed, with_bits4, byte_count);
+
+        self.last_point_mut().prev_next_point_id1 = signal_index;
+    }
+
+    fn write_timestamp_change(&mut self, timestamp: i64) {
+        if self.prev_timestamp2 == timestamp {
+            self.write_code(CodeWords::TIMESTAMP2);
+        } else if self.prev_timestamp1 < timestamp {
+            if self.prev_timestamp1.wrapping_add(self.prev_time_delta1) == timestamp {
+                self.write_code(CodeWords::TIME_DELTA1_FORWARD);
+            } else if self.prev_timestamp1.wrapping_add(self.prev_time_delta2) == timestamp {
+                self.write_code(CodeWords::TIME_DELTA2_FORWARD);
+            } else if self.prev_timestamp1.wrapping_add(self.prev_time_delta3) == timestamp {
+                self.write_code(CodeWords::TIME_DELTA3_FORWARD);
+            } else if self.prev_timestamp1.wrapping_add(self.prev_time_delta4) == timestamp {
+                self.write_code(CodeWords::TIME_DELTA4_FORWARD);
+            } else {
+                self.write_code(CodeWords::TIME_XOR_7BIT);
+                self.writer
+                    .write_7bit_u64((timestamp ^ self.prev_timestamp1) as u64);
+            }
+        } else if self.prev_timestamp1.wrapping_sub(self.prev_time_delta1) == timestamp {
+            self.write_code(CodeWords::TIME_DELTA1_REVERSE);
+        } else if self.prev_timestamp1.wrapping_sub(self.prev_time_delta2) == timestamp {
+            self.write_code(CodeWords::TIME_DELTA2_REVERSE);
+        } else if self.prev_timestamp1.wrapping_sub(self.prev_time_delta3) == timestamp {
+            self.write_code(CodeWords::TIME_DELTA3_REVERSE);
+        } else if self.prev_timestamp1.wrapping_sub(self.prev_time_delta4) == timestamp {
+            self.write_code(CodeWords::TIME_DELTA4_REVERSE);
+        } else {
+            self.write_code(CodeWords::TIME_XOR_7BIT);
+            self.writer
+                .write_7bit_u64((timestamp ^ self.prev_timestamp1) as u64);
+        }
+
+        // Save the smallest delta time
+        let min_delta = self.prev_timestamp1.wrapping_sub(timestamp).wrapping_abs();
+
+        if min_delta < self.prev_time_delta4
+            && min_delta != self.prev_time_delta1
+            && min_delta != self.prev_time_delta2
+            && min_delta != self.prev_time_delta3
+        {
+            if min_delta < self.prev_time_delta1 {
+                self.prev_time_delta4 = self.prev_time_delta3;
+                self.prev_time_delta3 = self.prev_time_delta2;
+                self.prev_time_delta2 = self.prev_time_delta1;
+                self.prev_time_delta1 = min_delta;
+            } else if min_delta < self.prev_time_delta2 {
+                self.prev_time_delta4 = self.prev_time_delta3;
+                self.prev_time_delta3 = self.prev_time_delta2;
+                self.prev_time_delta2 = min_delta;
+            } else if min_delta < self.prev_time_delta3 {
+                self.prev_time_delta4 = self.prev_time_delta3;
+                self.prev_time_delta3 = min_delta;
+            } else {
+                self.prev_time_delta4 = min_delta;
+            }
+        }
+
+        self.prev_timestamp2 = self.prev_timestamp1;
+        self.prev_timestamp1 = timestamp;
+    }
+
+    fn write_state_flags_change(&mut self, state_flags: u32, index: usize) {
+        if self.points[index].as_ref().unwrap().prev_state_flags2 == state_flags {
+            self.write_code(CodeWords::STATE_FLAGS2);
+        } else {
+            self.write_code(CodeWords::STATE_FLAGS_7BIT32);
+            self.writer.write_7bit_u32(state_flags);
+        }
+
+        let point = self.points[index].as_mut().unwrap();
+        point.prev_state_flags2 = point.prev_state_flags1;
+        point.prev_state_flags1 = state_flags;
+    }
+
+    fn write_value(&mut self, value: u32, index: usize) {
+        let point = self.points[index].as_ref().unwrap();
+
+        if point.prev_value1 == value {
+            self.write_code(CodeWords::VALUE1);
+            return;
+        }
+
+        if point.prev_value2 == value {
+            self.write_code(CodeWords::VALUE2);
+
+            let point = self.points[index].as_mut().unwrap();
+            point.prev_value2 = point.prev_value1;
+            point.prev_value1 = value;
+            return;
+        }
+
+        if point.prev_value3 == value {
+            self.write_code(CodeWords::VALUE3);
+        } else if value == 0 {
+            self.write_code(CodeWords::VALUE_ZERO);
+        } else {
+            let bits_changed = value ^ point.prev_value1;
+
+            let (code, with_bits4, byte_count) = if bits_changed <= BITS4 {
+                (CodeWords::VALUE_XOR4, true, 0)
+            } else if bits_changed <= BITS8 {
+                (CodeWords::VALUE_XOR8, false, 1)
+            } else if bits_changed <= BITS12 {
+                (CodeWords::VALUE_XOR12, true, 1)
+            } else if bits_changed <= BITS16 {
+                (CodeWords::VALUE_XOR16, false, 2)
+            } else if bits_changed <= BITS20 {
+                (CodeWords::VALUE_XOR20, true, 2)
+            } else if bits_changed <= BITS24 {
+                (CodeWords::VALUE_XOR24, false, 3)
+            } else if bits_changed <= BITS28 {
+                (CodeWords::VALUE_XOR28, true, 3)
+            } else {
+                (CodeWords::VALUE_XOR32, false, 4)
+            };
+
+            self.write_code(code);
+            self.writer
+                .write_xor_bits(bits_changed, with_bits4, byte_count);
+        }
+
+        let point = self.points[index].as_mut().unwrap();
+        point.prev_value3 = point.prev_value2;
+        point.prev_value2 = point.prev_value1;
+        point.prev_value1 = value;
+    }
+}
+
+impl Default for Encoder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transport::tssc::Decoder;
+    use proptest::prelude::*;
+
+    const MAX_TEST_SIGNAL_INDEX: i32 = 70_000;
+
+    type TestMeasurement = (i32, u64, u32, f32);
+
+    fn round_trip(blocks: &[Vec<TestMeasurement>]) -> Vec<Vec<TestMeasurement>> {
+        let mut encoder = Encoder::new();
+        let mut decoder = Decoder::new(MAX_TEST_SIGNAL_INDEX as u32);
+        let mut decoded_blocks = Vec::new();
+
+        for block in blocks {
+            encoder.set_buffer(block.len() * 32 + MIN_BLOCK_SPACE);
+
+            for &(signal_index, timestamp, flags, value) in block {
+                assert!(encoder.try_add_measurement(
+                    signal_index,
+                    Ticks::new(timestamp),
+                    StateFlags::from_bits_retain(flags),
+                    value
+                ));
+            }
+
+            decoder.set_buffer(&encoder.finish_block());
+
+            let mut decoded = Vec::new();
+
+            while let Some((signal_index, timestamp, flags, value)) =
+                decoder.try_get_measurement().unwrap()
+            {
+                decoded.push((signal_index, timestamp.value(), flags.bits(), value));
+            }
+
+            decoded_blocks.push(decoded);
+        }
+
+        decoded_blocks
+    }
+
+    fn to_bits(blocks: &[Vec<TestMeasurement>]) -> Vec<Vec<(i32, u64, u32, u32)>> {
+        blocks
+            .iter()
+            .map(|block| {
+                block
+                    .iter()
+                    .map(|&(id, timestamp, flags, value)| (id, timestamp, flags, value.to_bits()))
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_encoder_matches_reference_stream() {
+        let mut encoder = Encoder::new();
+        encoder.set_buffer(1024);
+
+        assert!(encoder.try_add_measurement(0, Ticks::new(1), StateFlags::NORMAL, 1.0));
+        assert!(encoder.try_add_measurement(1, Ticks::new(1), StateFlags::NORMAL, 0.0));
+
+        assert_eq!(
+            encoder.finish_block(),
+            [0x11, 0x01, 0x1F, 0x00, 0x00, 0x80, 0x3F, 0x80, 0x00]
+        );
+    }
+
+    #[test]
+    fn test_encoder_rejects_full_block() {
+        let mut encoder = Encoder::new();
+        encoder.set_buffer(MIN_BLOCK_SPACE - 1);
+
+        assert!(!encoder.try_add_measurement(0, Ticks::new(1), StateFlags::NORMAL, 1.0));
+    }
+
+    fn measurement_strategy() -> impl Strategy<Value = TestMeasurement> {
+        // Mix of arbitrary values and small, repetitive ranges that exercise
+        // the delta, history and adaptive code paths of the algorithm
+        (
+            prop_oneof![0..8i32, 0..=MAX_TEST_SIGNAL_INDEX],
+            prop_oneof![
+                (0..16u64).prop_map(|offset| 638_000_000_000_000_000 + offset * 333_333),
+                any::<u64>(),
+            ],
+            prop_oneof![Just(0u32), Just(4u32), any::<u32>()],
+            prop_oneof![
+                Just(0.0f32),
+                (0..4u8).prop_map(|value| 59.95 + value as f32 * 0.01),
+                any::<u32>().prop_map(f32::from_bits),
+            ],
+        )
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(64))]
+
+        #[test]
+        fn test_encoder_round_trips_through_decoder(
+            blocks in prop::collection::vec(prop::collection::vec(measurement_strategy(), 0..200), 1..5)
+        ) {
+            prop_assert_eq!(to_bits(&round_trip(&blocks)), to_bits(&blocks));
+        }
+    }
+}
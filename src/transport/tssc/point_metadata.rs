@@ -0,0 +1,227 @@
+//******************************************************************************************************
+//  point_metadata.rs - Gbtc
+//
+//  Copyright © 2023, Grid Protection Alliance.  All Rights Reserved.
+//
+//  Licensed to the Grid Protection Alliance (GPA) under one or more contributor license agreements. See
+//  the NOTICE file distributed with this work for additional information regarding copyright ownership.
+//  The GPA licenses this file to you under the MIT License (MIT), the "License"; you may not use this
+//  file except in compliance with the License. You may obtain a copy of the License at:
+//
+//      http://opensource.org/licenses/MIT
+//
+//  Unless agreed to in writing, the subject software distributed under the License is distributed on an
+//  "AS-IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied. Refer to the
+//  License for the specific language governing permissions and limitations.
+//
+//  Code Modification History:
+//  ----------------------------------------------------------------------------------------------------
+//  04/01/2023 - J. Ritchie Carroll
+//       Generated original version of source code.
+//
+//******************************************************************************************************
+
+use crate::transport::tssc::code_words::CodeWords;
+use crate::transport::tssc::decoder::Reader;
+use crate::transport::tssc::encoder::Writer;
+use std::error::Error;
+
+// Represents the per-point state of the TSSC algorithm. Each point tracks its recent history along
+// with statistics on the code words sent after it, so that the most frequent codes can be adaptively
+// assigned the shortest bit sequences.
+#[derive(Debug, Clone)]
+pub(crate) struct PointMetadata {
+    pub(crate) prev_next_point_id1: i32,
+    pub(crate) prev_state_flags1: u32,
+    pub(crate) prev_state_flags2: u32,
+    pub(crate) prev_value1: u32,
+    pub(crate) prev_value2: u32,
+    pub(crate) prev_value3: u32,
+
+    command_stats: [u8; 32],
+    commands_sent_since_last_change: i32,
+
+    // Bit codes for the 4 modes of encoding
+    mode: u8,
+
+    // Mode 1 means no prefix
+    mode21: u8,
+    mode31: u8,
+    mode301: u8,
+    mode41: u8,
+    mode401: u8,
+    mode4001: u8,
+
+    startup_mode: i32,
+}
+
+impl PointMetadata {
+    pub(crate) fn new() -> Self {
+        PointMetadata {
+            prev_next_point_id1: 0,
+            prev_state_flags1: 0,
+            prev_state_flags2: 0,
+            prev_value1: 0,
+            prev_value2: 0,
+            prev_value3: 0,
+            command_stats: [0; 32],
+            commands_sent_since_last_change: 0,
+            mode: 4,
+            mode21: 0,
+            mode31: 0,
+            mode301: 0,
+            mode41: CodeWords::VALUE1,
+            mode401: CodeWords::VALUE2,
+            mode4001: CodeWords::VALUE3,
+            startup_mode: 0,
+        }
+    }
+
+    pub(crate) fn write_code(&mut self, code: u8, writer: &mut Writer) {
+        // Codes are at most 5 bits, so longer writes are the zero bit prefixes followed by the code
+        match self.mode {
+            1 => writer.write_bits(code as u32, 5),
+            2 => {
+                if code == self.mode21 {
+                    writer.write_bits(1, 1);
+                } else {
+                    writer.write_bits(code as u32, 6);
+                }
+            }
+            3 => {
+                if code == self.mode31 {
+                    writer.write_bits(1, 1);
+                } else if code == self.mode301 {
+                    writer.write_bits(1, 2);
+                } else {
+                    writer.write_bits(code as u32, 7);
+                }
+            }
+            _ => {
+                if code == self.mode41 {
+                    writer.write_bits(1, 1);
+                } else if code == self.mode401 {
+                    writer.write_bits(1, 2);
+                } else if code == self.mode4001 {
+                    writer.write_bits(1, 3);
+                } else {
+                    writer.write_bits(code as u32, 8);
+                }
+            }
+        }
+
+        self.update_code_statistics(code);
+    }
+
+    pub(crate) fn read_code(&mut self, reader: &mut Reader) -> Result<u8, Box<dyn Error>> {
+        let code = match self.mode {
+            1 => reader.read_bits5()?,
+            2 => {
+                if reader.read_bit()? == 1 {
+                    self.mode21
+                } else {
+                    reader.read_bits5()?
+                }
+            }
+            3 => {
+                if reader.read_bit()? == 1 {
+                    self.mode31
+                } else if reader.read_bit()? == 1 {
+                    self.mode301
+                } else {
+                    reader.read_bits5()?
+                }
+            }
+            4 => {
+                if reader.read_bit()? == 1 {
+                    self.mode41
+                } else if reader.read_bit()? == 1 {
+                    self.mode401
+                } else if reader.read_bit()? == 1 {
+                    self.mode4001
+                } else {
+                    reader.read_bits5()?
+                }
+            }
+            _ => return Err("unsupported TSSC encoding mode".into()),
+        };
+
+        self.update_code_statistics(code);
+
+        Ok(code)
+    }
+
+    fn update_code_statistics(&mut self, code: u8) {
+        self.commands_sent_since_last_change += 1;
+        self.command_stats[code as usize] = self.command_stats[code as usize].wrapping_add(1);
+
+        if (self.startup_mode == 0 && self.commands_sent_since_last_change > 5)
+            || (self.startup_mode == 1 && self.commands_sent_since_last_change > 20)
+        {
+            self.startup_mode += 1;
+            self.adapt_commands();
+        } else if self.startup_mode == 2 && self.commands_sent_since_last_change > 100 {
+            self.adapt_commands();
+        }
+    }
+
+    fn adapt_commands(&mut self) {
+        let mut code1: u8 = 0;
+        let mut count1: i32 = 0;
+        let mut code2: u8 = 1;
+        let mut count2: i32 = 0;
+        let mut code3: u8 = 2;
+        let mut count3: i32 = 0;
+        let mut total: i32 = 0;
+
+        for (i, stat) in self.command_stats.iter_mut().enumerate() {
+            let count = *stat as i32;
+            *stat = 0;
+            total += count;
+
+            if count > count3 {
+                if count > count1 {
+                    code3 = code2;
+                    count3 = count2;
+                    code2 = code1;
+                    count2 = count1;
+                    code1 = i as u8;
+                    count1 = count;
+                } else if count > count2 {
+                    code3 = code2;
+                    count3 = count2;
+                    code2 = i as u8;
+                    count2 = count;
+                } else {
+                    code3 = i as u8;
+                    count3 = count;
+                }
+            }
+        }
+
+        let mode1_size = total * 5;
+        let mode2_size = count1 + (total - count1) * 6;
+        let mode3_size = count1 + count2 * 2 + (total - count1 - count2) * 7;
+        let mode4_size = count1 + count2 * 2 + count3 * 3 + (total - count1 - count2 - count3) * 8;
+
+        let min_size = mode1_size.min(mode2_size).min(mode3_size).min(mode4_size);
+
+        if min_size == mode1_size {
+            self.mode = 1;
+        } else if min_size == mode2_size {
+            self.mode = 2;
+            self.mode21 = code1;
+        } else if min_size == mode3_size {
+            self.mode = 3;
+            self.mode31 = code1;
+            self.mode301 = code2;
+        } else {
+            self.mode = 4;
+            self.mode41 = code1;
+            self.mode401 = code2;
+            self.mode4001 = code3;
+        }
+
+        self.commands_sent_since_last_change = 0;
+    }
+}